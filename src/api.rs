@@ -27,10 +27,25 @@
 //! `.next().await`. Off by default and compiles away entirely (no
 //! `futures-core` dependency pulled in) when disabled. The blocking
 //! [`sync::Paginator`] already implements [`Iterator`] unconditionally.
+//!
+//! ## Time-range walking
+//!
+//! [`Client::walk_time_range`] / [`sync::Client::walk_time_range`] walk any
+//! [`TimeSeries`](crate::api::TimeSeries) endpoint (index price, funding-rate
+//! history, aggregated OI history, candles, …) across a long `from`/`to` range
+//! in fixed-size windows, de-duplicating items repeated on a window boundary
+//! by timestamp. The walker reports the last timestamp it yielded, and a
+//! [`TimeRange`](crate::api::TimeRange) can
+//! [`resume_after`](crate::api::TimeRange::resume_after) it to pick up an
+//! interrupted walk. [`Client::walk_index_price`],
+//! [`Client::walk_funding_rate_history`] and
+//! [`Client::walk_open_interest_history`] take the endpoint's options
+//! instead of a raw path and query, so `from`/`to` are read in the
+//! endpoint's own unit.
 
 use reqwest::StatusCode;
 use serde::de::DeserializeOwned;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
 use std::marker::PhantomData;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
@@ -331,8 +346,13 @@ impl BuilderState {
 /// whether `send`, the backoff sleep, and `handle_response` are awaited: pass
 /// `await` as the trailing argument for the async flavor, and omit it for the
 /// blocking flavor.
+///
+/// Every helper that exists in both flavors follows this pattern: its body is
+/// written once as a macro in its own module, taking the same trailing
+/// `await`, so the two copies can't drift apart.
 macro_rules! get_loop {
     ($self:expr, $endpoint:expr, $parameters:expr, $handle_response:path, $sleep:path $(, $aw:ident)?) => {{
+        crate::validate::check_parameters($endpoint, $parameters.as_ref())?;
         let url: String = format!("{}{}", $self.inner.base_url, $endpoint);
         let mut attempt: u32 = 0;
 
@@ -448,6 +468,111 @@ impl std::fmt::Debug for Client {
     }
 }
 
+/// The [`TimeRange`] spanned by an options struct's `from`/`to`, given in
+/// the unit of `T`'s endpoint.
+fn options_range<T: TimeSeries>(
+    from: Option<i64>,
+    to: Option<i64>,
+    window: Duration,
+) -> Result<TimeRange> {
+    let bound = |argument: &str, value: Option<i64>| {
+        value
+            .map(|value| T::QUERY_UNIT.to_millis(value))
+            .ok_or_else(|| Error::InvalidArgument {
+                argument: argument.to_string(),
+                reason: "must be set to walk a range, e.g. with `since`/`until` or `last`"
+                    .to_string(),
+            })
+    };
+    Ok(TimeRange::new(
+        bound("from", from)?,
+        bound("to", to)?,
+        window,
+    ))
+}
+
+/// Generates the typed `walk_*` methods shared by [`Client`] and
+/// [`sync::Client`]. Each takes the endpoint's options struct, whose range
+/// is set with the `since`/`until`/`last` builders of [`crate::datetime`],
+/// so the unit of `from`/`to` is the endpoint's own.
+macro_rules! typed_walks {
+    () => {
+        /// Walks [`IndexPrice::get`](crate::IndexPrice::get) for `asset`
+        /// over the `from`/`to` of `options`, one `window` at a time.
+        /// Fails with [`Error::InvalidArgument`] when either bound is unset.
+        pub fn walk_index_price(
+            &self,
+            asset: impl Into<String>,
+            options: crate::generated::IndexPriceOptions,
+            window: Duration,
+        ) -> Result<TimeRangeWalker<crate::generated::IndexPriceResponse>> {
+            let range = crate::api::options_range::<crate::generated::IndexPriceResponse>(
+                options.from,
+                options.to,
+                window,
+            )?;
+            let mut params = BTreeMap::from([("asset".to_string(), asset.into())]);
+            if let Some(interval) = options.interval {
+                params.insert("interval".to_string(), interval.to_string());
+            }
+            Ok(self.walk_time_range("/api/v1/index-price", params, range))
+        }
+
+        /// Walks [`FundingRate::history`](crate::FundingRate::history) for
+        /// `exchange` and `symbol` over the `from`/`to` of `options`, one
+        /// `window` at a time. Fails with [`Error::InvalidArgument`] when
+        /// either bound is unset.
+        pub fn walk_funding_rate_history(
+            &self,
+            exchange: impl Into<String>,
+            symbol: impl Into<String>,
+            options: crate::generated::FundingRateHistoryOptions,
+            window: Duration,
+        ) -> Result<TimeRangeWalker<crate::generated::FundingRateHistoryResponse>> {
+            let range = crate::api::options_range::<crate::generated::FundingRateHistoryResponse>(
+                options.from,
+                options.to,
+                window,
+            )?;
+            let mut params = BTreeMap::from([
+                ("exchange".to_string(), exchange.into()),
+                ("symbol".to_string(), symbol.into()),
+            ]);
+            if let Some(page) = options.page {
+                params.insert("page".to_string(), page.to_string());
+            }
+            if let Some(limit) = options.limit {
+                params.insert("limit".to_string(), limit.to_string());
+            }
+            if let Some(sort) = options.sort {
+                params.insert("sort".to_string(), sort.to_string());
+            }
+            Ok(self.walk_time_range("/api/v1/funding-rate/history", params, range))
+        }
+
+        /// Walks
+        /// [`OpenInterest::history_aggregated`](crate::OpenInterest::history_aggregated)
+        /// for `token_id` over the `from`/`to` of `options`, one `window` at
+        /// a time. Fails with [`Error::InvalidArgument`] when either bound
+        /// is unset.
+        pub fn walk_open_interest_history(
+            &self,
+            token_id: impl Into<String>,
+            options: crate::generated::OpenInterestHistoryAggregatedOptions,
+            window: Duration,
+        ) -> Result<TimeRangeWalker<crate::generated::OpenInterestHistoryAggregatedResponse>> {
+            let range = crate::api::options_range::<
+                crate::generated::OpenInterestHistoryAggregatedResponse,
+            >(options.from, options.to, window)?;
+            let mut params = BTreeMap::from([("token_id".to_string(), token_id.into())]);
+            if let Some(interval) = options.interval {
+                params.insert("interval".to_string(), interval.to_string());
+            }
+            Ok(self.walk_time_range("/api/v1/open-interest/history-aggregated", params, range))
+        }
+    };
+}
+
 impl Client {
     /// Creates a new client authenticating with the given API key.
     ///
@@ -473,6 +598,10 @@ impl Client {
     /// `429` honors its `Retry-After` header. Fatal statuses
     /// (`400`/`401`/`403`/`404`) are returned without retry.
     ///
+    /// Before anything is sent, `parameters` are checked against the
+    /// constraints declared for `endpoint` in [`crate::validate`]; a
+    /// violation is returned as [`Error::InvalidArgument`].
+    ///
     /// With the `tracing` feature enabled, each call is wrapped in a span
     /// carrying `method`, `endpoint`, `attempt`, and the resolved `status`;
     /// retries additionally emit a debug event with the backoff delay. The
@@ -516,6 +645,26 @@ impl Client {
     {
         Paginator::new(self.clone(), endpoint, params)
     }
+
+    /// Returns a walker over a time-bounded endpoint (see [`TimeSeries`]),
+    /// requesting `range` one window at a time.
+    ///
+    /// `params` seeds the query string for every window (e.g. `asset`,
+    /// `interval`); its `from`/`to` keys are overwritten per window. Call
+    /// [`TimeRangeWalker::next_window`] in a loop to walk forward.
+    pub fn walk_time_range<T>(
+        &self,
+        endpoint: impl Into<String>,
+        params: BTreeMap<String, String>,
+        range: TimeRange,
+    ) -> TimeRangeWalker<T>
+    where
+        T: TimeSeries + DeserializeOwned,
+    {
+        TimeRangeWalker::new(self.clone(), endpoint, params, range)
+    }
+
+    typed_walks!();
 }
 
 /// Implemented by paged response envelopes — the `page`/`limit`/`data` shape
@@ -838,6 +987,336 @@ fn consume_page<T: Paginated>(
     Some(items)
 }
 
+/// The unit a time-bounded endpoint expects for its `from`/`to` query
+/// parameters. The walker itself always works in UTC milliseconds and only
+/// converts when building the query string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeUnit {
+    /// Unix seconds (e.g. `/api/v1/index-price`, `/api/v1/funding-rate/history`).
+    Seconds,
+    /// Unix milliseconds (e.g. `/api/v1/open-interest/history-aggregated`).
+    Milliseconds,
+}
+
+impl TimeUnit {
+    /// Converts a `from`/`to` value in this unit to milliseconds.
+    fn to_millis(self, value: i64) -> i64 {
+        match self {
+            TimeUnit::Seconds => value.saturating_mul(1000),
+            TimeUnit::Milliseconds => value,
+        }
+    }
+
+    /// Converts a lower bound in milliseconds to this unit, rounding down so
+    /// the window's start is never skipped.
+    fn floor_from_millis(self, millis: i64) -> i64 {
+        match self {
+            TimeUnit::Seconds => millis.div_euclid(1000),
+            TimeUnit::Milliseconds => millis,
+        }
+    }
+
+    /// Converts an upper bound in milliseconds to this unit, rounding up so
+    /// the window's end is never cut short.
+    fn ceil_from_millis(self, millis: i64) -> i64 {
+        match self {
+            TimeUnit::Seconds => millis.div_euclid(1000) + i64::from(millis.rem_euclid(1000) != 0),
+            TimeUnit::Milliseconds => millis,
+        }
+    }
+}
+
+/// Implemented by time-bounded response envelopes — endpoints taking
+/// `from`/`to` query parameters and returning timestamped items (e.g.
+/// `IndexPriceResponse`, `FundingRateHistoryResponse`) — so
+/// [`Client::walk_time_range`] / [`sync::Client::walk_time_range`] can drive a
+/// generic time-window walker over them, the same way [`Paginated`] backs the
+/// auto-paginator.
+pub trait TimeSeries {
+    /// The item type yielded per window.
+    type Item;
+
+    /// The unit the endpoint expects for its `from`/`to` query parameters.
+    const QUERY_UNIT: TimeUnit;
+
+    /// Consumes the response, yielding this window's items.
+    fn into_items(self) -> Vec<Self::Item>;
+
+    /// The item's timestamp in UTC milliseconds, used for de-duplication and
+    /// as the walker's resume point.
+    fn timestamp(item: &Self::Item) -> i64;
+
+    /// For envelopes carrying several parallel series (e.g. one per
+    /// exchange), the series an item belongs to, so items from different
+    /// series sharing a timestamp are not mistaken for duplicates. Defaults
+    /// to `None` (a single series).
+    fn series(_item: &Self::Item) -> Option<&str> {
+        None
+    }
+}
+
+/// Implements [`TimeSeries`] for a single-series envelope whose `data` field
+/// holds items with a millisecond `timestamp` field.
+macro_rules! impl_time_series {
+    ($response:ty, $item:ty, $unit:expr) => {
+        impl TimeSeries for $response {
+            type Item = $item;
+
+            const QUERY_UNIT: TimeUnit = $unit;
+
+            fn into_items(self) -> Vec<Self::Item> {
+                self.data
+            }
+
+            fn timestamp(item: &Self::Item) -> i64 {
+                item.timestamp
+            }
+        }
+    };
+}
+
+impl_time_series!(
+    crate::generated::IndexPriceResponse,
+    crate::generated::IndexPriceView,
+    TimeUnit::Seconds
+);
+impl_time_series!(
+    crate::generated::FundingRateHistoryResponse,
+    crate::generated::FundingRateHistoryView,
+    TimeUnit::Seconds
+);
+impl_time_series!(
+    crate::generated::CexCandleResponse,
+    crate::generated::CexCandleView,
+    TimeUnit::Seconds
+);
+
+impl TimeSeries for crate::generated::OpenInterestHistoryAggregatedResponse {
+    type Item = crate::models::OpenInterestHistoryPoint;
+
+    const QUERY_UNIT: TimeUnit = TimeUnit::Milliseconds;
+
+    fn into_items(self) -> Vec<Self::Item> {
+        self.points()
+    }
+
+    fn timestamp(item: &Self::Item) -> i64 {
+        item.timestamp
+    }
+
+    fn series(item: &Self::Item) -> Option<&str> {
+        Some(&item.exchange)
+    }
+}
+
+/// The range walked by [`Client::walk_time_range`]: `[from, to]` in UTC
+/// milliseconds, split into consecutive windows of `window` length.
+///
+/// Pick a window small enough that one request covers it in full (e.g. under
+/// the endpoint's page `limit` at the chosen interval) — the walker issues a
+/// single request per window and does not paginate within it.
+#[derive(Debug, Clone)]
+pub struct TimeRange {
+    from: i64,
+    to: i64,
+    window: Duration,
+    resume_after: Option<i64>,
+}
+
+impl TimeRange {
+    /// Creates a range from `from` to `to` (inclusive, UTC milliseconds),
+    /// walked in windows of `window` length. A zero `window` is treated as
+    /// one millisecond.
+    pub fn new(from: i64, to: i64, window: Duration) -> Self {
+        TimeRange {
+            from,
+            to,
+            window,
+            resume_after: None,
+        }
+    }
+
+    /// Resumes an interrupted walk after `timestamp` (UTC milliseconds,
+    /// typically [`TimeRangeWalker::last_timestamp`] from the previous run):
+    /// walking restarts at that timestamp, and items at or before it are
+    /// skipped rather than yielded again.
+    pub fn resume_after(mut self, timestamp: i64) -> Self {
+        self.resume_after = Some(timestamp);
+        self
+    }
+}
+
+/// Bookkeeping shared by the async and blocking time-range walkers: the
+/// window cursor, the resume threshold, and the keys needed to de-duplicate
+/// items repeated across a window boundary. Kept free of `async`/blocking
+/// specifics, like [`consume_page`] for the paginators.
+#[derive(Debug)]
+struct WindowCursor {
+    cursor: i64,
+    end: i64,
+    window_ms: i64,
+    resume_after: Option<i64>,
+    last_timestamp: Option<i64>,
+    done: bool,
+    /// `(series, timestamp)` keys yielded by the previous window. Windows
+    /// only ever overlap on their shared boundary, so this is all that is
+    /// needed to drop repeats.
+    previous: HashSet<(Option<String>, i64)>,
+}
+
+impl WindowCursor {
+    fn new(range: TimeRange) -> Self {
+        let window_ms = i64::try_from(range.window.as_millis())
+            .unwrap_or(i64::MAX)
+            .max(1);
+        let cursor = match range.resume_after {
+            Some(after) => range.from.max(after),
+            None => range.from,
+        };
+        WindowCursor {
+            cursor,
+            end: range.to,
+            window_ms,
+            resume_after: range.resume_after,
+            last_timestamp: range.resume_after,
+            done: cursor > range.to,
+            previous: HashSet::new(),
+        }
+    }
+
+    /// The next window's `[start, stop]` bounds in milliseconds, or `None`
+    /// once the range is covered.
+    fn next_bounds(&self) -> Option<(i64, i64)> {
+        if self.done {
+            return None;
+        }
+        let stop = self.cursor.saturating_add(self.window_ms).min(self.end);
+        Some((self.cursor, stop))
+    }
+
+    /// `base` with the window's `from`/`to` set in `unit`.
+    fn params(
+        base: &BTreeMap<String, String>,
+        unit: TimeUnit,
+        (start, stop): (i64, i64),
+    ) -> BTreeMap<String, String> {
+        let mut params = base.clone();
+        params.insert(
+            "from".to_string(),
+            unit.floor_from_millis(start).to_string(),
+        );
+        params.insert("to".to_string(), unit.ceil_from_millis(stop).to_string());
+        params
+    }
+
+    /// Advances past the window `[_, stop]` and returns its items, sorted by
+    /// timestamp with boundary repeats and already-resumed-past items
+    /// removed.
+    fn consume<T: TimeSeries>(&mut self, stop: i64, response: T) -> Vec<T::Item> {
+        let mut items = response.into_items();
+        items.sort_by_key(|item| T::timestamp(item));
+
+        let mut keys = HashSet::with_capacity(items.len());
+        items.retain(|item| {
+            let timestamp = T::timestamp(item);
+            if self.resume_after.is_some_and(|after| timestamp <= after) {
+                return false;
+            }
+            let key = (T::series(item).map(str::to_string), timestamp);
+            !self.previous.contains(&key) && keys.insert(key)
+        });
+
+        if let Some(last) = items.last() {
+            let timestamp = T::timestamp(last);
+            self.last_timestamp = Some(self.last_timestamp.map_or(timestamp, |t| t.max(timestamp)));
+        }
+        self.previous = keys;
+        self.cursor = stop;
+        self.done = stop >= self.end;
+        items
+    }
+}
+
+/// Async time-range walker returned by [`Client::walk_time_range`].
+///
+/// Each [`next_window`](Self::next_window) call requests one window of the
+/// range (with `from`/`to` set in the endpoint's [`TimeSeries::QUERY_UNIT`]),
+/// until the whole range is covered. Items repeated across a window boundary
+/// are yielded once, and [`last_timestamp`](Self::last_timestamp) records how
+/// far the walk got, so an interrupted walk can pick up again via
+/// [`TimeRange::resume_after`]:
+///
+/// ```no_run
+/// use datamaxi::api::{ClientBuilder, TimeRange};
+/// use datamaxi::IndexPriceResponse;
+/// use std::collections::BTreeMap;
+/// use std::time::Duration;
+///
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = ClientBuilder::new().api_key("my_api_key").build()?;
+/// let mut params = BTreeMap::new();
+/// params.insert("asset".to_string(), "BTC".to_string());
+/// params.insert("interval".to_string(), "1h".to_string());
+///
+/// let day = Duration::from_secs(24 * 60 * 60);
+/// let range = TimeRange::new(1_735_689_600_000, 1_738_368_000_000, day * 7);
+/// let mut windows = client.walk_time_range::<IndexPriceResponse>("/api/v1/index-price", params, range);
+///
+/// while let Some(items) = windows.next_window().await? {
+///     for item in items {
+///         println!("{} {}", item.timestamp, item.price);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct TimeRangeWalker<T: TimeSeries> {
+    client: Client,
+    endpoint: String,
+    params: BTreeMap<String, String>,
+    window: WindowCursor,
+    _marker: PhantomData<T>,
+}
+
+impl<T> TimeRangeWalker<T>
+where
+    T: TimeSeries + DeserializeOwned,
+{
+    fn new(
+        client: Client,
+        endpoint: impl Into<String>,
+        params: BTreeMap<String, String>,
+        range: TimeRange,
+    ) -> Self {
+        TimeRangeWalker {
+            client,
+            endpoint: endpoint.into(),
+            params,
+            window: WindowCursor::new(range),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Fetches and returns the next window's items (possibly empty, e.g.
+    /// across an outage), or `Ok(None)` once the range is covered. Once
+    /// exhausted, further calls keep returning `Ok(None)`.
+    pub async fn next_window(&mut self) -> Result<Option<Vec<T::Item>>> {
+        let Some(bounds) = self.window.next_bounds() else {
+            return Ok(None);
+        };
+        let params = WindowCursor::params(&self.params, T::QUERY_UNIT, bounds);
+        let response: T = self.client.get(&self.endpoint, Some(params)).await?;
+        Ok(Some(self.window.consume(bounds.1, response)))
+    }
+
+    /// The latest item timestamp yielded so far (or the
+    /// [`TimeRange::resume_after`] point, if nothing newer has been yielded
+    /// yet). Persist it to resume the walk later.
+    pub fn last_timestamp(&self) -> Option<i64> {
+        self.window.last_timestamp
+    }
+}
+
 /// Reads at most [`MAX_ERROR_BODY_BYTES`] of an async response body, streaming
 /// chunk by chunk rather than buffering the whole body. Mirrors the blocking
 /// path's `response.take(MAX_ERROR_BODY_BYTES).read_to_string(&mut body)`.
@@ -1102,6 +1581,16 @@ pub enum Error {
     /// Reading the response body failed.
     #[error(transparent)]
     Io(#[from] std::io::Error),
+
+    /// An argument was rejected client-side, before any request was sent
+    /// (for example an exchange that does not offer the requested data).
+    #[error("Invalid argument `{argument}`: {reason}")]
+    InvalidArgument {
+        /// The offending parameter, e.g. `exchange` or `source_exchange`.
+        argument: String,
+        /// Why the value was rejected.
+        reason: String,
+    },
 }
 
 /// Synchronous client surface, enabled by the `sync` feature.
@@ -1115,8 +1604,8 @@ pub mod sync {
     use super::{
         consume_page, is_retryable_error, is_retryable_status, jittered_backoff_delay,
         map_error_status, retry_delay_for_response, starting_page, truncate_body, user_agent,
-        BuilderState, Error, Paginated, Result, RetryConfig, BASE_URL, DEFAULT_TIMEOUT,
-        MAX_ERROR_BODY_BYTES,
+        BuilderState, Error, Paginated, Result, RetryConfig, TimeRange, TimeSeries, WindowCursor,
+        BASE_URL, DEFAULT_TIMEOUT, MAX_ERROR_BODY_BYTES,
     };
     use reqwest::blocking::Response;
     use reqwest::StatusCode;
@@ -1189,7 +1678,8 @@ pub mod sync {
         /// failures (timeouts, connection errors, `429`, and `5xx`) are retried
         /// per the client's retry config with exponential backoff (a `429`
         /// honors `Retry-After`); fatal statuses are returned without retry.
-        /// Backoff waits use a blocking [`std::thread::sleep`].
+        /// Backoff waits use a blocking [`std::thread::sleep`]. Parameters
+        /// are validated first, as in the async client.
         ///
         /// With the `tracing` feature enabled, each call is wrapped in a span
         /// carrying `method`, `endpoint`, `attempt`, and the resolved
@@ -1230,6 +1720,30 @@ pub mod sync {
         {
             Paginator::new(self.clone(), endpoint, params)
         }
+
+        /// Returns a walker over a time-bounded endpoint (see
+        /// [`super::TimeSeries`]). Mirrors the async
+        /// [`super::Client::walk_time_range`]; see its docs for how `params`
+        /// and the range work.
+        pub fn walk_time_range<T>(
+            &self,
+            endpoint: impl Into<String>,
+            params: BTreeMap<String, String>,
+            range: TimeRange,
+        ) -> TimeRangeWalker<T>
+        where
+            T: TimeSeries + DeserializeOwned,
+        {
+            TimeRangeWalker {
+                client: self.clone(),
+                endpoint: endpoint.into(),
+                params,
+                window: WindowCursor::new(range),
+                _marker: PhantomData,
+            }
+        }
+
+        typed_walks!();
     }
 
     /// Blocking auto-paginator returned by [`Client::paginate`], implementing
@@ -1311,6 +1825,50 @@ pub mod sync {
         }
     }
 
+    /// Blocking time-range walker returned by [`Client::walk_time_range`],
+    /// implementing [`Iterator`] over windows of items (one
+    /// `Result<Vec<T::Item>>` per window, possibly empty). Mirrors the async
+    /// [`super::TimeRangeWalker`]; iteration stops once the range is covered,
+    /// and after the first `Err`.
+    pub struct TimeRangeWalker<T: TimeSeries> {
+        client: Client,
+        endpoint: String,
+        params: BTreeMap<String, String>,
+        window: WindowCursor,
+        _marker: PhantomData<T>,
+    }
+
+    impl<T: TimeSeries> TimeRangeWalker<T> {
+        /// The latest item timestamp yielded so far; see
+        /// [`super::TimeRangeWalker::last_timestamp`].
+        pub fn last_timestamp(&self) -> Option<i64> {
+            self.window.last_timestamp
+        }
+    }
+
+    impl<T> Iterator for TimeRangeWalker<T>
+    where
+        T: TimeSeries + DeserializeOwned,
+    {
+        type Item = Result<Vec<T::Item>>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            let bounds = self.window.next_bounds()?;
+            let params = WindowCursor::params(&self.params, T::QUERY_UNIT, bounds);
+
+            match self.client.get::<T>(&self.endpoint, Some(params)) {
+                Ok(response) => Some(Ok(self.window.consume(bounds.1, response))),
+                Err(error) => {
+                    // Stop walking after an error rather than retrying the
+                    // same window forever; `last_timestamp` still marks where
+                    // to resume.
+                    self.window.done = true;
+                    Some(Err(error))
+                }
+            }
+        }
+    }
+
     /// Reads at most [`MAX_ERROR_BODY_BYTES`] of a blocking response body,
     /// truncated on a UTF-8 char boundary. The blocking counterpart to the
     /// async [`super::read_body_capped`]; shared by the `400` and `500` arms of
@@ -1749,4 +2307,112 @@ mod tests {
             "without a reported total, only an empty page should terminate"
         );
     }
+
+    // --- Time-range walking -------------------------------------------------
+
+    /// A minimal single-series envelope for exercising [`WindowCursor`]
+    /// without a real generated response type.
+    struct DummySeries {
+        data: Vec<(Option<&'static str>, i64)>,
+    }
+
+    impl TimeSeries for DummySeries {
+        type Item = (Option<&'static str>, i64);
+
+        const QUERY_UNIT: TimeUnit = TimeUnit::Seconds;
+
+        fn into_items(self) -> Vec<Self::Item> {
+            self.data
+        }
+
+        fn timestamp(item: &Self::Item) -> i64 {
+            item.1
+        }
+
+        fn series(item: &Self::Item) -> Option<&str> {
+            item.0
+        }
+    }
+
+    fn series(timestamps: &[i64]) -> DummySeries {
+        DummySeries {
+            data: timestamps.iter().map(|&t| (None, t)).collect(),
+        }
+    }
+
+    #[test]
+    fn time_unit_rounds_bounds_outward() {
+        assert_eq!(TimeUnit::Seconds.floor_from_millis(1_999), 1);
+        assert_eq!(TimeUnit::Seconds.ceil_from_millis(1_001), 2);
+        assert_eq!(TimeUnit::Seconds.ceil_from_millis(2_000), 2);
+        assert_eq!(TimeUnit::Milliseconds.ceil_from_millis(1_001), 1_001);
+    }
+
+    #[test]
+    fn window_cursor_splits_range_into_windows() {
+        let mut cursor = WindowCursor::new(TimeRange::new(0, 2_500, Duration::from_secs(1)));
+
+        let mut bounds = Vec::new();
+        while let Some(b) = cursor.next_bounds() {
+            bounds.push(b);
+            cursor.consume(b.1, series(&[]));
+        }
+
+        assert_eq!(bounds, [(0, 1_000), (1_000, 2_000), (2_000, 2_500)]);
+    }
+
+    #[test]
+    fn window_cursor_params_use_query_unit() {
+        let mut base = BTreeMap::new();
+        base.insert("asset".to_string(), "BTC".to_string());
+        base.insert("from".to_string(), "stale".to_string());
+
+        let params = WindowCursor::params(&base, TimeUnit::Seconds, (1_500, 2_500));
+
+        assert_eq!(params["asset"], "BTC");
+        assert_eq!(params["from"], "1");
+        assert_eq!(params["to"], "3");
+    }
+
+    #[test]
+    fn window_cursor_dedupes_boundary_items_per_series() {
+        let mut cursor = WindowCursor::new(TimeRange::new(0, 2_000, Duration::from_secs(1)));
+
+        let first = cursor.consume(1_000, series(&[1_000, 0, 500, 500]));
+        assert_eq!(
+            first.iter().map(|i| i.1).collect::<Vec<_>>(),
+            [0, 500, 1_000]
+        );
+
+        // The boundary bucket repeats in the next window; a different series
+        // at the same timestamp is not a duplicate.
+        let second = cursor.consume(
+            2_000,
+            DummySeries {
+                data: vec![(None, 1_000), (Some("bybit"), 1_000), (None, 2_000)],
+            },
+        );
+        assert_eq!(second, [(Some("bybit"), 1_000), (None, 2_000)]);
+        assert_eq!(cursor.last_timestamp, Some(2_000));
+        assert!(cursor.next_bounds().is_none());
+    }
+
+    #[test]
+    fn window_cursor_resumes_after_last_timestamp() {
+        let range = TimeRange::new(0, 10_000, Duration::from_secs(5)).resume_after(6_000);
+        let mut cursor = WindowCursor::new(range);
+
+        assert_eq!(cursor.last_timestamp, Some(6_000));
+        assert_eq!(cursor.next_bounds(), Some((6_000, 10_000)));
+
+        let items = cursor.consume(10_000, series(&[6_000, 7_000]));
+        assert_eq!(items.iter().map(|i| i.1).collect::<Vec<_>>(), [7_000]);
+        assert_eq!(cursor.last_timestamp, Some(7_000));
+    }
+
+    #[test]
+    fn window_cursor_with_inverted_range_is_done() {
+        let cursor = WindowCursor::new(TimeRange::new(10, 0, Duration::from_secs(1)));
+        assert!(cursor.next_bounds().is_none());
+    }
 }
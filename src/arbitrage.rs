@@ -0,0 +1,519 @@
+//! Fee- and transferability-aware arbitrage over premium data.
+//!
+//! A [`PremiumDetail`] row is a price gap between a source and a target
+//! market. Whether it can be traded depends on two more endpoints: the taker
+//! fees of both legs ([`TradingFees::fees`](crate::TradingFees::fees)) and,
+//! when the asset has to move between two spot exchanges, whether the source
+//! allows withdrawals and the target deposits on a common network
+//! ([`WalletStatus::get`](crate::WalletStatus::get)). The API does not
+//! document the wallet state values yet, so a state that is not understood
+//! leaves the route's [`Transfer`](crate::arbitrage::Transfer) unknown
+//! rather than blocked.
+//!
+//! [`Arbitrage::evaluate`](crate::arbitrage::Arbitrage::evaluate) joins the
+//! three offline and ranks the resulting
+//! [`Opportunity`](crate::arbitrage::Opportunity)s by net edge after fees;
+//! [`Client::arbitrage`](crate::Client::arbitrage) fetches them first. Every
+//! route is read as *buy on the source, sell on the target*.
+//!
+//! ```no_run
+//! use datamaxi::arbitrage::Arbitrage;
+//! use datamaxi::{Client, PremiumOptions};
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = Client::new("my_api_key");
+//! let options = PremiumOptions::new().source_exchange("binance").target_exchange("upbit");
+//! let ranked = client.arbitrage(options, &Arbitrage::new(10_000.0).min_net_edge(0.5)).await?;
+//! for opportunity in ranked.iter().take(5) {
+//!     println!(
+//!         "{}: {:.2}% net, {:.0} expected on 10k",
+//!         opportunity.base_id, opportunity.net_edge_pct, opportunity.expected_profit,
+//!     );
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::api::{Error, Result};
+use crate::enums::{Market, WalletState};
+use crate::exchange::Exchange;
+use crate::generated::{
+    CexFeesOptions, CexFeesView, PremiumDetail, PremiumOptions, WalletStatusOptions,
+    WalletStatusView,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Taker fee assumed for a leg missing from the fee schedule: 0.1%, as a
+/// fraction.
+pub const DEFAULT_TAKER_FEE: f64 = 0.001;
+
+/// Settings for evaluating premium rows as arbitrage routes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Arbitrage {
+    notional: f64,
+    default_taker_fee: f64,
+    min_net_edge_pct: Option<f64>,
+    include_blocked: bool,
+}
+
+impl Arbitrage {
+    /// Evaluates routes for a trade of `notional`, in the currency the
+    /// premium prices are quoted in.
+    pub fn new(notional: f64) -> Self {
+        Arbitrage {
+            notional,
+            default_taker_fee: DEFAULT_TAKER_FEE,
+            min_net_edge_pct: None,
+            include_blocked: false,
+        }
+    }
+
+    /// The taker fee, as a fraction (`0.001` = 0.1%), for legs the fee
+    /// schedule does not cover. Defaults to [`DEFAULT_TAKER_FEE`].
+    pub fn default_taker_fee(mut self, fee: f64) -> Self {
+        self.default_taker_fee = fee;
+        self
+    }
+
+    /// Drops routes whose net edge is below `pct` percent.
+    pub fn min_net_edge(mut self, pct: f64) -> Self {
+        self.min_net_edge_pct = Some(pct);
+        self
+    }
+
+    /// Keeps routes blocked by closed deposits or withdrawals, ranked after
+    /// every other route. They are dropped by default.
+    pub fn include_blocked(mut self, include: bool) -> Self {
+        self.include_blocked = include;
+        self
+    }
+
+    /// Joins premium rows with the fee schedule and wallet statuses, and
+    /// returns the routes ranked by net edge, best first.
+    ///
+    /// Rows without a premium (neither `pdp` nor both prices) are skipped.
+    /// Exchange ids are compared normalized, assets and networks
+    /// case-insensitively.
+    pub fn evaluate<'a>(
+        &self,
+        premiums: impl IntoIterator<Item = &'a PremiumDetail>,
+        fees: &[CexFeesView],
+        wallets: &[WalletStatusView],
+    ) -> Vec<Opportunity> {
+        let fees: BTreeMap<_, _> = fees
+            .iter()
+            .map(|fee| {
+                let key = (
+                    Exchange::new(&fee.exchange),
+                    upper(&fee.base),
+                    upper(&fee.quote),
+                );
+                (key, fee)
+            })
+            .collect();
+        let mut networks: BTreeMap<_, Vec<&WalletStatusView>> = BTreeMap::new();
+        for wallet in wallets {
+            networks
+                .entry((Exchange::new(&wallet.exchange), upper(&wallet.currency)))
+                .or_default()
+                .push(wallet);
+        }
+
+        let mut ranked: Vec<Opportunity> = premiums
+            .into_iter()
+            .filter_map(|detail| {
+                let source = self.leg(
+                    &fees, &detail.se, &detail.sm, &detail.sb, &detail.sq, detail.sp,
+                );
+                let target = self.leg(
+                    &fees, &detail.te, &detail.tm, &detail.tb, &detail.tq, detail.tp,
+                );
+                let gross = detail.pdp.or(match (detail.sp, detail.tp) {
+                    (Some(source), Some(target)) if source > 0.0 => {
+                        Some((target / source - 1.0) * 100.0)
+                    }
+                    _ => None,
+                })?;
+                let net =
+                    ((1.0 - source.taker_fee) * (1.0 + gross / 100.0) * (1.0 - target.taker_fee)
+                        - 1.0)
+                        * 100.0;
+                let transfer = transfer(&networks, &source, &target, detail.t);
+                Some(Opportunity {
+                    base_id: detail.bid.clone(),
+                    source,
+                    target,
+                    gross_edge_pct: gross,
+                    net_edge_pct: net,
+                    expected_profit: self.notional * net / 100.0,
+                    transfer,
+                })
+            })
+            .filter(|opportunity| self.include_blocked || !opportunity.transfer.is_blocked())
+            .filter(|opportunity| {
+                self.min_net_edge_pct
+                    .is_none_or(|min| opportunity.net_edge_pct >= min)
+            })
+            .collect();
+        ranked.sort_by(|a, b| {
+            (a.transfer.is_blocked(), -a.net_edge_pct)
+                .partial_cmp(&(b.transfer.is_blocked(), -b.net_edge_pct))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        ranked
+    }
+
+    fn leg(
+        &self,
+        fees: &BTreeMap<(Exchange, String, String), &CexFeesView>,
+        exchange: &str,
+        market: &str,
+        base: &str,
+        quote: &str,
+        price: Option<f64>,
+    ) -> RouteLeg {
+        let exchange = Exchange::new(exchange);
+        let market = Market::from(market);
+        let (base, quote) = (upper(base), upper(quote));
+        let fee = fees
+            .get(&(exchange.clone(), base.clone(), quote.clone()))
+            .and_then(|fee| match market {
+                Market::Futures => fee.futures_taker_fee,
+                _ => fee.spot_take_fee,
+            });
+        RouteLeg {
+            exchange,
+            market,
+            base,
+            quote,
+            price,
+            taker_fee: fee.unwrap_or(self.default_taker_fee),
+            fee_known: fee.is_some(),
+        }
+    }
+}
+
+/// One side of a route.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RouteLeg {
+    /// The exchange traded on.
+    pub exchange: Exchange,
+    /// Spot or futures.
+    pub market: Market,
+    /// Base asset, upper-cased.
+    pub base: String,
+    /// Quote asset, upper-cased.
+    pub quote: String,
+    /// Latest price in the requested currency, if reported.
+    pub price: Option<f64>,
+    /// The taker fee applied, as a fraction.
+    pub taker_fee: f64,
+    /// `false` if [`taker_fee`](Self::taker_fee) is the configured default
+    /// because the fee schedule has no entry for this market.
+    pub fee_known: bool,
+}
+
+/// Whether the asset can move from the source to the target exchange.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum Transfer {
+    /// At least one leg is futures: the route is hedged in place and nothing
+    /// has to move.
+    NotRequired,
+    /// Withdrawals on the source and deposits on the target are open on
+    /// these networks.
+    Open {
+        /// The common open networks, upper-cased.
+        networks: Vec<String>,
+    },
+    /// Deposits or withdrawals are closed.
+    Blocked {
+        /// Which side is closed.
+        reason: String,
+    },
+    /// No wallet status was available or its states are not understood,
+    /// and the premium row does not say.
+    Unknown,
+}
+
+impl Transfer {
+    /// Whether the route is blocked by closed deposits or withdrawals.
+    pub fn is_blocked(&self) -> bool {
+        matches!(self, Transfer::Blocked { .. })
+    }
+}
+
+/// A premium row evaluated as a route: buy on the source, sell on the target.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Opportunity {
+    /// The base token's id (`bid`).
+    pub base_id: String,
+    /// Where the asset is bought.
+    pub source: RouteLeg,
+    /// Where the asset is sold.
+    pub target: RouteLeg,
+    /// The premium before fees, percent.
+    pub gross_edge_pct: f64,
+    /// The premium after both taker fees, percent.
+    pub net_edge_pct: f64,
+    /// [`net_edge_pct`](Self::net_edge_pct) applied to the notional.
+    pub expected_profit: f64,
+    /// Whether the asset can move between the exchanges.
+    pub transfer: Transfer,
+}
+
+fn upper(value: &str) -> String {
+    value.trim().to_ascii_uppercase()
+}
+
+/// Networks on which `state` is open, or `None` without wallet data or
+/// when any state on the asset is not understood.
+fn open_networks(
+    wallets: Option<&Vec<&WalletStatusView>>,
+    state: fn(&WalletStatusView) -> WalletState,
+) -> Option<BTreeSet<String>> {
+    let mut networks = BTreeSet::new();
+    for wallet in wallets? {
+        if state(wallet).is_open()? {
+            networks.insert(upper(&wallet.network));
+        }
+    }
+    Some(networks)
+}
+
+fn transfer(
+    networks: &BTreeMap<(Exchange, String), Vec<&WalletStatusView>>,
+    source: &RouteLeg,
+    target: &RouteLeg,
+    transferable: Option<bool>,
+) -> Transfer {
+    if source.market != Market::Spot || target.market != Market::Spot {
+        return Transfer::NotRequired;
+    }
+    let withdraw = open_networks(
+        networks.get(&(source.exchange.clone(), source.base.clone())),
+        WalletStatusView::withdraw_state_kind,
+    );
+    let deposit = open_networks(
+        networks.get(&(target.exchange.clone(), target.base.clone())),
+        WalletStatusView::deposit_state_kind,
+    );
+    let blocked = |reason: String| Transfer::Blocked { reason };
+    match (withdraw, deposit) {
+        (Some(withdraw), _) if withdraw.is_empty() => blocked(format!(
+            "withdrawals of {} are closed on {} on every network",
+            source.base, source.exchange
+        )),
+        (_, Some(deposit)) if deposit.is_empty() => blocked(format!(
+            "deposits of {} are closed on {} on every network",
+            target.base, target.exchange
+        )),
+        (Some(withdraw), Some(deposit)) => {
+            let networks: Vec<String> = withdraw.intersection(&deposit).cloned().collect();
+            if networks.is_empty() {
+                blocked(format!(
+                    "no network is open for withdrawal on {} and deposit on {}",
+                    source.exchange, target.exchange
+                ))
+            } else {
+                Transfer::Open { networks }
+            }
+        }
+        _ if transferable == Some(false) => {
+            blocked("the premium row reports the asset as not transferable".to_string())
+        }
+        _ => Transfer::Unknown,
+    }
+}
+
+/// Exchanges on either leg of any row.
+fn exchanges(details: &[PremiumDetail]) -> BTreeSet<Exchange> {
+    details
+        .iter()
+        .flat_map(|detail| [Exchange::new(&detail.se), Exchange::new(&detail.te)])
+        .collect()
+}
+
+/// Base assets of spot-to-spot rows, the only ones that need a transfer.
+fn transfer_assets(details: &[PremiumDetail]) -> BTreeSet<String> {
+    details
+        .iter()
+        .filter(|detail| {
+            Market::from(detail.sm.as_str()) == Market::Spot
+                && Market::from(detail.tm.as_str()) == Market::Spot
+        })
+        .flat_map(|detail| [upper(&detail.sb), upper(&detail.tb)])
+        .collect()
+}
+
+/// Body of the async and blocking `arbitrage`; see `get_loop!` in [`crate::api`].
+macro_rules! arbitrage {
+    ($client:expr, $options:expr, $arbitrage:expr $(, $aw:ident)?) => {{
+        let client = $client;
+        let response = client.premium().get($options)$(.$aw)?;
+        let details: Vec<PremiumDetail> =
+            response?.data.into_iter().map(|view| view.detail).collect();
+
+        let mut fees = Vec::new();
+        for exchange in exchanges(&details) {
+            let schedule = client
+                .trading_fees()
+                .fees(CexFeesOptions::new().exchange(exchange))$(.$aw)?;
+            fees.extend(schedule?);
+        }
+
+        let mut wallets = Vec::new();
+        for asset in transfer_assets(&details) {
+            let statuses = client
+                .wallet_status()
+                .get(asset, WalletStatusOptions::default())$(.$aw)?;
+            match statuses {
+                Ok(statuses) => wallets.extend(statuses),
+                // An asset the wallet service does not track leaves its
+                // routes to the premium row's own transferability flag.
+                Err(Error::NotFound { .. }) => {}
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok($arbitrage.evaluate(&details, &fees, &wallets))
+    }};
+}
+
+impl crate::api::Client {
+    /// Fetches premium rows for `options`, joins them with trading fees and
+    /// wallet statuses, and ranks them with `arbitrage` (one premium request,
+    /// one fee request per exchange, one wallet request per spot asset).
+    pub async fn arbitrage(
+        &self,
+        options: PremiumOptions,
+        arbitrage: &Arbitrage,
+    ) -> Result<Vec<Opportunity>> {
+        arbitrage!(self, options, arbitrage, await)
+    }
+}
+
+#[cfg(feature = "sync")]
+impl crate::api::sync::Client {
+    /// Blocking mirror of the async `Client::arbitrage`.
+    pub fn arbitrage(
+        &self,
+        options: PremiumOptions,
+        arbitrage: &Arbitrage,
+    ) -> Result<Vec<Opportunity>> {
+        arbitrage!(self, options, arbitrage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn premium(source: &str, target: &str, market: &str, pdp: f64) -> PremiumDetail {
+        PremiumDetail {
+            bid: "bitcoin".into(),
+            se: source.into(),
+            sm: "spot".into(),
+            sb: "BTC".into(),
+            sq: "USDT".into(),
+            te: target.into(),
+            tm: market.into(),
+            tb: "BTC".into(),
+            tq: "KRW".into(),
+            pdp: Some(pdp),
+            ..Default::default()
+        }
+    }
+
+    fn fee(exchange: &str, quote: &str, taker: f64) -> CexFeesView {
+        CexFeesView {
+            exchange: exchange.into(),
+            base: "BTC".into(),
+            quote: quote.into(),
+            spot_take_fee: Some(taker),
+            futures_taker_fee: Some(taker / 2.0),
+            ..Default::default()
+        }
+    }
+
+    fn wallet(exchange: &str, network: &str, deposit: &str, withdraw: &str) -> WalletStatusView {
+        WalletStatusView {
+            exchange: exchange.into(),
+            currency: "btc".into(),
+            network: network.into(),
+            deposit_state: deposit.into(),
+            withdraw_state: withdraw.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn net_edge_applies_both_taker_fees() {
+        let fees = [fee("binance", "USDT", 0.001), fee("upbit", "KRW", 0.0025)];
+        let ranked = Arbitrage::new(10_000.0).evaluate(
+            &[premium("binance", "upbit", "spot", 2.0)],
+            &fees,
+            &[],
+        );
+
+        let opportunity = &ranked[0];
+        let net = (0.999 * 1.02 * 0.9975 - 1.0) * 100.0;
+        assert!((opportunity.net_edge_pct - net).abs() < 1e-12);
+        assert!((opportunity.expected_profit - net * 100.0).abs() < 1e-9);
+        assert!(opportunity.source.fee_known && opportunity.target.fee_known);
+        assert_eq!(opportunity.transfer, Transfer::Unknown);
+
+        let futures = Arbitrage::new(1.0).default_taker_fee(0.0).evaluate(
+            &[premium("binance", "bybit", "futures", 1.0)],
+            &fees,
+            &[],
+        );
+        assert_eq!(futures[0].transfer, Transfer::NotRequired);
+        assert!(!futures[0].target.fee_known);
+        assert_eq!(futures[0].target.taker_fee, 0.0);
+    }
+
+    #[test]
+    fn unrecognized_wallet_states_stay_unknown() {
+        let wallets = [
+            wallet("binance", "BTC", "s", "s"),
+            wallet("upbit", "BTC", "s", "s"),
+        ];
+        let ranked = Arbitrage::new(1.0).evaluate(
+            &[premium("binance", "upbit", "spot", 2.0)],
+            &[],
+            &wallets,
+        );
+
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].transfer, Transfer::Unknown);
+    }
+
+    #[test]
+    fn untransferable_rows_block_and_rank_last() {
+        let premiums = [
+            PremiumDetail {
+                t: Some(false),
+                ..premium("binance", "upbit", "spot", 3.0)
+            },
+            premium("bithumb", "upbit", "spot", 1.0),
+        ];
+        let wallets = [wallet("binance", "BTC", "suspended", "suspended")];
+
+        let open = Arbitrage::new(1.0).evaluate(&premiums, &[], &wallets);
+        let routes: Vec<&str> = open.iter().map(|o| o.source.exchange.as_str()).collect();
+        assert_eq!(routes, ["bithumb"]);
+
+        let all = Arbitrage::new(1.0)
+            .include_blocked(true)
+            .evaluate(&premiums, &[], &wallets);
+        let order: Vec<&str> = all.iter().map(|o| o.source.exchange.as_str()).collect();
+        assert_eq!(order, ["bithumb", "binance"]);
+        assert!(all[1].transfer.is_blocked());
+        assert!(Arbitrage::new(1.0)
+            .min_net_edge(5.0)
+            .evaluate(&premiums, &[], &wallets)
+            .is_empty());
+    }
+}
@@ -0,0 +1,139 @@
+//! Apache Arrow conversion of the response models (feature `arrow`).
+//!
+//! Any slice of [`Tabular`](crate::table::Tabular) rows converts to a typed
+//! [`RecordBatch`](arrow_array::RecordBatch) via
+//! [`ToRecordBatch`](crate::arrow::ToRecordBatch), following the
+//! [`table`](crate::table) schema rules: timestamps are
+//! `timestamp[ms, tz=UTC]`, `Option<_>` fields are nullable columns, and
+//! string lists are `list<utf8>`.
+//!
+//! ```
+//! use datamaxi::arrow::ToRecordBatch;
+//! use datamaxi::CexCandleView;
+//!
+//! let candles = vec![CexCandleView::default()]; // e.g. `CexCandle::get(..).data`
+//! let batch = candles.to_record_batch()?;
+//! assert_eq!(batch.num_rows(), 1);
+//! # Ok::<(), datamaxi::arrow::ArrowError>(())
+//! ```
+
+use crate::table::{Column, ColumnSpec, ColumnType, ColumnValues, Tabular};
+use arrow_array::builder::{ListBuilder, StringBuilder};
+use arrow_array::{
+    ArrayRef, BooleanArray, Float64Array, Int64Array, RecordBatch, StringArray,
+    TimestampMillisecondArray,
+};
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use std::sync::Arc;
+
+/// Re-exported so callers can name the conversion error without depending
+/// on `arrow-schema` directly.
+pub use arrow_schema::ArrowError;
+
+/// The Arrow schema of `T`'s columns.
+pub fn schema<T: Tabular>() -> SchemaRef {
+    Arc::new(Schema::new(
+        T::schema().iter().map(field).collect::<Vec<_>>(),
+    ))
+}
+
+/// Converts a slice of rows into a [`RecordBatch`].
+pub trait ToRecordBatch {
+    /// One record batch with a row per element and the schema of
+    /// [`schema`].
+    fn to_record_batch(&self) -> Result<RecordBatch, ArrowError>;
+}
+
+impl<T: Tabular> ToRecordBatch for [T] {
+    fn to_record_batch(&self) -> Result<RecordBatch, ArrowError> {
+        let arrays: Vec<ArrayRef> = T::columns(self).into_iter().map(array).collect();
+        RecordBatch::try_new(schema::<T>(), arrays)
+    }
+}
+
+fn field(spec: &ColumnSpec) -> Field {
+    let data_type = match spec.column_type {
+        ColumnType::Timestamp => DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())),
+        ColumnType::Int64 => DataType::Int64,
+        ColumnType::Float64 => DataType::Float64,
+        ColumnType::Boolean => DataType::Boolean,
+        ColumnType::Utf8 => DataType::Utf8,
+        ColumnType::Utf8List => {
+            DataType::List(Arc::new(Field::new_list_field(DataType::Utf8, true)))
+        }
+    };
+    Field::new(spec.name.clone(), data_type, spec.nullable)
+}
+
+fn array(column: Column) -> ArrayRef {
+    match column.values {
+        ColumnValues::Int64(values) if column.spec.column_type == ColumnType::Timestamp => {
+            Arc::new(TimestampMillisecondArray::from(values).with_timezone("UTC"))
+        }
+        ColumnValues::Int64(values) => Arc::new(Int64Array::from(values)),
+        ColumnValues::Float64(values) => Arc::new(Float64Array::from(values)),
+        ColumnValues::Boolean(values) => Arc::new(BooleanArray::from(values)),
+        ColumnValues::Utf8(values) => Arc::new(StringArray::from(values)),
+        ColumnValues::Utf8List(values) => {
+            let mut builder = ListBuilder::new(StringBuilder::new());
+            for value in values {
+                match value {
+                    Some(items) => {
+                        for item in items {
+                            builder.values().append_value(item);
+                        }
+                        builder.append(true);
+                    }
+                    None => builder.append(false),
+                }
+            }
+            Arc::new(builder.finish())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generated::{CexSymbolCautionsView, FundingRateHistoryView};
+    use arrow_array::Array;
+
+    #[test]
+    fn record_batch_types_timestamps_and_nulls() {
+        let rows = [
+            FundingRateHistoryView {
+                timestamp: 1_700_000_000_000,
+                funding_rate: Some(0.0001),
+            },
+            FundingRateHistoryView {
+                timestamp: 1_700_000_028_800,
+                funding_rate: None,
+            },
+        ];
+
+        let batch = rows.to_record_batch().expect("valid batch");
+
+        let schema = batch.schema();
+        assert_eq!(
+            schema.field(0).data_type(),
+            &DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into()))
+        );
+        assert!(!schema.field(0).is_nullable());
+        assert!(schema.field(1).is_nullable());
+        assert_eq!(batch.column(1).null_count(), 1);
+    }
+
+    #[test]
+    fn string_lists_become_list_arrays() {
+        let rows = [CexSymbolCautionsView {
+            reasons: vec!["a".into(), "b".into()],
+            ..Default::default()
+        }];
+
+        let batch = rows.to_record_batch().expect("valid batch");
+        let reasons = batch.column_by_name("reasons").expect("column");
+
+        assert!(matches!(reasons.data_type(), DataType::List(_)));
+        assert_eq!(reasons.len(), 1);
+    }
+}
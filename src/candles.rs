@@ -0,0 +1,492 @@
+//! Candle series utilities: gap detection and repair over
+//! [`CexCandleResponse::data`].
+//!
+//! Exchange outages leave holes in candle series, and repeated or
+//! overlapping fetches leave duplicates. A
+//! [`CandleSeries`](crate::candles::CandleSeries) pairs a list of
+//! [`CexCandleView`]s with its [`CexCandleInterval`] so those problems can be
+//! reported ([`inspect`](crate::candles::CandleSeries::inspect)), repaired
+//! from the API ([`CexCandle::refetch_missing`](crate::CexCandle::refetch_missing)),
+//! or papered over ([`fill_forward`](crate::candles::CandleSeries::fill_forward)).
+//!
+//! Candle timestamps are UTC milliseconds at the candle open; the series
+//! grid is every multiple of the interval length.
+//!
+//! ```no_run
+//! use datamaxi::candles::CandleSeries;
+//! use datamaxi::{CexCandleInterval, CexCandleOptions, Client};
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let candle = Client::new("my_api_key").cex_candle();
+//! let options = CexCandleOptions::new().interval(CexCandleInterval::_1h);
+//! let response = candle.get("binance", "BTC-USDT", options.clone()).await?;
+//!
+//! let mut series = CandleSeries::new(CexCandleInterval::_1h, response.data);
+//! if !series.inspect().is_clean() {
+//!     candle
+//!         .refetch_missing("binance", "BTC-USDT", options, &mut series)
+//!         .await?;
+//!     series.fill_forward();
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::generated::{CexCandleInterval, CexCandleOptions, CexCandleResponse, CexCandleView};
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+
+impl CexCandleInterval {
+    /// The candle length this interval stands for.
+    pub fn duration(&self) -> Duration {
+        const MINUTE: u64 = 60;
+        let secs = match self {
+            CexCandleInterval::_1m => MINUTE,
+            CexCandleInterval::_5m => 5 * MINUTE,
+            CexCandleInterval::_15m => 15 * MINUTE,
+            CexCandleInterval::_1h => 60 * MINUTE,
+            CexCandleInterval::_4h => 4 * 60 * MINUTE,
+            CexCandleInterval::_12h => 12 * 60 * MINUTE,
+            CexCandleInterval::_1d => 24 * 60 * MINUTE,
+        };
+        Duration::from_secs(secs)
+    }
+
+    /// The interval matching a wire value such as a response's
+    /// [`CexCandleResponse::interval`] (`"1h"`), or `None` if unknown.
+    pub fn from_wire(value: &str) -> Option<Self> {
+        [
+            CexCandleInterval::_1m,
+            CexCandleInterval::_5m,
+            CexCandleInterval::_15m,
+            CexCandleInterval::_1h,
+            CexCandleInterval::_4h,
+            CexCandleInterval::_12h,
+            CexCandleInterval::_1d,
+        ]
+        .into_iter()
+        .find(|interval| interval.as_str() == value)
+    }
+
+    /// The interval length in milliseconds.
+    fn millis(&self) -> i64 {
+        self.duration().as_millis() as i64
+    }
+}
+
+/// What [`CandleSeries::inspect`] found wrong with a series. Every list holds
+/// candle-open timestamps (UTC milliseconds), in ascending order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GapReport {
+    /// Grid slots from the one holding the first candle through the last
+    /// candle with no aligned candle on them.
+    pub missing: Vec<i64>,
+    /// Timestamps carried by more than one candle (listed once each).
+    pub duplicates: Vec<i64>,
+    /// Timestamps of candles that arrived earlier than the candle before them.
+    pub out_of_order: Vec<i64>,
+    /// Timestamps of candles reporting zero traded volume.
+    pub zero_volume: Vec<i64>,
+    /// Timestamps that do not fall on the interval grid.
+    pub misaligned: Vec<i64>,
+}
+
+impl GapReport {
+    /// Whether the series has no missing, duplicate, out-of-order or
+    /// misaligned candles. Zero-volume bars alone don't make a series dirty:
+    /// quiet markets legitimately produce them.
+    pub fn is_clean(&self) -> bool {
+        self.missing.is_empty()
+            && self.duplicates.is_empty()
+            && self.out_of_order.is_empty()
+            && self.misaligned.is_empty()
+    }
+
+    /// The missing slots grouped into contiguous `[first, last]` runs, i.e.
+    /// the sub-windows to re-fetch.
+    pub fn missing_windows(&self, interval: CexCandleInterval) -> Vec<(i64, i64)> {
+        let step = interval.millis();
+        let mut windows: Vec<(i64, i64)> = Vec::new();
+        for &timestamp in &self.missing {
+            match windows.last_mut() {
+                Some((_, last)) if timestamp - *last == step => *last = timestamp,
+                _ => windows.push((timestamp, timestamp)),
+            }
+        }
+        windows
+    }
+}
+
+/// A candle series at a fixed [`CexCandleInterval`].
+///
+/// Construction keeps the candles exactly as given, so
+/// [`inspect`](Self::inspect) can report duplicates and ordering problems;
+/// every mutating method leaves the series sorted and de-duplicated.
+#[derive(Debug, Clone)]
+pub struct CandleSeries {
+    interval: CexCandleInterval,
+    candles: Vec<CexCandleView>,
+}
+
+impl CandleSeries {
+    /// Wraps `candles` sampled at `interval`.
+    pub fn new(interval: CexCandleInterval, candles: Vec<CexCandleView>) -> Self {
+        CandleSeries { interval, candles }
+    }
+
+    /// Wraps a [`CexCandleResponse`], reading the interval from its
+    /// `interval` field. Returns `None` if that is not a known interval.
+    pub fn from_response(response: CexCandleResponse) -> Option<Self> {
+        let interval = CexCandleInterval::from_wire(&response.interval)?;
+        Some(CandleSeries::new(interval, response.data))
+    }
+
+    /// The series interval.
+    pub fn interval(&self) -> CexCandleInterval {
+        self.interval
+    }
+
+    /// The candles, in their current order.
+    pub fn candles(&self) -> &[CexCandleView] {
+        &self.candles
+    }
+
+    /// Consumes the series, returning its candles.
+    pub fn into_candles(self) -> Vec<CexCandleView> {
+        self.candles
+    }
+
+    /// Reports missing slots, duplicates, out-of-order and misaligned
+    /// candles, and zero-volume bars.
+    pub fn inspect(&self) -> GapReport {
+        let step = self.interval.millis();
+        let mut report = GapReport::default();
+
+        let mut counts: BTreeMap<i64, usize> = BTreeMap::new();
+        let mut previous: Option<i64> = None;
+        for candle in &self.candles {
+            let timestamp = candle.timestamp;
+            *counts.entry(timestamp).or_default() += 1;
+            if previous.is_some_and(|p| timestamp < p) {
+                report.out_of_order.push(timestamp);
+            }
+            previous = Some(timestamp);
+            if candle.volume == 0.0 {
+                report.zero_volume.push(timestamp);
+            }
+            if timestamp.rem_euclid(step) != 0 {
+                report.misaligned.push(timestamp);
+            }
+        }
+
+        report.duplicates = counts
+            .iter()
+            .filter(|(_, &count)| count > 1)
+            .map(|(&timestamp, _)| timestamp)
+            .collect();
+
+        // Walk the interval grid rather than stepping from each candle, so a
+        // misaligned candle (which fills no slot) cannot shift the slots
+        // after it.
+        if let (Some(&first), Some(&last)) = (counts.keys().next(), counts.keys().next_back()) {
+            let mut slot = first.div_euclid(step) * step;
+            while slot <= last {
+                if !counts.contains_key(&slot) {
+                    report.missing.push(slot);
+                }
+                slot += step;
+            }
+        }
+
+        report.out_of_order.sort_unstable();
+        report.out_of_order.dedup();
+        report.zero_volume.sort_unstable();
+        report.zero_volume.dedup();
+        report.misaligned.sort_unstable();
+        report.misaligned.dedup();
+        report
+    }
+
+    /// Sorts the series by timestamp and drops duplicates, keeping the
+    /// *last* candle seen for each timestamp (so a newer fetch appended via
+    /// [`merge`](Self::merge) wins over stale data).
+    pub fn normalize(&mut self) {
+        let mut by_timestamp: BTreeMap<i64, CexCandleView> = BTreeMap::new();
+        for candle in self.candles.drain(..) {
+            by_timestamp.insert(candle.timestamp, candle);
+        }
+        self.candles = by_timestamp.into_values().collect();
+    }
+
+    /// Merges `candles` into the series (they win over existing candles at
+    /// the same timestamp), leaving it normalized.
+    pub fn merge(&mut self, candles: impl IntoIterator<Item = CexCandleView>) {
+        self.candles.extend(candles);
+        self.normalize();
+    }
+
+    /// Fills every missing slot of the interval grid (see
+    /// [`GapReport::missing`]) with a flat candle at the close of the last
+    /// candle before it (`open = high = low = close`) and zero volume,
+    /// leaving the series normalized. A slot before the first candle has no
+    /// previous close and stays missing. Returns the number of candles
+    /// inserted.
+    pub fn fill_forward(&mut self) -> usize {
+        self.normalize();
+        let step = self.interval.millis();
+        let Some(first) = self.candles.first().map(|candle| candle.timestamp) else {
+            return 0;
+        };
+        let occupied: BTreeSet<i64> = self.candles.iter().map(|c| c.timestamp).collect();
+        let mut filled: Vec<CexCandleView> = Vec::with_capacity(self.candles.len());
+        let mut inserted = 0;
+
+        // Step along the grid, as `inspect` does, so slots stay aligned
+        // after a misaligned candle.
+        let mut slot = first.div_euclid(step) * step;
+        for candle in self.candles.drain(..) {
+            while slot < candle.timestamp {
+                if let Some(previous) = filled.last().filter(|_| !occupied.contains(&slot)) {
+                    let close = previous.close;
+                    filled.push(CexCandleView {
+                        close,
+                        timestamp: slot,
+                        high: close,
+                        low: close,
+                        open: close,
+                        volume: 0.0,
+                    });
+                    inserted += 1;
+                }
+                slot += step;
+            }
+            filled.push(candle);
+        }
+
+        self.candles = filled;
+        inserted
+    }
+}
+
+/// Body of the async and blocking `refetch_missing`; see `get_loop!` in [`crate::api`].
+macro_rules! refetch_missing {
+    ($self:expr, $exchange:expr, $symbol:expr, $options:expr, $series:expr $(, $aw:ident)?) => {{
+        let series: &mut CandleSeries = $series;
+        let exchange: String = $exchange.into();
+        let symbol: String = $symbol.into();
+        series.normalize();
+        let deduped = series.candles.len();
+        let interval = series.interval;
+
+        let windows = series.inspect().missing_windows(interval);
+        for (first, last) in windows {
+            let options = missing_window_options($options.clone(), interval, first, last);
+            let response = $self.get(exchange.as_str(), symbol.as_str(), options)$(.$aw)?;
+            let response = response?;
+            series.merge(
+                response
+                    .data
+                    .into_iter()
+                    .filter(|candle| candle.timestamp >= first && candle.timestamp <= last),
+            );
+        }
+
+        Ok(series.candles.len() - deduped)
+    }};
+}
+
+/// `options` narrowed to the `[first, last]` window (unix seconds, rounded
+/// outward) at `interval`.
+fn missing_window_options(
+    options: CexCandleOptions,
+    interval: CexCandleInterval,
+    first: i64,
+    last: i64,
+) -> CexCandleOptions {
+    options
+        .interval(interval)
+        .from(first.div_euclid(1000))
+        .to(last.div_euclid(1000) + i64::from(last.rem_euclid(1000) != 0))
+}
+
+impl crate::generated::async_internal::CexCandle {
+    /// Re-fetches every missing sub-window of `series` (see
+    /// [`GapReport::missing_windows`]) from `exchange`/`symbol`, one request
+    /// per contiguous run of missing candles, and merges the results in.
+    ///
+    /// `options` supplies the market/currency; its interval and `from`/`to`
+    /// are overridden per window. The series is left normalized, and slots
+    /// the exchange genuinely has no data for stay missing (see
+    /// [`CandleSeries::fill_forward`]). Returns the number of candles
+    /// recovered.
+    pub async fn refetch_missing(
+        &self,
+        exchange: impl Into<String>,
+        symbol: impl Into<String>,
+        options: CexCandleOptions,
+        series: &mut CandleSeries,
+    ) -> crate::api::Result<usize> {
+        refetch_missing!(self, exchange, symbol, options, series, await)
+    }
+}
+
+#[cfg(feature = "sync")]
+impl crate::generated::sync_internal::CexCandle {
+    /// Blocking mirror of the async `CexCandle::refetch_missing`.
+    pub fn refetch_missing(
+        &self,
+        exchange: impl Into<String>,
+        symbol: impl Into<String>,
+        options: CexCandleOptions,
+        series: &mut CandleSeries,
+    ) -> crate::api::Result<usize> {
+        refetch_missing!(self, exchange, symbol, options, series)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: i64 = 3_600_000;
+
+    fn candle(timestamp: i64, close: f64, volume: f64) -> CexCandleView {
+        CexCandleView {
+            close,
+            timestamp,
+            high: close,
+            low: close,
+            open: close,
+            volume,
+        }
+    }
+
+    #[test]
+    fn interval_round_trips_wire_value() {
+        assert_eq!(
+            CexCandleInterval::from_wire("4h"),
+            Some(CexCandleInterval::_4h)
+        );
+        assert_eq!(CexCandleInterval::from_wire("2h"), None);
+        assert_eq!(CexCandleInterval::_15m.duration(), Duration::from_secs(900));
+    }
+
+    #[test]
+    fn inspect_reports_every_problem() {
+        let series = CandleSeries::new(
+            CexCandleInterval::_1h,
+            vec![
+                candle(0, 1.0, 1.0),
+                candle(3 * HOUR, 2.0, 0.0),
+                candle(HOUR, 3.0, 1.0),
+                candle(HOUR, 3.0, 1.0),
+                candle(5 * HOUR + 1, 4.0, 1.0),
+            ],
+        );
+
+        let report = series.inspect();
+
+        assert_eq!(report.missing, [2 * HOUR, 4 * HOUR, 5 * HOUR]);
+        assert_eq!(report.duplicates, [HOUR]);
+        assert_eq!(report.out_of_order, [HOUR]);
+        assert_eq!(report.zero_volume, [3 * HOUR]);
+        assert_eq!(report.misaligned, [5 * HOUR + 1]);
+        assert!(!report.is_clean());
+        assert_eq!(
+            report.missing_windows(CexCandleInterval::_1h),
+            [(2 * HOUR, 2 * HOUR), (4 * HOUR, 5 * HOUR)]
+        );
+    }
+
+    #[test]
+    fn misaligned_candle_does_not_shift_later_slots() {
+        let series = CandleSeries::new(
+            CexCandleInterval::_1h,
+            vec![
+                candle(0, 1.0, 1.0),
+                candle(HOUR, 1.0, 1.0),
+                candle(2 * HOUR + HOUR / 2, 1.0, 1.0),
+                candle(5 * HOUR, 1.0, 1.0),
+            ],
+        );
+
+        let report = series.inspect();
+
+        assert_eq!(report.misaligned, [2 * HOUR + HOUR / 2]);
+        assert_eq!(report.missing, [2 * HOUR, 3 * HOUR, 4 * HOUR]);
+    }
+
+    #[test]
+    fn clean_series_with_quiet_bars_is_clean() {
+        let series = CandleSeries::new(
+            CexCandleInterval::_1h,
+            vec![candle(0, 1.0, 0.0), candle(HOUR, 1.0, 2.0)],
+        );
+        assert!(series.inspect().is_clean());
+    }
+
+    #[test]
+    fn normalize_keeps_last_duplicate() {
+        let mut series = CandleSeries::new(
+            CexCandleInterval::_1h,
+            vec![
+                candle(HOUR, 1.0, 1.0),
+                candle(0, 2.0, 1.0),
+                candle(HOUR, 9.0, 1.0),
+            ],
+        );
+        series.normalize();
+        let closes: Vec<f64> = series.candles().iter().map(|c| c.close).collect();
+        assert_eq!(closes, [2.0, 9.0]);
+    }
+
+    #[test]
+    fn fill_forward_uses_previous_close_and_zero_volume() {
+        let mut series = CandleSeries::new(
+            CexCandleInterval::_1h,
+            vec![candle(0, 10.0, 5.0), candle(3 * HOUR, 12.0, 5.0)],
+        );
+
+        assert_eq!(series.fill_forward(), 2);
+
+        let candles = series.candles();
+        assert_eq!(candles.len(), 4);
+        assert_eq!(candles[1].timestamp, HOUR);
+        assert_eq!((candles[1].open, candles[1].close), (10.0, 10.0));
+        assert_eq!(candles[2].volume, 0.0);
+        assert!(series.inspect().missing.is_empty());
+    }
+
+    #[test]
+    fn fill_forward_follows_the_grid_past_a_misaligned_candle() {
+        const MINUTE: i64 = 60_000;
+        let mut series = CandleSeries::new(
+            CexCandleInterval::_1m,
+            vec![
+                candle(0, 1.0, 1.0),
+                candle(90_000, 2.0, 1.0),
+                candle(4 * MINUTE, 3.0, 1.0),
+            ],
+        );
+
+        assert_eq!(series.fill_forward(), 3);
+
+        let slots: Vec<(i64, f64)> = series
+            .candles()
+            .iter()
+            .map(|c| (c.timestamp, c.close))
+            .collect();
+        assert_eq!(
+            slots,
+            [
+                (0, 1.0),
+                (MINUTE, 1.0),
+                (90_000, 2.0),
+                (2 * MINUTE, 2.0),
+                (3 * MINUTE, 2.0),
+                (4 * MINUTE, 3.0)
+            ]
+        );
+        assert!(series.inspect().missing.is_empty());
+    }
+}
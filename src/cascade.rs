@@ -0,0 +1,526 @@
+//! Liquidation cascade detection over polled liquidation events.
+//!
+//! A [`CascadeDetector`](crate::cascade::CascadeDetector) consumes
+//! [`LiquidationFeedEntry`]s from successive polls of
+//! [`Liquidation::feed`](crate::Liquidation::feed) (or [`LiquidationEntry`]s
+//! from [`Liquidation::get`](crate::Liquidation::get)), drops the events a
+//! previous poll already delivered, and sums liquidated USD per token in
+//! fixed time buckets. A bucket raises one
+//! [`CascadeAlert`](crate::cascade::CascadeAlert) the first time its total
+//! clears the absolute floor and at least one relative threshold:
+//!
+//! - a multiple of the token's rolling baseline, the mean of the preceding
+//!   buckets (empty ones count as zero); and
+//! - a fraction of the token's open interest, as last recorded from
+//!   [`OpenInterest::get`](crate::OpenInterest::get) with
+//!   [`record_open_interest`](crate::cascade::CascadeDetector::record_open_interest).
+//!
+//! [`Client::poll_cascades`](crate::Client::poll_cascades) runs one poll.
+//! Timestamps are UTC milliseconds.
+//!
+//! ```
+//! use datamaxi::cascade::{CascadeDetector, Trigger};
+//! use datamaxi::LiquidationFeedEntry;
+//! use std::time::Duration;
+//!
+//! let mut detector = CascadeDetector::new()
+//!     .bucket(Duration::from_secs(60))
+//!     .min_usd(1_000_000.0)
+//!     .baseline_multiple(Some(5.0));
+//!
+//! let event = |timestamp: i64, volume_usd: f64| LiquidationFeedEntry {
+//!     token_id: "bitcoin".into(),
+//!     base: "BTC".into(),
+//!     exchange: "binance".into(),
+//!     symbol: "BTC-USDT".into(),
+//!     side: "long".into(),
+//!     timestamp,
+//!     volume_usd: Some(volume_usd),
+//!     ..Default::default()
+//! };
+//! // A quiet minute, then 1.5M liquidated in the next one.
+//! assert!(detector.ingest(&[event(0, 50_000.0)]).is_empty());
+//! let alerts = detector.ingest(&[event(60_000, 900_000.0), event(61_000, 600_000.0)]);
+//! assert_eq!(alerts.len(), 1);
+//! assert_eq!(alerts[0].total_usd, 1_500_000.0);
+//! assert_eq!(alerts[0].triggers, vec![Trigger::Baseline]);
+//! ```
+//!
+//! [`LiquidationEntry`]: crate::LiquidationEntry
+//! [`LiquidationFeedEntry`]: crate::LiquidationFeedEntry
+
+use crate::api::Result;
+use crate::enums::Side;
+use crate::exchange::Exchange;
+use crate::generated::{
+    LiquidationEntry, LiquidationFeedEntry, LiquidationFeedOptions, OpenInterestResponse,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::time::Duration;
+
+/// A single liquidation, as reported by the feed or per-symbol endpoints.
+pub trait LiquidationEvent {
+    /// Token id the event belongs to (e.g. `bitcoin`).
+    fn token_id(&self) -> &str;
+    /// Base asset (e.g. `BTC`).
+    fn base(&self) -> &str;
+    /// Exchange id.
+    fn exchange(&self) -> &str;
+    /// Exchange symbol.
+    fn symbol(&self) -> &str;
+    /// Which positions were closed.
+    fn side(&self) -> Side;
+    /// Event time, UTC milliseconds.
+    fn timestamp(&self) -> i64;
+    /// Base-asset volume.
+    fn volume(&self) -> f64;
+    /// USD value: the reported one, else volume times the USD price.
+    fn volume_usd(&self) -> Option<f64>;
+}
+
+macro_rules! liquidation_event {
+    ($($ty:ty),* $(,)?) => {$(
+        impl LiquidationEvent for $ty {
+            fn token_id(&self) -> &str {
+                &self.token_id
+            }
+            fn base(&self) -> &str {
+                &self.base
+            }
+            fn exchange(&self) -> &str {
+                &self.exchange
+            }
+            fn symbol(&self) -> &str {
+                &self.symbol
+            }
+            fn side(&self) -> Side {
+                self.side_kind()
+            }
+            fn timestamp(&self) -> i64 {
+                self.timestamp
+            }
+            fn volume(&self) -> f64 {
+                self.volume
+            }
+            fn volume_usd(&self) -> Option<f64> {
+                self.volume_usd
+                    .or_else(|| self.price_usd.map(|price| price * self.volume))
+            }
+        }
+    )*};
+}
+
+liquidation_event!(LiquidationEntry, LiquidationFeedEntry);
+
+/// A threshold a bucket cleared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Trigger {
+    /// The total reached the baseline multiple.
+    Baseline,
+    /// The total reached the open interest fraction.
+    OpenInterest,
+}
+
+/// A burst of liquidations for one token in one bucket.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CascadeAlert {
+    /// Token id (e.g. `bitcoin`).
+    pub token_id: String,
+    /// Base asset, upper-cased.
+    pub base: String,
+    /// Bucket start, UTC milliseconds.
+    pub bucket_start: i64,
+    /// Bucket end (exclusive), UTC milliseconds.
+    pub bucket_end: i64,
+    /// USD liquidated in the bucket so far.
+    pub total_usd: f64,
+    /// USD of long positions liquidated (forced sells).
+    pub long_usd: f64,
+    /// USD of short positions liquidated (forced buys).
+    pub short_usd: f64,
+    /// Number of events in the bucket.
+    pub events: usize,
+    /// Exchanges with events in the bucket, sorted.
+    pub exchanges: Vec<Exchange>,
+    /// Mean USD per bucket over the baseline window.
+    pub baseline_usd: f64,
+    /// `total_usd / baseline_usd`; infinite on a zero baseline.
+    pub baseline_ratio: f64,
+    /// The token's open interest in USD, when recorded.
+    pub open_interest_usd: Option<f64>,
+    /// `total_usd / open_interest_usd`, when recorded.
+    pub oi_fraction: Option<f64>,
+    /// The relative thresholds cleared, in declaration order.
+    pub triggers: Vec<Trigger>,
+}
+
+impl CascadeAlert {
+    /// The side carrying most of the USD: longs on a tie.
+    pub fn dominant_side(&self) -> Side {
+        if self.short_usd > self.long_usd {
+            Side::Short
+        } else {
+            Side::Long
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Bucket {
+    long_usd: f64,
+    short_usd: f64,
+    events: usize,
+    exchanges: BTreeSet<Exchange>,
+    alerted: bool,
+}
+
+impl Bucket {
+    fn total(&self) -> f64 {
+        self.long_usd + self.short_usd
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct TokenState {
+    base: String,
+    buckets: BTreeMap<i64, Bucket>,
+}
+
+type EventKey = (String, String, i64, bool, u64);
+
+/// Per-token liquidation buckets with baseline and open interest thresholds.
+#[derive(Debug, Clone)]
+pub struct CascadeDetector {
+    bucket_ms: i64,
+    baseline_buckets: usize,
+    min_usd: f64,
+    baseline_multiple: Option<f64>,
+    oi_fraction: Option<f64>,
+    tokens: HashMap<String, TokenState>,
+    open_interest: HashMap<String, BTreeMap<(Exchange, String), f64>>,
+    seen: BTreeMap<i64, HashSet<EventKey>>,
+    unknown_sides: usize,
+}
+
+impl Default for CascadeDetector {
+    fn default() -> Self {
+        CascadeDetector::new()
+    }
+}
+
+impl CascadeDetector {
+    /// One-minute buckets, a 60-bucket baseline, a 1M USD floor, a 5x
+    /// baseline multiple and no open interest threshold.
+    pub fn new() -> Self {
+        CascadeDetector {
+            bucket_ms: 60_000,
+            baseline_buckets: 60,
+            min_usd: 1_000_000.0,
+            baseline_multiple: Some(5.0),
+            oi_fraction: None,
+            tokens: HashMap::new(),
+            open_interest: HashMap::new(),
+            seen: BTreeMap::new(),
+            unknown_sides: 0,
+        }
+    }
+
+    /// Bucket width (at least one millisecond).
+    pub fn bucket(mut self, width: Duration) -> Self {
+        self.bucket_ms = (width.as_millis() as i64).max(1);
+        self
+    }
+
+    /// Number of preceding buckets averaged into the baseline (at least one).
+    pub fn baseline_buckets(mut self, buckets: usize) -> Self {
+        self.baseline_buckets = buckets.max(1);
+        self
+    }
+
+    /// USD a bucket must reach before any relative threshold is considered.
+    pub fn min_usd(mut self, usd: f64) -> Self {
+        self.min_usd = usd;
+        self
+    }
+
+    /// Multiple of the baseline that fires [`Trigger::Baseline`]; `None`
+    /// disables it.
+    pub fn baseline_multiple(mut self, multiple: Option<f64>) -> Self {
+        self.baseline_multiple = multiple;
+        self
+    }
+
+    /// Fraction of open interest (e.g. `0.01` for 1%) that fires
+    /// [`Trigger::OpenInterest`]; `None` disables it.
+    pub fn oi_fraction(mut self, fraction: Option<f64>) -> Self {
+        self.oi_fraction = fraction;
+        self
+    }
+
+    /// Records one exchange's open interest for a token, replacing the
+    /// previous snapshot of that symbol. A token's open interest is the sum
+    /// over its recorded symbols; snapshots without a USD value are ignored.
+    pub fn record_open_interest(&mut self, snapshot: &OpenInterestResponse) {
+        if let Some(usd) = snapshot.open_interest_usd {
+            self.open_interest
+                .entry(snapshot.token_id.clone())
+                .or_default()
+                .insert(
+                    (Exchange::new(&snapshot.exchange), snapshot.symbol.clone()),
+                    usd,
+                );
+        }
+    }
+
+    /// The token's recorded open interest in USD.
+    pub fn open_interest_usd(&self, token_id: &str) -> Option<f64> {
+        self.open_interest
+            .get(token_id)
+            .filter(|symbols| !symbols.is_empty())
+            .map(|symbols| symbols.values().sum())
+    }
+
+    /// Events skipped so far because their side is neither long nor short
+    /// (`sell` and `buy` count as long and short, see [`Side`]).
+    pub fn unknown_sides(&self) -> usize {
+        self.unknown_sides
+    }
+
+    /// Adds events and returns an alert for every bucket they pushed over
+    /// the thresholds, at most one per bucket. Events seen before, events
+    /// without a USD value, and events older than the baseline window are
+    /// skipped; events with an unrecognized side are skipped and counted in
+    /// [`unknown_sides`](Self::unknown_sides).
+    pub fn ingest<'a, E, I>(&mut self, events: I) -> Vec<CascadeAlert>
+    where
+        E: LiquidationEvent + 'a,
+        I: IntoIterator<Item = &'a E>,
+    {
+        let mut touched = BTreeSet::new();
+        for event in events {
+            let Some(usd) = event.volume_usd() else {
+                continue;
+            };
+            let start = event.timestamp().div_euclid(self.bucket_ms) * self.bucket_ms;
+            if self
+                .seen
+                .keys()
+                .next()
+                .is_some_and(|&oldest| start < oldest)
+            {
+                continue;
+            }
+            let side = event.side();
+            if !matches!(side, Side::Long | Side::Short) {
+                self.unknown_sides += 1;
+                continue;
+            }
+            let key = (
+                event.exchange().to_ascii_lowercase(),
+                event.symbol().to_owned(),
+                event.timestamp(),
+                side == Side::Long,
+                event.volume().to_bits(),
+            );
+            if !self.seen.entry(start).or_default().insert(key) {
+                continue;
+            }
+            let token = self.tokens.entry(event.token_id().to_owned()).or_default();
+            if token.base.is_empty() {
+                token.base = event.base().to_ascii_uppercase();
+            }
+            let bucket = token.buckets.entry(start).or_default();
+            if side == Side::Long {
+                bucket.long_usd += usd;
+            } else {
+                bucket.short_usd += usd;
+            }
+            bucket.events += 1;
+            bucket.exchanges.insert(Exchange::new(event.exchange()));
+            touched.insert((event.token_id().to_owned(), start));
+        }
+
+        let alerts = touched
+            .into_iter()
+            .filter_map(|(token_id, start)| self.evaluate(&token_id, start))
+            .collect();
+        self.prune();
+        alerts
+    }
+
+    fn evaluate(&mut self, token_id: &str, start: i64) -> Option<CascadeAlert> {
+        let open_interest_usd = self.open_interest_usd(token_id);
+        let token = self.tokens.get_mut(token_id)?;
+        let window_start = start - self.bucket_ms * self.baseline_buckets as i64;
+        let baseline_usd = token
+            .buckets
+            .range(window_start..start)
+            .map(|(_, bucket)| bucket.total())
+            .sum::<f64>()
+            / self.baseline_buckets as f64;
+        let bucket = token.buckets.get_mut(&start)?;
+        let total_usd = bucket.total();
+        if bucket.alerted || total_usd < self.min_usd {
+            return None;
+        }
+
+        let baseline_ratio = if baseline_usd > 0.0 {
+            total_usd / baseline_usd
+        } else {
+            f64::INFINITY
+        };
+        let oi_fraction = open_interest_usd
+            .filter(|&oi| oi > 0.0)
+            .map(|oi| total_usd / oi);
+        let mut triggers = Vec::new();
+        if self.baseline_multiple.is_some_and(|m| baseline_ratio >= m) {
+            triggers.push(Trigger::Baseline);
+        }
+        if let (Some(threshold), Some(fraction)) = (self.oi_fraction, oi_fraction) {
+            if fraction >= threshold {
+                triggers.push(Trigger::OpenInterest);
+            }
+        }
+        if triggers.is_empty() {
+            return None;
+        }
+
+        bucket.alerted = true;
+        Some(CascadeAlert {
+            token_id: token_id.to_owned(),
+            base: token.base.clone(),
+            bucket_start: start,
+            bucket_end: start + self.bucket_ms,
+            total_usd,
+            long_usd: bucket.long_usd,
+            short_usd: bucket.short_usd,
+            events: bucket.events,
+            exchanges: bucket.exchanges.iter().cloned().collect(),
+            baseline_usd,
+            baseline_ratio,
+            open_interest_usd,
+            oi_fraction,
+            triggers,
+        })
+    }
+
+    // Keeps the baseline window behind the newest bucket seen, plus that
+    // bucket.
+    fn prune(&mut self) {
+        let Some(&newest) = self.seen.keys().next_back() else {
+            return;
+        };
+        let cutoff = newest - self.bucket_ms * self.baseline_buckets as i64;
+        self.seen = self.seen.split_off(&cutoff);
+        self.tokens.retain(|_, token| {
+            token.buckets = token.buckets.split_off(&cutoff);
+            !token.buckets.is_empty()
+        });
+    }
+}
+
+/// Body of the async and blocking `poll_cascades`; see `get_loop!` in [`crate::api`].
+macro_rules! poll_cascades {
+    ($client:expr, $detector:expr, $options:expr $(, $aw:ident)?) => {{
+        let response = $client.liquidation().feed($options)$(.$aw)?;
+        let detector: &mut CascadeDetector = $detector;
+        Ok(detector.ingest(&response?.data))
+    }};
+}
+
+impl crate::api::Client {
+    /// Polls [`Liquidation::feed`](crate::Liquidation::feed) once and feeds
+    /// the events to `detector`, returning the alerts raised. Poll at least
+    /// as often as the feed's `limit` events arrive, or bursts are missed.
+    pub async fn poll_cascades(
+        &self,
+        detector: &mut CascadeDetector,
+        options: LiquidationFeedOptions,
+    ) -> Result<Vec<CascadeAlert>> {
+        poll_cascades!(self, detector, options, await)
+    }
+}
+
+#[cfg(feature = "sync")]
+impl crate::api::sync::Client {
+    /// Blocking mirror of the async `Client::poll_cascades`.
+    pub fn poll_cascades(
+        &self,
+        detector: &mut CascadeDetector,
+        options: LiquidationFeedOptions,
+    ) -> Result<Vec<CascadeAlert>> {
+        poll_cascades!(self, detector, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(timestamp: i64, side: &str, volume_usd: f64) -> LiquidationFeedEntry {
+        LiquidationFeedEntry {
+            token_id: "bitcoin".into(),
+            base: "btc".into(),
+            exchange: "bybit".into(),
+            symbol: "BTCUSDT".into(),
+            side: side.into(),
+            timestamp,
+            volume: volume_usd / 100_000.0,
+            volume_usd: Some(volume_usd),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn repeated_polls_do_not_double_count_or_realert() {
+        let mut detector = CascadeDetector::new().min_usd(100.0);
+        let poll = [event(1_000, "long", 80.0), event(2_000, "short", 40.0)];
+
+        let alerts = detector.ingest(&poll);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!((alerts[0].long_usd, alerts[0].short_usd), (80.0, 40.0));
+        assert_eq!(alerts[0].base, "BTC");
+        assert_eq!(alerts[0].dominant_side(), Side::Long);
+        assert!(detector.ingest(&poll).is_empty());
+    }
+
+    #[test]
+    fn baseline_multiple_is_relative_to_preceding_buckets() {
+        let mut detector = CascadeDetector::new()
+            .min_usd(0.0)
+            .baseline_buckets(2)
+            .baseline_multiple(Some(3.0));
+        detector.ingest(&[event(0, "long", 100.0), event(60_000, "long", 100.0)]);
+
+        // Baseline is 100 per bucket: 250 is not enough, 300 is.
+        assert!(detector.ingest(&[event(120_000, "long", 250.0)]).is_empty());
+        let alerts = detector.ingest(&[event(121_000, "short", 50.0)]);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].baseline_usd, 100.0);
+        assert_eq!(alerts[0].baseline_ratio, 3.0);
+    }
+
+    #[test]
+    fn open_interest_trigger_uses_summed_snapshots() {
+        let mut detector = CascadeDetector::new()
+            .min_usd(0.0)
+            .baseline_multiple(None)
+            .oi_fraction(Some(0.01));
+        for (exchange, usd) in [("binance", 6_000.0), ("bybit", 4_000.0)] {
+            detector.record_open_interest(&OpenInterestResponse {
+                exchange: exchange.into(),
+                symbol: "BTCUSDT".into(),
+                token_id: "bitcoin".into(),
+                open_interest_usd: Some(usd),
+                ..Default::default()
+            });
+        }
+
+        assert!(detector.ingest(&[event(0, "long", 99.0)]).is_empty());
+        let alerts = detector.ingest(&[event(1, "long", 1.0)]);
+        assert_eq!(alerts[0].open_interest_usd, Some(10_000.0));
+        assert_eq!(alerts[0].oi_fraction, Some(0.01));
+        assert_eq!(alerts[0].triggers, vec![Trigger::OpenInterest]);
+    }
+}
@@ -0,0 +1,473 @@
+//! Incremental local candle store.
+//!
+//! A [`CandleStore`](crate::dataset::CandleStore) keeps one append-only
+//! JSON-lines file of [`CexCandleView`]s per series under a root directory,
+//! next to a watermark file holding the open time of the newest stored
+//! candle. [`CandleSync`](crate::dataset::CandleSync) brings a series up to
+//! date: it fetches only closed candles after the watermark, re-fetches any
+//! hole left in what came back (see
+//! [`CexCandle::refetch_missing`](crate::CexCandle::refetch_missing)),
+//! appends, and advances the watermark. A blocking mirror for cron jobs lives
+//! in [`dataset::sync`](crate::dataset::sync) (feature `sync`).
+//!
+//! Layout, with path components reduced to `[A-Za-z0-9._-]`:
+//!
+//! ```text
+//! <root>/<exchange>/<market>/<symbol>/<interval>.jsonl      one candle per line
+//! <root>/<exchange>/<market>/<symbol>/<interval>.watermark  newest stored open time (ms)
+//! ```
+//!
+//! ```no_run
+//! use datamaxi::dataset::{CandleStore, CandleSync, CandleSyncOptions, SeriesKey};
+//! use datamaxi::{CexCandleInterval, CexCandleSymbolsOptions, Client};
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let candle = Client::new("my_api_key").cex_candle();
+//! let keys: Vec<SeriesKey> = candle
+//!     .symbols("binance", CexCandleSymbolsOptions::new())
+//!     .await?
+//!     .iter()
+//!     .filter_map(|symbol| SeriesKey::from_symbol(symbol, CexCandleInterval::_1h))
+//!     .collect();
+//!
+//! let engine = CandleSync::new(
+//!     candle,
+//!     CandleStore::open("./candles")?,
+//!     CandleSyncOptions::new(1_704_067_200_000),
+//! );
+//! for (key, result) in engine.sync_all(&keys).await {
+//!     match result {
+//!         Ok(report) => println!("{}: +{}", key.symbol, report.appended),
+//!         Err(err) => eprintln!("{}: {err}", key.symbol),
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::candles::CandleSeries;
+use crate::generated::async_internal::CexCandle;
+use crate::generated::{
+    CexCandleCurrency, CexCandleInterval, CexCandleMarket, CexCandleOptions, CexCandleSymbolsView,
+    CexCandleView,
+};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+/// Errors returned by the candle store and sync engine.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum Error {
+    /// Fetching candles failed.
+    #[error(transparent)]
+    Api(#[from] crate::api::Error),
+
+    /// Reading or writing the store failed.
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    /// A stored file could not be decoded.
+    #[error("corrupt store file {path}: {source}")]
+    Corrupt {
+        /// The offending file.
+        path: PathBuf,
+        /// What was wrong with it.
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+}
+
+/// Result alias for the candle store and sync engine.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Identifies one stored candle series.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeriesKey {
+    /// Exchange id, e.g. `binance`.
+    pub exchange: String,
+    /// Symbol as the candle endpoint takes it, e.g. `BTC-USDT`.
+    pub symbol: String,
+    /// Spot or futures.
+    pub market: CexCandleMarket,
+    /// Candle interval.
+    pub interval: CexCandleInterval,
+}
+
+impl SeriesKey {
+    /// A key for `exchange`/`symbol` on `market` at `interval`.
+    pub fn new(
+        exchange: impl Into<String>,
+        symbol: impl Into<String>,
+        market: CexCandleMarket,
+        interval: CexCandleInterval,
+    ) -> Self {
+        SeriesKey {
+            exchange: exchange.into(),
+            symbol: symbol.into(),
+            market,
+            interval,
+        }
+    }
+
+    /// A key for an entry of `CexCandle::symbols` at `interval`, or `None` if
+    /// its market is neither `spot` nor `futures`.
+    pub fn from_symbol(symbol: &CexCandleSymbolsView, interval: CexCandleInterval) -> Option<Self> {
+        let market = match symbol.market.as_str() {
+            "spot" => CexCandleMarket::Spot,
+            "futures" => CexCandleMarket::Futures,
+            _ => return None,
+        };
+        Some(SeriesKey::new(
+            symbol.exchange.as_str(),
+            symbol.symbol.as_str(),
+            market,
+            interval,
+        ))
+    }
+}
+
+/// An on-disk candle store; see the [module docs](self) for the layout.
+#[derive(Debug, Clone)]
+pub struct CandleStore {
+    root: PathBuf,
+}
+
+impl CandleStore {
+    /// Opens (creating if needed) the store rooted at `root`.
+    pub fn open(root: impl Into<PathBuf>) -> Result<Self> {
+        let root = root.into();
+        fs::create_dir_all(&root)?;
+        Ok(CandleStore { root })
+    }
+
+    /// The store's root directory.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Open time (UTC ms) of the newest stored candle of `key`, or `None` if
+    /// nothing has been stored yet.
+    pub fn watermark(&self, key: &SeriesKey) -> Result<Option<i64>> {
+        let path = self.path(key, "watermark");
+        match fs::read_to_string(&path) {
+            Ok(text) => text.trim().parse().map(Some).map_err(|err| Error::Corrupt {
+                path,
+                source: Box::new(err),
+            }),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Every stored candle of `key`, sorted and de-duplicated (an interrupted
+    /// sync can leave a re-appended tail; the later copy wins).
+    pub fn load(&self, key: &SeriesKey) -> Result<Vec<CexCandleView>> {
+        let path = self.path(key, "jsonl");
+        let file = match File::open(&path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut candles = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let candle = serde_json::from_str(&line).map_err(|err| Error::Corrupt {
+                path: path.clone(),
+                source: Box::new(err),
+            })?;
+            candles.push(candle);
+        }
+
+        let mut series = CandleSeries::new(key.interval, candles);
+        series.normalize();
+        Ok(series.into_candles())
+    }
+
+    /// Appends `candles` (expected newer than the watermark, in order) and
+    /// moves the watermark to the last one.
+    pub fn append(&self, key: &SeriesKey, candles: &[CexCandleView]) -> Result<()> {
+        let Some(last) = candles.last() else {
+            return Ok(());
+        };
+        let path = self.path(key, "jsonl");
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let mut writer = BufWriter::new(file);
+        for candle in candles {
+            serde_json::to_writer(&mut writer, candle).map_err(io::Error::from)?;
+            writer.write_all(b"\n")?;
+        }
+        writer
+            .into_inner()
+            .map_err(|err| err.into_error())?
+            .sync_data()?;
+
+        // The data is durable before the watermark moves; a crash in between
+        // only causes the tail to be fetched and appended again.
+        let watermark = self.path(key, "watermark");
+        let temporary = watermark.with_extension("watermark.tmp");
+        fs::write(&temporary, last.timestamp.to_string())?;
+        fs::rename(&temporary, &watermark)?;
+        Ok(())
+    }
+
+    fn path(&self, key: &SeriesKey, extension: &str) -> PathBuf {
+        self.root
+            .join(path_component(&key.exchange))
+            .join(key.market.as_str())
+            .join(path_component(&key.symbol))
+            .join(format!("{}.{extension}", key.interval.as_str()))
+    }
+}
+
+fn path_component(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Options for [`CandleSync`].
+#[derive(Debug, Clone)]
+pub struct CandleSyncOptions {
+    backfill_from: i64,
+    until: Option<i64>,
+    candles_per_request: i64,
+    currency: Option<CexCandleCurrency>,
+}
+
+impl CandleSyncOptions {
+    /// Series with no watermark are backfilled from `backfill_from` (UTC
+    /// ms, rounded up to the interval grid).
+    pub fn new(backfill_from: i64) -> Self {
+        CandleSyncOptions {
+            backfill_from,
+            until: None,
+            candles_per_request: 1000,
+            currency: None,
+        }
+    }
+
+    /// Syncs only candles closed by `until` (UTC ms) instead of by now.
+    pub fn until(mut self, until: i64) -> Self {
+        self.until = Some(until);
+        self
+    }
+
+    /// Caps each request's `from`/`to` window at `count` candles (1000 by
+    /// default).
+    pub fn candles_per_request(mut self, count: i64) -> Self {
+        self.candles_per_request = count.max(1);
+        self
+    }
+
+    /// Quote currency passed to every candle request.
+    pub fn currency(mut self, currency: CexCandleCurrency) -> Self {
+        self.currency = Some(currency);
+        self
+    }
+
+    fn base_options(&self, key: &SeriesKey) -> CexCandleOptions {
+        let options = CexCandleOptions::new()
+            .market(key.market)
+            .interval(key.interval);
+        match self.currency {
+            Some(currency) => options.currency(currency),
+            None => options,
+        }
+    }
+}
+
+/// What one [`CandleSync::sync_series`] call did.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SeriesReport {
+    /// Candles appended to the store.
+    pub appended: usize,
+    /// Of those, candles recovered by re-fetching a hole.
+    pub repaired: usize,
+    /// Slots still missing inside the appended range after repair (the
+    /// exchange has no data for them).
+    pub missing: Vec<i64>,
+    /// The watermark after the sync.
+    pub watermark: Option<i64>,
+}
+
+/// Body of the async and blocking series sync; see `get_loop!` in [`crate::api`].
+macro_rules! sync_series {
+    ($candle:expr, $store:expr, $options:expr, $key:expr $(, $aw:ident)?) => {{
+        let store: &CandleStore = $store;
+        let options: &CandleSyncOptions = $options;
+        let key: &SeriesKey = $key;
+        let step = key.interval.duration().as_millis() as i64;
+
+        let watermark = store.watermark(key)?;
+        let first = match watermark {
+            Some(watermark) => watermark + step,
+            None => options.backfill_from + (-options.backfill_from).rem_euclid(step),
+        };
+        // Open time of the newest candle fully closed by `until`.
+        let last = (options.until.unwrap_or_else(now_millis) / step - 1) * step;
+
+        let mut fetched = Vec::new();
+        let mut cursor = first;
+        while cursor <= last {
+            let stop = last.min(cursor + (options.candles_per_request - 1) * step);
+            let request = options
+                .base_options(key)
+                .from(cursor.div_euclid(1000))
+                .to(stop.div_euclid(1000) + i64::from(stop.rem_euclid(1000) != 0));
+            let response = $candle.get(key.exchange.as_str(), key.symbol.as_str(), request)$(.$aw)?;
+            let response = response?;
+            fetched.extend(
+                response
+                    .data
+                    .into_iter()
+                    .filter(|candle| candle.timestamp >= cursor && candle.timestamp <= stop),
+            );
+            cursor = stop + step;
+        }
+
+        // A placeholder at the watermark makes a hole right after it an
+        // interior gap, so it is repaired like any other.
+        let mut series = CandleSeries::new(key.interval, fetched);
+        if let Some(watermark) = watermark {
+            series.merge([CexCandleView {
+                timestamp: watermark,
+                ..CexCandleView::default()
+            }]);
+        }
+        let repaired = $candle.refetch_missing(
+            key.exchange.as_str(),
+            key.symbol.as_str(),
+            options.base_options(key),
+            &mut series,
+        )$(.$aw)?;
+        let repaired = repaired?;
+        let missing = series.inspect().missing;
+
+        let appended: Vec<CexCandleView> = series
+            .into_candles()
+            .into_iter()
+            .filter(|candle| watermark.is_none_or(|watermark| candle.timestamp > watermark))
+            .collect();
+        store.append(key, &appended)?;
+
+        Ok(SeriesReport {
+            appended: appended.len(),
+            repaired,
+            missing,
+            watermark: appended.last().map(|candle| candle.timestamp).or(watermark),
+        })
+    }};
+}
+
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+/// Incremental sync engine over a [`CandleStore`].
+#[derive(Clone)]
+pub struct CandleSync {
+    candle: CexCandle,
+    store: CandleStore,
+    options: CandleSyncOptions,
+}
+
+impl CandleSync {
+    /// An engine fetching through `candle` (e.g. `client.cex_candle()`) into
+    /// `store`.
+    pub fn new(candle: CexCandle, store: CandleStore, options: CandleSyncOptions) -> Self {
+        CandleSync {
+            candle,
+            store,
+            options,
+        }
+    }
+
+    /// The store being synced.
+    pub fn store(&self) -> &CandleStore {
+        &self.store
+    }
+
+    /// Fetches the closed candles of `key` newer than its watermark, repairs
+    /// holes among them, appends them and advances the watermark.
+    pub async fn sync_series(&self, key: &SeriesKey) -> Result<SeriesReport> {
+        sync_series!(self.candle, &self.store, &self.options, key, await)
+    }
+
+    /// Syncs every series in `keys`, one after another. A failing series does
+    /// not stop the others.
+    pub async fn sync_all(&self, keys: &[SeriesKey]) -> Vec<(SeriesKey, Result<SeriesReport>)> {
+        let mut results = Vec::with_capacity(keys.len());
+        for key in keys {
+            results.push((key.clone(), self.sync_series(key).await));
+        }
+        results
+    }
+}
+
+/// Blocking mirror of the sync engine, for cron jobs (feature `sync`).
+#[cfg(feature = "sync")]
+#[cfg_attr(docsrs, doc(cfg(feature = "sync")))]
+pub mod sync {
+    use super::{now_millis, CandleStore, CandleSyncOptions, Result, SeriesKey, SeriesReport};
+    use crate::candles::CandleSeries;
+    use crate::generated::sync_internal::CexCandle;
+    use crate::generated::CexCandleView;
+
+    /// Blocking mirror of [`CandleSync`](super::CandleSync).
+    #[derive(Clone)]
+    pub struct CandleSync {
+        candle: CexCandle,
+        store: CandleStore,
+        options: CandleSyncOptions,
+    }
+
+    impl CandleSync {
+        /// An engine fetching through `candle` (e.g. the blocking
+        /// `client.cex_candle()`) into `store`.
+        pub fn new(candle: CexCandle, store: CandleStore, options: CandleSyncOptions) -> Self {
+            CandleSync {
+                candle,
+                store,
+                options,
+            }
+        }
+
+        /// The store being synced.
+        pub fn store(&self) -> &CandleStore {
+            &self.store
+        }
+
+        /// Blocking mirror of [`CandleSync::sync_series`](super::CandleSync::sync_series).
+        pub fn sync_series(&self, key: &SeriesKey) -> Result<SeriesReport> {
+            sync_series!(self.candle, &self.store, &self.options, key)
+        }
+
+        /// Blocking mirror of [`CandleSync::sync_all`](super::CandleSync::sync_all).
+        pub fn sync_all(&self, keys: &[SeriesKey]) -> Vec<(SeriesKey, Result<SeriesReport>)> {
+            keys.iter()
+                .map(|key| (key.clone(), self.sync_series(key)))
+                .collect()
+        }
+    }
+}
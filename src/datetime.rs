@@ -0,0 +1,337 @@
+//! Typed timestamps for models and option builders.
+//!
+//! Models carry instants as UTC milliseconds (`i64`), and time-bounded option
+//! builders take raw `from`/`to` in whatever unit their endpoint uses. This
+//! module adds:
+//!
+//! - [`UnixMillis`](crate::datetime::UnixMillis), implemented for
+//!   [`SystemTime`](std::time::SystemTime) and, behind the `chrono` / `time` features, for
+//!   `chrono::DateTime<Tz>` and `time::OffsetDateTime`;
+//! - `since` / `until` on every time-bounded option builder, taking any
+//!   [`UnixMillis`](crate::datetime::UnixMillis) and converting to the
+//!   endpoint's unit, plus `last(Duration)` for ranges ending now;
+//! - with `chrono`, a `<field>_datetime()` accessor returning
+//!   `DateTime<Utc>` for every millisecond timestamp field of every model
+//!   (`CexCandleView::timestamp_datetime`, `TelegramMessagesView::published_at_datetime`, …);
+//! - with `time`, the matching `<field>_offset_datetime()` returning
+//!   `OffsetDateTime` (UTC offset).
+//!
+//! Accessors return `None` when an optional field is absent or the value is
+//! outside the target type's range.
+//!
+//! ```
+//! use datamaxi::CexCandleOptions;
+//! use std::time::{Duration, SystemTime};
+//!
+//! let last_week = CexCandleOptions::new().last(Duration::from_secs(7 * 86_400));
+//! let pinned = CexCandleOptions::new()
+//!     .since(SystemTime::UNIX_EPOCH + Duration::from_secs(1_735_657_200))
+//!     .until(SystemTime::UNIX_EPOCH + Duration::from_secs(1_735_693_200));
+//! assert_eq!((pinned.from, pinned.to), (Some(1_735_657_200), Some(1_735_693_200)));
+//! ```
+
+use crate::generated::*;
+#[cfg(any(feature = "chrono", feature = "time"))]
+use crate::models::OpenInterestHistoryPoint;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// An instant convertible to UTC milliseconds since the Unix epoch.
+pub trait UnixMillis {
+    /// Milliseconds since 1970-01-01T00:00:00Z (negative before it).
+    fn unix_millis(&self) -> i64;
+}
+
+impl UnixMillis for SystemTime {
+    fn unix_millis(&self) -> i64 {
+        match self.duration_since(UNIX_EPOCH) {
+            Ok(after) => i64::try_from(after.as_millis()).unwrap_or(i64::MAX),
+            Err(before) => i64::try_from(before.duration().as_millis())
+                .map(|millis| -millis)
+                .unwrap_or(i64::MIN),
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> UnixMillis for chrono::DateTime<Tz> {
+    fn unix_millis(&self) -> i64 {
+        self.timestamp_millis()
+    }
+}
+
+#[cfg(feature = "time")]
+impl UnixMillis for time::OffsetDateTime {
+    fn unix_millis(&self) -> i64 {
+        let millis = self.unix_timestamp_nanos().div_euclid(1_000_000);
+        i64::try_from(millis).unwrap_or(if millis < 0 { i64::MIN } else { i64::MAX })
+    }
+}
+
+/// Adds `since` / `until` / `last` to option builders whose `from`/`to`
+/// fields are in `unit` (`seconds` or `millis`).
+macro_rules! impl_range_builder {
+    (@from seconds, $millis:expr) => { $millis.div_euclid(1000) };
+    (@to seconds, $millis:expr) => { -(-$millis).div_euclid(1000) };
+    (@from millis, $millis:expr) => { $millis };
+    (@to millis, $millis:expr) => { $millis };
+    ($($options:ident { $from:ident, $to:ident, $unit:ident })*) => {$(
+        impl $options {
+            #[doc = concat!("Sets `", stringify!($from), "` to `start`, rounded down to the endpoint's unit.")]
+            pub fn since(mut self, start: impl UnixMillis) -> Self {
+                self.$from = Some(impl_range_builder!(@from $unit, start.unix_millis()));
+                self
+            }
+
+            #[doc = concat!("Sets `", stringify!($to), "` to `end`, rounded up to the endpoint's unit.")]
+            pub fn until(mut self, end: impl UnixMillis) -> Self {
+                self.$to = Some(impl_range_builder!(@to $unit, end.unix_millis()));
+                self
+            }
+
+            /// Sets the range to the `length` ending now.
+            pub fn last(self, length: Duration) -> Self {
+                let now = SystemTime::now();
+                self.since(now.checked_sub(length).unwrap_or(UNIX_EPOCH)).until(now)
+            }
+        }
+    )*};
+}
+
+impl_range_builder! {
+    CexCandleOptions { from, to, seconds }
+    FundingRateHistoryOptions { from, to, seconds }
+    IndexPriceOptions { from, to, seconds }
+    OpenInterestHistoryAggregatedOptions { from, to, millis }
+    CexSymbolDelistingsOptions { from_ms, to_ms, millis }
+}
+
+/// A millisecond timestamp field, required or optional.
+#[cfg(any(feature = "chrono", feature = "time"))]
+trait MillisField {
+    fn millis(&self) -> Option<i64>;
+}
+
+#[cfg(any(feature = "chrono", feature = "time"))]
+impl MillisField for i64 {
+    fn millis(&self) -> Option<i64> {
+        Some(*self)
+    }
+}
+
+#[cfg(any(feature = "chrono", feature = "time"))]
+impl MillisField for Option<i64> {
+    fn millis(&self) -> Option<i64> {
+        *self
+    }
+}
+
+#[cfg(feature = "time")]
+fn offset_datetime(millis: i64) -> Option<time::OffsetDateTime> {
+    time::OffsetDateTime::from_unix_timestamp_nanos(i128::from(millis) * 1_000_000).ok()
+}
+
+/// Adds `<field>_datetime()` (feature `chrono`) and
+/// `<field>_offset_datetime()` (feature `time`) for each listed millisecond
+/// timestamp field.
+#[cfg(any(feature = "chrono", feature = "time"))]
+macro_rules! impl_datetime_accessors {
+    ($($model:ident { $($field:ident => $chrono:ident, $time:ident;)* })*) => {$(
+        impl $model {
+            $(
+                #[cfg(feature = "chrono")]
+                #[cfg_attr(docsrs, doc(cfg(feature = "chrono")))]
+                #[doc = concat!("[`", stringify!($field), "`](Self::", stringify!($field), ") as a `chrono` UTC date-time.")]
+                pub fn $chrono(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+                    chrono::DateTime::from_timestamp_millis(self.$field.millis()?)
+                }
+
+                #[cfg(feature = "time")]
+                #[cfg_attr(docsrs, doc(cfg(feature = "time")))]
+                #[doc = concat!("[`", stringify!($field), "`](Self::", stringify!($field), ") as a `time` date-time at UTC.")]
+                pub fn $time(&self) -> Option<time::OffsetDateTime> {
+                    offset_datetime(self.$field.millis()?)
+                }
+            )*
+        }
+    )*};
+}
+
+#[cfg(any(feature = "chrono", feature = "time"))]
+impl_datetime_accessors! {
+    OpenInterestHistoryPoint {
+        timestamp => timestamp_datetime, timestamp_offset_datetime;
+    }
+    CexAnnouncementsView {
+        timestamp => timestamp_datetime, timestamp_offset_datetime;
+    }
+    CexCandleView {
+        timestamp => timestamp_datetime, timestamp_offset_datetime;
+    }
+    CexSymbolCautionsView {
+        end_at => end_at_datetime, end_at_offset_datetime;
+    }
+    CexSymbolDelistingsView {
+        delisting_at => delisting_at_datetime, delisting_at_offset_datetime;
+        listed_at => listed_at_datetime, listed_at_offset_datetime;
+    }
+    CexSymbolMetadataView {
+        caution_end_at => caution_end_at_datetime, caution_end_at_offset_datetime;
+        delisting_at => delisting_at_datetime, delisting_at_offset_datetime;
+        listed_at => listed_at_datetime, listed_at_offset_datetime;
+    }
+    CexSymbolOiStatsView {
+        ts => ts_datetime, ts_offset_datetime;
+    }
+    CexSymbolOiView {
+        ts => ts_datetime, ts_offset_datetime;
+    }
+    CexSymbolVolumeView {
+        ts => ts_datetime, ts_offset_datetime;
+    }
+    CexTokenUpdatesView {
+        timestamp => timestamp_datetime, timestamp_offset_datetime;
+    }
+    ForexResponse {
+        timestamp => timestamp_datetime, timestamp_offset_datetime;
+    }
+    FundingRateHistoryView {
+        timestamp => timestamp_datetime, timestamp_offset_datetime;
+    }
+    FundingRateLatestResponse {
+        timestamp => timestamp_datetime, timestamp_offset_datetime;
+    }
+    IndexPriceView {
+        timestamp => timestamp_datetime, timestamp_offset_datetime;
+    }
+    LiquidationEntry {
+        timestamp => timestamp_datetime, timestamp_offset_datetime;
+    }
+    LiquidationFeedEntry {
+        timestamp => timestamp_datetime, timestamp_offset_datetime;
+    }
+    LiquidationHeatmapResponse {
+        generated_at => generated_at_datetime, generated_at_offset_datetime;
+    }
+    LiquidationMapResponse {
+        generated_at => generated_at_datetime, generated_at_offset_datetime;
+    }
+    LiquidationStatsResponse {
+        generated_at => generated_at_datetime, generated_at_offset_datetime;
+    }
+    LiquidationSymbolHistoryBucket {
+        ts => ts_datetime, ts_offset_datetime;
+    }
+    LiquidationSymbolHistoryResponse {
+        generated_at => generated_at_datetime, generated_at_offset_datetime;
+    }
+    ListingsHistoricalView {
+        announced_at => announced_at_datetime, announced_at_offset_datetime;
+        deposit_at => deposit_at_datetime, deposit_at_offset_datetime;
+        trade_at => trade_at_datetime, trade_at_offset_datetime;
+    }
+    NaverTrendView {
+        timestamp => timestamp_datetime, timestamp_offset_datetime;
+    }
+    OpenInterestListEntry {
+        timestamp => timestamp_datetime, timestamp_offset_datetime;
+    }
+    OpenInterestResponse {
+        timestamp => timestamp_datetime, timestamp_offset_datetime;
+    }
+    OpenInterestSummaryResponse {
+        generated_at => generated_at_datetime, generated_at_offset_datetime;
+    }
+    PremiumDetail {
+        d => d_datetime, d_offset_datetime;
+        sfrt => sfrt_datetime, sfrt_offset_datetime;
+        snd => snd_datetime, snd_offset_datetime;
+        st => st_datetime, st_offset_datetime;
+        tfrt => tfrt_datetime, tfrt_offset_datetime;
+        tnd => tnd_datetime, tnd_offset_datetime;
+        tt => tt_datetime, tt_offset_datetime;
+    }
+    TelegramChannelsView {
+        created_at => created_at_datetime, created_at_offset_datetime;
+    }
+    TelegramMessagesView {
+        published_at => published_at_datetime, published_at_offset_datetime;
+    }
+    TickerView {
+        timestamp => timestamp_datetime, timestamp_offset_datetime;
+    }
+    WalletStatusView {
+        updated_at => updated_at_datetime, updated_at_offset_datetime;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JAN_1_2025_MS: i64 = 1_735_689_600_000;
+
+    fn at(millis: i64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_millis(millis as u64)
+    }
+
+    #[test]
+    fn builders_convert_to_each_endpoint_unit() {
+        let start = at(JAN_1_2025_MS + 1);
+        let end = at(JAN_1_2025_MS + 1);
+
+        let seconds = CexCandleOptions::new().since(start).until(end);
+        assert_eq!(seconds.from, Some(1_735_689_600));
+        assert_eq!(seconds.to, Some(1_735_689_601));
+
+        let millis = CexSymbolDelistingsOptions::new().since(start).until(end);
+        assert_eq!(millis.from_ms, Some(JAN_1_2025_MS + 1));
+        assert_eq!(millis.to_ms, Some(JAN_1_2025_MS + 1));
+    }
+
+    #[test]
+    fn last_spans_the_requested_length() {
+        let options = OpenInterestHistoryAggregatedOptions::new().last(Duration::from_secs(60));
+        let (from, to) = (options.from.expect("set"), options.to.expect("set"));
+        assert_eq!(to - from, 60_000);
+    }
+
+    #[test]
+    fn system_time_before_epoch_is_negative() {
+        assert_eq!((UNIX_EPOCH - Duration::from_millis(5)).unix_millis(), -5);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_accessors_and_builders() {
+        use chrono::{TimeZone, Utc};
+
+        let candle = CexCandleView {
+            timestamp: JAN_1_2025_MS,
+            ..Default::default()
+        };
+        let expected = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        assert_eq!(candle.timestamp_datetime(), Some(expected));
+        assert_eq!(ListingsHistoricalView::default().trade_at_datetime(), None);
+        assert_eq!(
+            IndexPriceOptions::new().since(expected).from,
+            Some(JAN_1_2025_MS / 1000)
+        );
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time_accessors_and_builders() {
+        let candle = CexCandleView {
+            timestamp: JAN_1_2025_MS,
+            ..Default::default()
+        };
+        let expected = time::OffsetDateTime::from_unix_timestamp(JAN_1_2025_MS / 1000).unwrap();
+        assert_eq!(candle.timestamp_offset_datetime(), Some(expected));
+        assert_eq!(
+            OpenInterestHistoryAggregatedOptions::new()
+                .until(expected)
+                .to,
+            Some(JAN_1_2025_MS)
+        );
+    }
+}
@@ -0,0 +1,449 @@
+//! Exact decimal values of prices, volumes, rates and fees (feature
+//! `rust_decimal`).
+//!
+//! The models decode JSON numbers as `f64`, which is convenient for analysis
+//! but accumulates binary rounding error in PnL and fee accounting, and a
+//! literal with more significant digits than an `f64` holds is already
+//! rounded once it is decoded. This module mirrors every model with `f64`
+//! fields under the same name
+//! ([`decimal::CexCandleView`](crate::decimal::CexCandleView),
+//! [`decimal::CexFeesView`](crate::decimal::CexFeesView),
+//! [`decimal::TickerView`](crate::decimal::TickerView), …). A mirror has the
+//! same field names, each an `Option<Decimal>` parsed from the number's JSON
+//! text (see [`parse_decimal`](crate::decimal::parse_decimal)), and the
+//! nested models that hold such fields; nothing else.
+//!
+//! [`Exact<T>`](crate::decimal::Exact) decodes one response body into both
+//! the model and its mirror, so the exact values can be fetched through
+//! [`Client::get`](crate::Client::get) with the endpoint's path and query:
+//!
+//! ```no_run
+//! use datamaxi::decimal::Exact;
+//! use datamaxi::{CexCandleResponse, Client};
+//! use std::collections::BTreeMap;
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = Client::new("my_api_key");
+//! let query = BTreeMap::from([
+//!     ("exchange".to_string(), "binance".to_string()),
+//!     ("symbol".to_string(), "BTC-USDT".to_string()),
+//! ]);
+//! let candles: Exact<CexCandleResponse> =
+//!     client.get("/api/v1/cex/candle", Some(query)).await?;
+//! for (candle, exact) in candles.value.data.iter().zip(&candles.decimals.data) {
+//!     println!("{}: {:?}", candle.timestamp, exact.close);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! A mirror can also be decoded on its own from text already at hand:
+//!
+//! ```
+//! use datamaxi::decimal::{CexFeesView, Decimal};
+//!
+//! let fees: CexFeesView =
+//!     serde_json::from_str(r#"{"spot_maker_fee":0.001,"spot_take_fee":null}"#).unwrap();
+//! let notional = Decimal::from(25_000);
+//! assert_eq!(fees.spot_maker_fee.map(|fee| fee * notional), Some(Decimal::from(25)));
+//! assert_eq!(fees.spot_take_fee, None);
+//! ```
+
+use serde::de::{DeserializeOwned, Error as _};
+use serde::{Deserialize, Deserializer};
+use serde_json::value::RawValue;
+
+/// Re-exported so callers can name the field type without depending on
+/// `rust_decimal` directly.
+pub use rust_decimal::Decimal;
+
+/// Parses the text of a JSON number, plain (`0.1`) or in exponent form
+/// (`1e-8`), into the [`Decimal`] it denotes, without going through `f64`.
+///
+/// Returns `None` when `Decimal` cannot hold the value exactly: more than
+/// 28 fractional digits (e.g. `1e-30`), or beyond about ±7.9e28. The value
+/// is never rounded.
+pub fn parse_decimal(text: &str) -> Option<Decimal> {
+    if text.contains(['e', 'E']) {
+        Decimal::from_scientific(text).ok()
+    } else {
+        Decimal::from_str_exact(text).ok()
+    }
+}
+
+/// Decodes a number field from its raw JSON text: `None` when the field is
+/// `null` or [`parse_decimal`] cannot hold it, an error when it is not a
+/// number.
+fn exact<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Decimal>, D::Error> {
+    let Some(raw) = Option::<Box<RawValue>>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    let text = raw.get();
+    if !text.starts_with(|c: char| c == '-' || c.is_ascii_digit()) {
+        return Err(D::Error::custom(format!("expected a number, found {text}")));
+    }
+    Ok(parse_decimal(text))
+}
+
+/// Links a model to its decimal mirror in this module.
+pub trait HasDecimals {
+    /// The mirror with the model's `f64` fields as exact decimals.
+    type Decimals: DeserializeOwned;
+}
+
+/// A model and its decimal mirror, decoded from the same JSON text.
+#[derive(Debug, Clone)]
+pub struct Exact<T: HasDecimals> {
+    /// The model, with `f64` fields.
+    pub value: T,
+    /// The same fields as exact decimals.
+    pub decimals: T::Decimals,
+}
+
+impl<'de, T: HasDecimals + DeserializeOwned> Deserialize<'de> for Exact<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = Box::<RawValue>::deserialize(deserializer)?;
+        Ok(Exact {
+            value: serde_json::from_str(raw.get()).map_err(D::Error::custom)?,
+            decimals: serde_json::from_str(raw.get()).map_err(D::Error::custom)?,
+        })
+    }
+}
+
+impl<T: HasDecimals> HasDecimals for Vec<T> {
+    type Decimals = Vec<T::Decimals>;
+}
+
+/// Defines the decimal mirror of each listed model: its number fields,
+/// then, after `;`, the nested fields holding other mirrors.
+macro_rules! decimal_models {
+    ($($model:ident {
+        $($field:ident: $wire:literal,)*
+        $(; $($nested:ident: $nested_wire:literal => $nested_ty:ty,)*)?
+    })*) => {$(
+        #[doc = concat!("The exact decimals of [`", stringify!($model), "`](crate::", stringify!($model), ").")]
+        #[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+        #[non_exhaustive]
+        pub struct $model {
+            $(
+                #[doc = concat!("[`", stringify!($model), "::", stringify!($field), "`](crate::", stringify!($model), "::", stringify!($field), "), exactly; `None` when absent, `null` or out of range.")]
+                #[serde(rename = $wire, default, deserialize_with = "exact")]
+                pub $field: Option<Decimal>,
+            )*
+            $($(
+                #[doc = concat!("[`", stringify!($model), "::", stringify!($nested), "`](crate::", stringify!($model), "::", stringify!($nested), ").")]
+                #[serde(rename = $nested_wire, default)]
+                pub $nested: $nested_ty,
+            )*)?
+        }
+
+        impl HasDecimals for crate::generated::$model {
+            type Decimals = $model;
+        }
+    )*};
+}
+
+decimal_models! {
+    CexCandleResponse {
+        ;
+        data: "data" => Vec<CexCandleView>,
+    }
+    CexCandleView {
+        close: "c",
+        high: "h",
+        low: "l",
+        open: "o",
+        volume: "v",
+    }
+    CexFeesView {
+        futures_maker_fee: "futures_maker_fee",
+        futures_taker_fee: "futures_taker_fee",
+        spot_maker_fee: "spot_maker_fee",
+        spot_take_fee: "spot_take_fee",
+    }
+    CexSymbolLiquidationView {
+        long_volume: "long_volume",
+        long_volume_usd: "long_volume_usd",
+        short_volume: "short_volume",
+        short_volume_usd: "short_volume_usd",
+        total_volume: "total_volume",
+        total_volume_usd: "total_volume_usd",
+    }
+    CexSymbolOiStatsView {
+        change_1h: "change_1h",
+        change_24h: "change_24h",
+        change_4h: "change_4h",
+        oi_to_vol_ratio: "oi_to_vol_ratio",
+        open_interest: "open_interest",
+        open_interest_usd: "open_interest_usd",
+        volume_24h_usd: "volume_24h_usd",
+    }
+    CexSymbolOiView {
+        open_interest: "open_interest",
+        open_interest_usd: "open_interest_usd",
+    }
+    CexSymbolVolumeView {
+        quote_volume: "quote_volume",
+        volume: "volume",
+    }
+    ForexResponse {
+        rate: "r",
+    }
+    FundingRateHistoryResponse {
+        ;
+        data: "data" => Vec<FundingRateHistoryView>,
+    }
+    FundingRateHistoryView {
+        funding_rate: "f",
+    }
+    FundingRateLatestResponse {
+        funding_rate: "f",
+    }
+    IndexPriceResponse {
+        ;
+        data: "data" => Vec<IndexPriceView>,
+    }
+    IndexPriceView {
+        price: "price",
+        volume: "volume",
+    }
+    LiquidationEntry {
+        price: "price",
+        price_usd: "priceUsd",
+        volume: "volume",
+        volume_usd: "volumeUsd",
+    }
+    LiquidationFeedEntry {
+        price: "price",
+        price_usd: "priceUsd",
+        volume: "volume",
+        volume_usd: "volumeUsd",
+    }
+    LiquidationFeedResponse {
+        ;
+        data: "data" => Vec<LiquidationFeedEntry>,
+    }
+    LiquidationHeatmapCell {
+        long_usd: "longUsd",
+        short_usd: "shortUsd",
+        total_usd: "totalUsd",
+    }
+    LiquidationHeatmapExchangesummary {
+        long_usd: "longUsd",
+        short_usd: "shortUsd",
+        total_usd: "totalUsd",
+    }
+    LiquidationHeatmapResponse {
+        grand_total: "grandTotal",
+        ;
+        cells: "cells" => Vec<LiquidationHeatmapCell>,
+        exchanges: "exchanges" => Vec<LiquidationHeatmapExchangesummary>,
+        tokens: "tokens" => Vec<LiquidationHeatmapTokensummary>,
+    }
+    LiquidationHeatmapTokensummary {
+        long_usd: "longUsd",
+        short_usd: "shortUsd",
+        total_usd: "totalUsd",
+    }
+    LiquidationMapAssumptions {
+        long_share_of_oi: "longShareOfOi",
+        mmr: "mmr",
+        ;
+        tiers: "tiers" => Vec<LiquidationMapTierassumption>,
+    }
+    LiquidationMapBucket {
+        l100x_usd: "l100xUsd",
+        l10x_usd: "l10xUsd",
+        l25x_usd: "l25xUsd",
+        l50x_usd: "l50xUsd",
+        price: "price",
+        total_usd: "totalUsd",
+    }
+    LiquidationMapResponse {
+        cumulative_long_usd: "cumulativeLongUsd",
+        cumulative_short_usd: "cumulativeShortUsd",
+        current_price: "currentPrice",
+        total_oi_usd: "totalOiUsd",
+        ;
+        assumptions: "assumptions" => LiquidationMapAssumptions,
+        buckets: "buckets" => Vec<LiquidationMapBucket>,
+    }
+    LiquidationMapTierassumption {
+        share: "share",
+    }
+    LiquidationResponse {
+        ;
+        data: "data" => Vec<LiquidationEntry>,
+    }
+    LiquidationStatsBiggest {
+        volume_usd: "volumeUsd",
+    }
+    LiquidationStatsResponse {
+        long_usd: "longUsd",
+        short_usd: "shortUsd",
+        total: "total",
+        ;
+        biggest: "biggest" => Option<LiquidationStatsBiggest>,
+    }
+    LiquidationSymbolHistoryBucket {
+        long_usd: "longUsd",
+        price: "price",
+        short_usd: "shortUsd",
+        total_usd: "totalUsd",
+    }
+    LiquidationSymbolHistoryResponse {
+        total_long_usd: "totalLongUsd",
+        total_short_usd: "totalShortUsd",
+        ;
+        buckets: "buckets" => Vec<LiquidationSymbolHistoryBucket>,
+    }
+    NaverTrendView {
+        value: "v",
+    }
+    OpenInterestListEntry {
+        open_interest: "openInterest",
+        open_interest_usd: "openInterestUsd",
+    }
+    OpenInterestListResponse {
+        ;
+        data: "data" => Vec<OpenInterestListEntry>,
+    }
+    OpenInterestResponse {
+        open_interest: "openInterest",
+        open_interest_usd: "openInterestUsd",
+    }
+    OpenInterestSummaryExchangesummary {
+        open_interest_usd: "openInterestUsd",
+    }
+    OpenInterestSummaryResponse {
+        grand_total: "grandTotal",
+        ;
+        exchanges: "exchanges" => Vec<OpenInterestSummaryExchangesummary>,
+        tokens: "tokens" => Vec<OpenInterestSummaryTokensummary>,
+    }
+    OpenInterestSummaryTokensummary {
+        open_interest_usd: "openInterestUsd",
+    }
+    PremiumDetail {
+        fg: "fg",
+        nfr: "nfr",
+        pdp: "pdp",
+        pdp15m: "pdp15m",
+        pdp1h: "pdp1h",
+        pdp24h: "pdp24h",
+        pdp30m: "pdp30m",
+        pdp4h: "pdp4h",
+        pdp5m: "pdp5m",
+        sad: "sad",
+        sad2p: "sad2p",
+        sadf: "sadf",
+        sbd2p: "sbd2p",
+        sfr: "sfr",
+        shb: "shb",
+        sla: "sla",
+        soi: "soi",
+        soich1h: "soich1h",
+        soich24h: "soich24h",
+        soich4h: "soich4h",
+        soivr: "soivr",
+        sp: "sp",
+        spdp15m: "spdp15m",
+        spdp1h: "spdp1h",
+        spdp24h: "spdp24h",
+        spdp30m: "spdp30m",
+        spdp4h: "spdp4h",
+        spdp5m: "spdp5m",
+        sv: "sv",
+        tad2p: "tad2p",
+        tbd: "tbd",
+        tbd2p: "tbd2p",
+        tbdf: "tbdf",
+        tfr: "tfr",
+        thb: "thb",
+        tla: "tla",
+        toi: "toi",
+        toich1h: "toich1h",
+        toich24h: "toich24h",
+        toich4h: "toich4h",
+        toivr: "toivr",
+        tp: "tp",
+        tpdp15m: "tpdp15m",
+        tpdp1h: "tpdp1h",
+        tpdp24h: "tpdp24h",
+        tpdp30m: "tpdp30m",
+        tpdp4h: "tpdp4h",
+        tpdp5m: "tpdp5m",
+        tv: "tv",
+    }
+    PremiumResponse {
+        ;
+        data: "data" => Vec<PremiumView>,
+    }
+    PremiumView {
+        source_annualized_funding_rate: "source_annualized_funding_rate",
+        target_annualized_funding_rate: "target_annualized_funding_rate",
+        ;
+        detail: "detail" => PremiumDetail,
+    }
+    TickerResponse {
+        ;
+        data: "data" => TickerView,
+    }
+    TickerView {
+        highest_bid: "hb",
+        lowest_ask: "la",
+        lower_depth: "ld",
+        price: "p",
+        price_24h: "p24h",
+        price_change: "pc",
+        upper_depth: "ud",
+        volume: "v",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn decimals_come_from_the_json_text() {
+        let body = r#"{"currency":"USD","exchange":"binance","interval":"1m","market":"spot",
+            "symbol":"BTC-USDT","data":[{"c":0.12345678901234567890123,"d":0,"h":0.3,
+            "l":0.00000123,"o":65432.12345678,"v":1e-8}]}"#;
+        let candles: Exact<crate::CexCandleResponse> =
+            serde_json::from_str(body).expect("valid candles");
+
+        let exact = &candles.decimals.data[0];
+        assert_eq!(
+            exact.close,
+            Decimal::from_str("0.12345678901234567890123").ok()
+        );
+        assert_ne!(
+            candles.value.data[0].close.to_string(),
+            "0.12345678901234567890123"
+        );
+        assert_eq!(exact.high, Decimal::from_str("0.3").ok());
+        assert_eq!(exact.low, Decimal::from_str("0.00000123").ok());
+        assert_eq!(exact.open, Decimal::from_str("65432.12345678").ok());
+        assert_eq!(exact.volume, Decimal::from_str("0.00000001").ok());
+    }
+
+    #[test]
+    fn null_absent_and_unrepresentable_values_are_none() {
+        let fees: CexFeesView =
+            serde_json::from_str(r#"{"spot_maker_fee":null,"spot_take_fee":1e-30}"#)
+                .expect("valid fees");
+        assert_eq!(fees.spot_maker_fee, None);
+        assert_eq!(fees.spot_take_fee, None);
+        assert_eq!(fees.futures_taker_fee, None);
+        assert!(serde_json::from_str::<CexFeesView>(r#"{"spot_take_fee":"0.1"}"#).is_err());
+
+        assert_eq!(
+            parse_decimal("1e-28"),
+            Decimal::from_str("0.0000000000000000000000000001").ok()
+        );
+        assert_eq!(parse_decimal("-2.5E+2"), Some(Decimal::from(-250)));
+        assert_eq!(parse_decimal("79228162514264337593543950336"), None);
+    }
+}
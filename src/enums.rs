@@ -0,0 +1,434 @@
+//! Typed enums for the string-valued response fields.
+//!
+//! The generated models keep fields such as
+//! [`LiquidationEntry::side`](crate::LiquidationEntry::side),
+//! [`CexSymbolMetadataView::status`](crate::CexSymbolMetadataView::status)
+//! or the various `market` fields as plain `String`s, so decoding never
+//! fails on a value the server adds later. Each such field gets a
+//! `<field>_kind()` accessor here that parses it into an enum with an
+//! `Unknown(String)` catch-all:
+//!
+//! | Enum | Fields |
+//! |---|---|
+//! | [`Side`](crate::enums::Side) | `side` of liquidation entries and liquidation map buckets |
+//! | [`Market`](crate::enums::Market) | every `market` field |
+//! | [`SymbolStatus`](crate::enums::SymbolStatus) | `status` of symbol metadata and delistings |
+//! | [`CautionLevel`](crate::enums::CautionLevel) | `caution_level` of symbol cautions and metadata |
+//! | [`TokenUpdateType`](crate::enums::TokenUpdateType) | [`CexTokenUpdatesView::update_type`](crate::CexTokenUpdatesView::update_type) |
+//! | [`WalletState`](crate::enums::WalletState) | `deposit_state` / `withdraw_state` of [`WalletStatusView`](crate::WalletStatusView) |
+//! | [`Currency`](crate::enums::Currency) | `currency` of candle and ticker responses |
+//!
+//! Known values match ASCII case-insensitively; anything else is kept
+//! verbatim in `Unknown`. [`as_str`](crate::enums::Side::as_str) gives the
+//! canonical spelling of a known variant, which is not always the text that
+//! was received: an alias parses to its variant, so
+//! `Side::from("sell").as_str()` is `"long"`. The enums also implement `FromStr`,
+//! `Display` and serde (as their wire string) for use in your own types, and
+//! convert from the matching option-side enums
+//! ([`CexSymbolCautionsMinLevel`](crate::CexSymbolCautionsMinLevel),
+//! [`CexTokenUpdatesType`](crate::CexTokenUpdatesType), the `*Market` and
+//! `*Currency` enums).
+//!
+//! ```
+//! use datamaxi::enums::{Side, SymbolStatus};
+//! use datamaxi::{CexSymbolMetadataView, LiquidationEntry};
+//!
+//! let entry = LiquidationEntry { side: "long".into(), ..Default::default() };
+//! assert_eq!(entry.side_kind(), Side::Long);
+//!
+//! let symbol = CexSymbolMetadataView { status: "auction".into(), ..Default::default() };
+//! assert_eq!(symbol.status_kind(), SymbolStatus::Unknown("auction".into()));
+//! ```
+
+use crate::generated::*;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+/// Defines a string-backed enum with an `Unknown(String)` catch-all. A
+/// variant may list `| "alias"` spellings that parse to it; `as_str` always
+/// gives the first.
+macro_rules! string_enum {
+    ($(#[$meta:meta])* $name:ident { $($(#[$vmeta:meta])* $variant:ident => $wire:literal $(| $alias:literal)*,)* }) => {
+        $(#[$meta])*
+        #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+        #[non_exhaustive]
+        pub enum $name {
+            $($(#[$vmeta])* $variant,)*
+            /// A value this version of the crate does not know, verbatim.
+            Unknown(String),
+        }
+
+        impl $name {
+            /// The wire value: the canonical spelling for known variants,
+            /// the original text for `Unknown`.
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => $wire,)*
+                    $name::Unknown(value) => value,
+                }
+            }
+
+            /// Whether the value was not recognized.
+            pub fn is_unknown(&self) -> bool {
+                matches!(self, $name::Unknown(_))
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(value: &str) -> Self {
+                $(if value.eq_ignore_ascii_case($wire)
+                    $(|| value.eq_ignore_ascii_case($alias))*
+                {
+                    return $name::$variant;
+                })*
+                $name::Unknown(value.to_string())
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = Infallible;
+
+            fn from_str(value: &str) -> Result<Self, Infallible> {
+                Ok($name::from(value))
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let value = String::deserialize(deserializer)?;
+                Ok($name::from(value.as_str()))
+            }
+        }
+    };
+}
+
+string_enum! {
+    /// The side of a liquidation: which positions were closed.
+    ///
+    /// Liquidation rows that carry the order side instead (`cex.liquidation`,
+    /// per [`Liquidation::symbol_history`](crate::Liquidation::symbol_history))
+    /// parse too: `sell` closes a long and `buy` a short.
+    Side {
+        /// Long positions liquidated (a forced sell).
+        Long => "long" | "sell",
+        /// Short positions liquidated (a forced buy).
+        Short => "short" | "buy",
+    }
+}
+
+string_enum! {
+    /// A spot or futures market.
+    Market {
+        /// Spot markets.
+        Spot => "spot",
+        /// Perpetual and dated futures markets.
+        Futures => "futures",
+    }
+}
+
+string_enum! {
+    /// The trading status of a listed symbol.
+    SymbolStatus {
+        /// Trading normally.
+        Trading => "trading",
+        /// Announced but not yet trading.
+        PreListing => "pre_listing",
+        /// Trading is halted.
+        Halt => "halt",
+        /// Only position-reducing orders are accepted.
+        CloseOnly => "close_only",
+        /// A delisting is scheduled.
+        Delisting => "delisting",
+        /// No longer trading.
+        Delisted => "delisted",
+    }
+}
+
+string_enum! {
+    /// The severity of an exchange caution flag, lowest first.
+    CautionLevel {
+        /// The mildest flag.
+        Caution => "caution",
+        /// An elevated flag.
+        Warning => "warning",
+        /// The most severe flag, often ahead of a delisting.
+        Danger => "danger",
+    }
+}
+
+string_enum! {
+    /// Whether a token update is a listing or a delisting.
+    TokenUpdateType {
+        /// The token was listed.
+        Listed => "listed",
+        /// The token was delisted.
+        Delisted => "delisted",
+    }
+}
+
+string_enum! {
+    /// Whether deposits or withdrawals of a currency are open on a network.
+    ///
+    /// The API does not document the values of `deposit_state` and
+    /// `withdraw_state`, so every state is kept as `Unknown` until it does.
+    WalletState {}
+}
+
+impl WalletState {
+    /// Whether the state means open (`Some(true)`) or closed
+    /// (`Some(false)`); `None` while the value is not understood.
+    pub fn is_open(&self) -> Option<bool> {
+        match self {
+            WalletState::Unknown(_) => None,
+        }
+    }
+}
+
+string_enum! {
+    /// A currency values are denominated in, as used by forex symbols
+    /// (`USD-KRW`).
+    Currency {
+        /// US dollar.
+        USD => "USD",
+        /// South Korean won.
+        KRW => "KRW",
+    }
+}
+
+impl From<CexSymbolCautionsMinLevel> for CautionLevel {
+    fn from(level: CexSymbolCautionsMinLevel) -> Self {
+        CautionLevel::from(level.as_str())
+    }
+}
+
+impl From<CexTokenUpdatesType> for TokenUpdateType {
+    fn from(update_type: CexTokenUpdatesType) -> Self {
+        TokenUpdateType::from(update_type.as_str())
+    }
+}
+
+/// Converts each generated `*Currency` option enum into a [`Currency`].
+macro_rules! currency_conversions {
+    ($($option:ident)*) => {$(
+        impl From<$option> for Currency {
+            fn from(currency: $option) -> Self {
+                Currency::from(currency.as_str())
+            }
+        }
+    )*};
+}
+
+currency_conversions! {
+    CexCandleCurrency
+    CexSymbolOiStatsCurrency
+    TickerCurrency
+}
+
+/// Converts between [`Market`] and each generated `*Market` option enum.
+macro_rules! market_conversions {
+    ($($option:ident)*) => {$(
+        impl From<$option> for Market {
+            fn from(market: $option) -> Self {
+                Market::from(market.as_str())
+            }
+        }
+
+        impl TryFrom<Market> for $option {
+            /// The market, when it has no counterpart in the option enum.
+            type Error = Market;
+
+            fn try_from(market: Market) -> Result<Self, Market> {
+                match market {
+                    Market::Spot => Ok($option::Spot),
+                    Market::Futures => Ok($option::Futures),
+                    other => Err(other),
+                }
+            }
+        }
+    )*};
+}
+
+market_conversions! {
+    CexCandleMarket
+    CexCandleExchangesMarket
+    CexCandleSymbolsMarket
+    CexSymbolCautionsMarket
+    CexSymbolDelistingsMarket
+    CexSymbolMetadataMarket
+    CexSymbolTagsMarket
+    CexSymbolVolumeMarket
+    PremiumSourceMarket
+    PremiumTargetMarket
+    TickerMarket
+    TickerExchangesMarket
+    TickerSymbolsMarket
+}
+
+/// Adds a `<field>_kind()` accessor parsing each listed `String` field.
+macro_rules! impl_kind_accessors {
+    ($($model:ident { $($field:ident => $accessor:ident: $kind:ident;)* })*) => {$(
+        impl $model {
+            $(
+                #[doc = concat!("[`", stringify!($field), "`](Self::", stringify!($field), ") as a [`", stringify!($kind), "`].")]
+                pub fn $accessor(&self) -> $kind {
+                    $kind::from(self.$field.as_str())
+                }
+            )*
+        }
+    )*};
+}
+
+impl_kind_accessors! {
+    CexCandleResponse {
+        currency => currency_kind: Currency;
+        market => market_kind: Market;
+    }
+    CexCandleSymbolsView {
+        market => market_kind: Market;
+    }
+    CexSymbolCautionsView {
+        caution_level => caution_level_kind: CautionLevel;
+        market => market_kind: Market;
+    }
+    CexSymbolDelistingsView {
+        market => market_kind: Market;
+        status => status_kind: SymbolStatus;
+    }
+    CexSymbolLiquidationView {
+        market => market_kind: Market;
+    }
+    CexSymbolMetadataView {
+        market => market_kind: Market;
+        status => status_kind: SymbolStatus;
+    }
+    CexSymbolOiStatsView {
+        market => market_kind: Market;
+    }
+    CexSymbolOiView {
+        market => market_kind: Market;
+    }
+    CexSymbolTagsView {
+        market => market_kind: Market;
+    }
+    CexSymbolVolumeView {
+        market => market_kind: Market;
+    }
+    CexTokenUpdatesView {
+        market => market_kind: Market;
+        update_type => update_type_kind: TokenUpdateType;
+    }
+    FundingRateSymbolsView {
+        market => market_kind: Market;
+    }
+    LiquidationEntry {
+        side => side_kind: Side;
+    }
+    LiquidationFeedEntry {
+        side => side_kind: Side;
+    }
+    LiquidationMapBucket {
+        side => side_kind: Side;
+    }
+    TickerResponse {
+        currency => currency_kind: Currency;
+        market => market_kind: Market;
+    }
+    TickerView {
+        market => market_kind: Market;
+    }
+    WalletStatusView {
+        deposit_state => deposit_state_kind: WalletState;
+        withdraw_state => withdraw_state_kind: WalletState;
+    }
+}
+
+impl CexSymbolMetadataView {
+    /// [`caution_level`](Self::caution_level) as a [`CautionLevel`], or
+    /// `None` when the symbol carries no caution (absent or `""`).
+    pub fn caution_level_kind(&self) -> Option<CautionLevel> {
+        self.caution_level
+            .as_deref()
+            .filter(|level| !level.is_empty())
+            .map(CautionLevel::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_values_parse_case_insensitively_and_unknown_round_trips() {
+        assert_eq!(Side::from("SHORT"), Side::Short);
+        assert_eq!(Side::from("sell"), Side::Long);
+        assert_eq!(Side::from("Buy"), Side::Short);
+        assert_eq!(Side::from("sell").as_str(), "long");
+        assert_eq!(
+            LiquidationFeedEntry {
+                side: "buy".into(),
+                ..Default::default()
+            }
+            .side_kind(),
+            Side::Short
+        );
+        assert_eq!(SymbolStatus::from("close_only"), SymbolStatus::CloseOnly);
+        assert_eq!(SymbolStatus::CloseOnly.as_str(), "close_only");
+
+        let unknown = Market::from("Options");
+        assert!(unknown.is_unknown());
+        assert_eq!(unknown.to_string(), "Options");
+    }
+
+    #[test]
+    fn serde_uses_the_wire_string() {
+        let levels: Vec<CautionLevel> =
+            serde_json::from_str(r#"["danger","extreme"]"#).expect("never fails on strings");
+        assert_eq!(
+            levels,
+            [
+                CautionLevel::Danger,
+                CautionLevel::Unknown("extreme".into())
+            ]
+        );
+        assert_eq!(
+            serde_json::to_string(&levels).expect("serializes"),
+            r#"["danger","extreme"]"#
+        );
+    }
+
+    #[test]
+    fn accessors_and_option_enum_conversions() {
+        let metadata = CexSymbolMetadataView {
+            market: "futures".into(),
+            caution_level: Some(String::new()),
+            ..Default::default()
+        };
+        assert_eq!(metadata.caution_level_kind(), None);
+        assert_eq!(
+            CexCandleMarket::try_from(metadata.market_kind()),
+            Ok(CexCandleMarket::Futures)
+        );
+        assert_eq!(
+            CautionLevel::from(CexSymbolCautionsMinLevel::Warning),
+            CautionLevel::Warning
+        );
+        assert_eq!(
+            TickerMarket::try_from(Market::from("margin")),
+            Err(Market::Unknown("margin".into()))
+        );
+    }
+}
@@ -0,0 +1,367 @@
+//! Typed exchange identifiers and a registry of what each exchange offers.
+//!
+//! Option builders and wrappers take exchanges as free strings. An
+//! [`Exchange`](crate::exchange::Exchange) is the normalized (trimmed,
+//! lower-case) id and converts into `String`, so it is accepted wherever a
+//! string is. An [`ExchangeRegistry`](crate::exchange::ExchangeRegistry)
+//! records which [`DataProduct`](crate::exchange::DataProduct)s each exchange
+//! supports; [`Client::exchange_registry`](crate::Client::exchange_registry)
+//! builds one from the `exchanges()` endpoints of `CexCandle`, `FundingRate`,
+//! `Premium`, `Ticker`, `TradingFees` and `WalletStatus`.
+//!
+//! [`ExchangeRegistry::require`](crate::exchange::ExchangeRegistry::require)
+//! and [`check_premium`](crate::exchange::ExchangeRegistry::check_premium)
+//! reject an unsupported combination with
+//! [`Error::InvalidArgument`](crate::api::Error::InvalidArgument) before a
+//! request is sent. The registry is a snapshot: fetch it once and reuse it.
+//!
+//! ```no_run
+//! use datamaxi::exchange::DataProduct;
+//! use datamaxi::Client;
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = Client::new("my_api_key");
+//! let registry = client.exchange_registry().await?;
+//!
+//! let exchange = registry.require("Binance", DataProduct::FundingRate)?;
+//! let latest = client.funding_rate().latest(&exchange, "BTC-USDT").await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::api::{Error, Result};
+use crate::generated::{CexCandleExchangesMarket, PremiumOptions, TickerExchangesMarket};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+/// A normalized exchange id, e.g. `binance` or `upbit`.
+///
+/// Displays, serializes and converts into `String` as the id the API uses.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Exchange(String);
+
+impl Exchange {
+    /// The exchange `id`, trimmed and lower-cased.
+    pub fn new(id: impl AsRef<str>) -> Self {
+        Exchange(id.as_ref().trim().to_ascii_lowercase())
+    }
+
+    /// The id as sent to the API.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for Exchange {
+    fn from(id: &str) -> Self {
+        Exchange::new(id)
+    }
+}
+
+impl From<String> for Exchange {
+    fn from(id: String) -> Self {
+        Exchange::new(id)
+    }
+}
+
+impl From<Exchange> for String {
+    fn from(exchange: Exchange) -> Self {
+        exchange.0
+    }
+}
+
+impl From<&Exchange> for String {
+    fn from(exchange: &Exchange) -> Self {
+        exchange.0.clone()
+    }
+}
+
+impl AsRef<str> for Exchange {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Exchange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Serialize for Exchange {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Exchange {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Exchange::from)
+    }
+}
+
+/// A data product an exchange may or may not be covered for, one per
+/// `exchanges()` listing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum DataProduct {
+    /// Spot candles (`/api/v1/cex/candle`, `market=spot`).
+    SpotCandle,
+    /// Futures candles (`/api/v1/cex/candle`, `market=futures`).
+    FuturesCandle,
+    /// Funding rates (`/api/v1/funding-rate`).
+    FundingRate,
+    /// Cross-exchange premium (`/api/v1/premium`), as source or target.
+    Premium,
+    /// Spot tickers (`/api/v1/ticker`, `market=spot`).
+    SpotTicker,
+    /// Futures tickers (`/api/v1/ticker`, `market=futures`).
+    FuturesTicker,
+    /// Trading fees (`/api/v1/trading-fees`).
+    TradingFees,
+    /// Deposit / withdrawal status (`/api/v1/wallet-status`).
+    WalletStatus,
+}
+
+impl DataProduct {
+    /// Every product, in declaration order.
+    pub const ALL: [DataProduct; 8] = [
+        DataProduct::SpotCandle,
+        DataProduct::FuturesCandle,
+        DataProduct::FundingRate,
+        DataProduct::Premium,
+        DataProduct::SpotTicker,
+        DataProduct::FuturesTicker,
+        DataProduct::TradingFees,
+        DataProduct::WalletStatus,
+    ];
+
+    /// A short human-readable name, used in error messages.
+    pub fn description(&self) -> &'static str {
+        match self {
+            DataProduct::SpotCandle => "spot candles",
+            DataProduct::FuturesCandle => "futures candles",
+            DataProduct::FundingRate => "funding rates",
+            DataProduct::Premium => "premium",
+            DataProduct::SpotTicker => "spot tickers",
+            DataProduct::FuturesTicker => "futures tickers",
+            DataProduct::TradingFees => "trading fees",
+            DataProduct::WalletStatus => "wallet status",
+        }
+    }
+}
+
+impl fmt::Display for DataProduct {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+/// Which [`DataProduct`]s each [`Exchange`] supports.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExchangeRegistry {
+    products: BTreeMap<Exchange, BTreeSet<DataProduct>>,
+}
+
+impl ExchangeRegistry {
+    /// An empty registry.
+    pub fn new() -> Self {
+        ExchangeRegistry::default()
+    }
+
+    /// Records that each of `exchanges` supports `product`.
+    pub fn insert<I, E>(&mut self, product: DataProduct, exchanges: I)
+    where
+        I: IntoIterator<Item = E>,
+        E: Into<Exchange>,
+    {
+        for exchange in exchanges {
+            self.products
+                .entry(exchange.into())
+                .or_default()
+                .insert(product);
+        }
+    }
+
+    /// Whether `exchange` supports `product`.
+    pub fn supports(&self, exchange: impl Into<Exchange>, product: DataProduct) -> bool {
+        self.products
+            .get(&exchange.into())
+            .is_some_and(|products| products.contains(&product))
+    }
+
+    /// The products `exchange` supports, empty if it is unknown.
+    pub fn products(&self, exchange: impl Into<Exchange>) -> Vec<DataProduct> {
+        self.products
+            .get(&exchange.into())
+            .map(|products| products.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Every known exchange, in id order.
+    pub fn exchanges(&self) -> impl Iterator<Item = &Exchange> {
+        self.products.keys()
+    }
+
+    /// The exchanges supporting `product`, in id order.
+    pub fn exchanges_for(&self, product: DataProduct) -> Vec<&Exchange> {
+        self.products
+            .iter()
+            .filter(|(_, products)| products.contains(&product))
+            .map(|(exchange, _)| exchange)
+            .collect()
+    }
+
+    /// The normalized `exchange`, or [`Error::InvalidArgument`] if it does
+    /// not support `product`.
+    pub fn require(&self, exchange: impl Into<Exchange>, product: DataProduct) -> Result<Exchange> {
+        self.require_argument("exchange", exchange.into(), product)
+    }
+
+    /// Checks the source and target exchanges of a premium query, if set.
+    pub fn check_premium(&self, options: &PremiumOptions) -> Result<()> {
+        for (argument, exchange) in [
+            ("source_exchange", &options.source_exchange),
+            ("target_exchange", &options.target_exchange),
+        ] {
+            if let Some(exchange) = exchange {
+                self.require_argument(argument, Exchange::new(exchange), DataProduct::Premium)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn require_argument(
+        &self,
+        argument: &str,
+        exchange: Exchange,
+        product: DataProduct,
+    ) -> Result<Exchange> {
+        let reason = match self.products.get(&exchange) {
+            Some(products) if products.contains(&product) => return Ok(exchange),
+            Some(_) => format!("`{exchange}` does not offer {product}"),
+            None => format!("unknown exchange `{exchange}`"),
+        };
+        let supported: Vec<&str> = self
+            .exchanges_for(product)
+            .into_iter()
+            .map(Exchange::as_str)
+            .collect();
+        Err(Error::InvalidArgument {
+            argument: argument.to_string(),
+            reason: format!(
+                "{reason}; {product} is offered by: {}",
+                supported.join(", ")
+            ),
+        })
+    }
+}
+
+/// Fetches every `exchanges()` listing into a registry. Shared by the async
+/// and blocking `exchange_registry`; pass `await` as the trailing argument
+/// for the async flavor, like `get_loop!` in [`crate::api`].
+macro_rules! exchange_registry {
+    ($client:expr $(, $aw:ident)?) => {{
+        let client = $client;
+        let mut registry = ExchangeRegistry::new();
+
+        let candle = client.cex_candle();
+        let spot = candle.exchanges(CexCandleExchangesMarket::Spot)$(.$aw)?;
+        registry.insert(DataProduct::SpotCandle, spot?);
+        let futures = candle.exchanges(CexCandleExchangesMarket::Futures)$(.$aw)?;
+        registry.insert(DataProduct::FuturesCandle, futures?);
+
+        let funding = client.funding_rate().exchanges()$(.$aw)?;
+        registry.insert(DataProduct::FundingRate, funding?);
+        let premium = client.premium().exchanges()$(.$aw)?;
+        registry.insert(DataProduct::Premium, premium?);
+
+        let ticker = client.ticker();
+        let spot = ticker.exchanges(TickerExchangesMarket::Spot)$(.$aw)?;
+        registry.insert(DataProduct::SpotTicker, spot?);
+        let futures = ticker.exchanges(TickerExchangesMarket::Futures)$(.$aw)?;
+        registry.insert(DataProduct::FuturesTicker, futures?);
+
+        let fees = client.trading_fees().exchanges()$(.$aw)?;
+        registry.insert(DataProduct::TradingFees, fees?);
+        let wallet = client.wallet_status().exchanges()$(.$aw)?;
+        registry.insert(DataProduct::WalletStatus, wallet?);
+
+        Ok(registry)
+    }};
+}
+
+impl crate::api::Client {
+    /// Builds an [`ExchangeRegistry`] from the `exchanges()` endpoints of
+    /// every product (eight requests).
+    pub async fn exchange_registry(&self) -> Result<ExchangeRegistry> {
+        exchange_registry!(self, await)
+    }
+}
+
+#[cfg(feature = "sync")]
+impl crate::api::sync::Client {
+    /// Blocking mirror of the async `Client::exchange_registry`.
+    pub fn exchange_registry(&self) -> Result<ExchangeRegistry> {
+        exchange_registry!(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry() -> ExchangeRegistry {
+        let mut registry = ExchangeRegistry::new();
+        registry.insert(DataProduct::FundingRate, ["binance", "bybit"]);
+        registry.insert(DataProduct::Premium, ["binance", "upbit"]);
+        registry
+    }
+
+    #[test]
+    fn lookups_normalize_exchange_ids() {
+        let registry = registry();
+
+        assert!(registry.supports(" Binance ", DataProduct::FundingRate));
+        assert!(!registry.supports("upbit", DataProduct::FundingRate));
+        assert_eq!(
+            registry.products("binance"),
+            [DataProduct::FundingRate, DataProduct::Premium]
+        );
+        assert_eq!(
+            registry.exchanges_for(DataProduct::Premium),
+            [&Exchange::new("binance"), &Exchange::new("upbit")]
+        );
+        assert_eq!(String::from(Exchange::new("UPBIT")), "upbit");
+    }
+
+    #[test]
+    fn unsupported_combinations_are_rejected() {
+        let registry = registry();
+
+        let exchange = registry
+            .require("BYBIT", DataProduct::FundingRate)
+            .expect("supported");
+        assert_eq!(exchange.as_str(), "bybit");
+
+        let err = registry
+            .require("upbit", DataProduct::FundingRate)
+            .expect_err("upbit has no funding");
+        assert!(matches!(
+            &err,
+            Error::InvalidArgument { argument, reason }
+                if argument == "exchange" && reason.contains("binance, bybit")
+        ));
+
+        let options = PremiumOptions::new()
+            .source_exchange("upbit")
+            .target_exchange("okx");
+        let err = registry.check_premium(&options).expect_err("okx unknown");
+        assert!(matches!(
+            &err,
+            Error::InvalidArgument { argument, reason }
+                if argument == "target_exchange" && reason.starts_with("unknown exchange `okx`")
+        ));
+    }
+}
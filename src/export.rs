@@ -0,0 +1,340 @@
+//! Streaming CSV and Parquet writers for historical datasets (feature
+//! `export`).
+//!
+//! [`CsvWriter`](crate::export::CsvWriter) and
+//! [`ParquetWriter`](crate::export::ParquetWriter) take rows one page or
+//! window at a time, as [`Paginator`](crate::api::Paginator) and
+//! [`TimeRangeWalker`](crate::api::TimeRangeWalker) yield them, so a long
+//! history is archived without being held in memory. Both implement
+//! [`RowWriter`](crate::export::RowWriter), whose
+//! [`write_pages`](crate::export::RowWriter::write_pages) drains a blocking
+//! paginator or walker directly.
+//!
+//! ```no_run
+//! use datamaxi::api::TimeRange;
+//! use datamaxi::export::{ParquetWriter, ParquetWriterOptions, RowWriter};
+//! use datamaxi::{Client, IndexPriceResponse, IndexPriceView};
+//! use std::collections::BTreeMap;
+//! use std::time::Duration;
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = Client::new("my_api_key");
+//! let file = std::fs::File::create("index-price.parquet")?;
+//! let mut writer = ParquetWriter::<_, IndexPriceView>::new(
+//!     file,
+//!     ParquetWriterOptions::new().row_group_size(100_000),
+//! )?;
+//!
+//! let range = TimeRange::new(1_704_067_200_000, 1_735_689_600_000, Duration::from_secs(7 * 86_400));
+//! let mut params = BTreeMap::new();
+//! params.insert("asset".to_string(), "BTC".to_string());
+//! let mut windows = client.walk_time_range::<IndexPriceResponse>("/api/v1/index-price", params, range);
+//! while let Some(items) = windows.next_window().await? {
+//!     writer.write(&items)?;
+//! }
+//! writer.finish()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Column schema
+//!
+//! Columns follow the [`table`](crate::table) rules for every model: one
+//! column per field, named after the Rust field, in declaration order, with
+//! nested fields flattened as `parent_child`. In Parquet, timestamps are
+//! `TIMESTAMP(MILLIS, UTC)`, `Option<_>` fields are `OPTIONAL` and string
+//! lists are `LIST<STRING>`. In CSV, timestamps are integer UTC
+//! milliseconds, nulls are empty fields, string lists and raw JSON are JSON
+//! text, and floats use Rust's shortest round-trip formatting.
+//!
+//! The schemas of the usual archive targets:
+//!
+//! | Model | Columns (`?` = nullable, `ts` = timestamp) |
+//! |---|---|
+//! | [`CexCandleView`](crate::CexCandleView) | `close`, `timestamp` ts, `high`, `low`, `open`, `volume` |
+//! | [`FundingRateHistoryView`](crate::FundingRateHistoryView) | `timestamp` ts, `funding_rate`? |
+//! | [`IndexPriceView`](crate::IndexPriceView) | `price`, `timestamp` ts, `volume` |
+//! | [`OpenInterestHistoryPoint`](crate::models::OpenInterestHistoryPoint) | `exchange`, `timestamp` ts, `raw`? (JSON) |
+//! | [`LiquidationSymbolHistoryBucket`](crate::LiquidationSymbolHistoryBucket) | `long_usd`, `price`?, `short_usd`, `total_usd`, `ts` ts |
+//!
+//! Any model's schema is available at runtime from
+//! [`Tabular::schema`](crate::table::Tabular::schema).
+
+use crate::arrow::{schema, ArrowError, ToRecordBatch};
+use crate::table::{ColumnValues, Tabular};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::errors::ParquetError;
+use parquet::file::properties::WriterProperties;
+use std::io::Write;
+use std::marker::PhantomData;
+use thiserror::Error;
+
+/// Errors returned by the export writers.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum Error {
+    /// Fetching a page to write failed.
+    #[error(transparent)]
+    Api(#[from] crate::api::Error),
+
+    /// Writing to the underlying sink failed.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The CSV encoder failed.
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
+
+    /// The Parquet encoder failed.
+    #[error("Parquet error: {0}")]
+    Parquet(#[from] ParquetError),
+
+    /// Converting rows to Arrow failed.
+    #[error("Arrow error: {0}")]
+    Arrow(#[from] ArrowError),
+}
+
+/// Result alias for the export writers.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// An incremental writer of `T` rows.
+pub trait RowWriter<T: Tabular> {
+    /// Appends `rows` after everything written so far.
+    fn write(&mut self, rows: &[T]) -> Result<()>;
+
+    /// Writes every page yielded by `pages` (e.g. a blocking
+    /// [`Paginator`](crate::api::sync::Paginator) or
+    /// [`TimeRangeWalker`](crate::api::sync::TimeRangeWalker)), stopping at
+    /// the first error. Returns the number of rows written.
+    fn write_pages<I, E>(&mut self, pages: I) -> Result<usize>
+    where
+        I: IntoIterator<Item = std::result::Result<Vec<T>, E>>,
+        Error: From<E>,
+    {
+        let mut written = 0;
+        for page in pages {
+            let page = page?;
+            self.write(&page)?;
+            written += page.len();
+        }
+        Ok(written)
+    }
+}
+
+/// Streams rows to CSV, header first.
+pub struct CsvWriter<W: Write, T> {
+    writer: csv::Writer<W>,
+    rows: PhantomData<fn(&T)>,
+}
+
+impl<W: Write, T: Tabular> CsvWriter<W, T> {
+    /// Wraps `writer` and writes the header row.
+    pub fn new(writer: W) -> Result<Self> {
+        let mut writer = csv::Writer::from_writer(writer);
+        writer.write_record(T::schema().iter().map(|spec| spec.name.as_str()))?;
+        Ok(CsvWriter {
+            writer,
+            rows: PhantomData,
+        })
+    }
+
+    /// Flushes and returns the underlying writer.
+    pub fn finish(self) -> Result<W> {
+        self.writer
+            .into_inner()
+            .map_err(|err| Error::Io(err.into_error()))
+    }
+}
+
+impl<W: Write, T: Tabular> RowWriter<T> for CsvWriter<W, T> {
+    fn write(&mut self, rows: &[T]) -> Result<()> {
+        let columns = T::columns(rows);
+        for row in 0..rows.len() {
+            let record = columns.iter().map(|column| csv_cell(&column.values, row));
+            self.writer.write_record(record)?;
+        }
+        Ok(())
+    }
+}
+
+fn csv_cell(values: &ColumnValues, row: usize) -> String {
+    match values {
+        ColumnValues::Int64(values) => values[row].map(|v| v.to_string()),
+        ColumnValues::Float64(values) => values[row].map(|v| v.to_string()),
+        ColumnValues::Boolean(values) => values[row].map(|v| v.to_string()),
+        ColumnValues::Utf8(values) => values[row].clone(),
+        ColumnValues::Utf8List(values) => values[row]
+            .as_ref()
+            .map(|items| serde_json::Value::from(items.clone()).to_string()),
+    }
+    .unwrap_or_default()
+}
+
+/// Options for [`ParquetWriter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParquetWriterOptions {
+    row_group_size: usize,
+    snappy: bool,
+}
+
+impl ParquetWriterOptions {
+    /// Row groups of up to 1 Mi rows, Snappy-compressed.
+    pub fn new() -> Self {
+        ParquetWriterOptions {
+            row_group_size: 1024 * 1024,
+            snappy: true,
+        }
+    }
+
+    /// Maximum rows per row group. Rows are buffered until a group fills
+    /// (or the writer finishes), independently of how they were paged.
+    pub fn row_group_size(mut self, rows: usize) -> Self {
+        self.row_group_size = rows.max(1);
+        self
+    }
+
+    /// Whether to Snappy-compress column chunks (on by default).
+    pub fn snappy(mut self, enabled: bool) -> Self {
+        self.snappy = enabled;
+        self
+    }
+}
+
+impl Default for ParquetWriterOptions {
+    fn default() -> Self {
+        ParquetWriterOptions::new()
+    }
+}
+
+/// Streams rows to a Parquet file.
+///
+/// The file is only valid once [`finish`](Self::finish) has written the
+/// footer.
+pub struct ParquetWriter<W: Write + Send, T> {
+    writer: ArrowWriter<W>,
+    rows: PhantomData<fn(&T)>,
+}
+
+impl<W: Write + Send, T: Tabular> ParquetWriter<W, T> {
+    /// Wraps `writer` with `T`'s schema.
+    pub fn new(writer: W, options: ParquetWriterOptions) -> Result<Self> {
+        let compression = if options.snappy {
+            Compression::SNAPPY
+        } else {
+            Compression::UNCOMPRESSED
+        };
+        let properties = WriterProperties::builder()
+            .set_max_row_group_size(options.row_group_size)
+            .set_compression(compression)
+            .build();
+        Ok(ParquetWriter {
+            writer: ArrowWriter::try_new(writer, schema::<T>(), Some(properties))?,
+            rows: PhantomData,
+        })
+    }
+
+    /// Writes any buffered rows and the file footer, and returns the
+    /// underlying writer.
+    pub fn finish(self) -> Result<W> {
+        Ok(self.writer.into_inner()?)
+    }
+}
+
+impl<W: Write + Send, T: Tabular> RowWriter<T> for ParquetWriter<W, T> {
+    fn write(&mut self, rows: &[T]) -> Result<()> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+        self.writer.write(&rows.to_record_batch()?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generated::{CexCandleView, FundingRateHistoryView, LiquidationSymbolHistoryBucket};
+    use crate::table::ColumnSpec;
+    use bytes::Bytes;
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+
+    fn describe(schema: Vec<ColumnSpec>) -> Vec<String> {
+        schema
+            .into_iter()
+            .map(|spec| format!("{}:{:?}:{}", spec.name, spec.column_type, spec.nullable))
+            .collect()
+    }
+
+    /// Locks the documented schemas of the usual archive targets.
+    #[test]
+    fn archive_schemas_are_stable() {
+        assert_eq!(
+            describe(CexCandleView::schema()),
+            [
+                "close:Float64:false",
+                "timestamp:Timestamp:false",
+                "high:Float64:false",
+                "low:Float64:false",
+                "open:Float64:false",
+                "volume:Float64:false",
+            ]
+        );
+        assert_eq!(
+            describe(FundingRateHistoryView::schema()),
+            ["timestamp:Timestamp:false", "funding_rate:Float64:true"]
+        );
+        assert_eq!(
+            describe(LiquidationSymbolHistoryBucket::schema()),
+            [
+                "long_usd:Float64:false",
+                "price:Float64:true",
+                "short_usd:Float64:false",
+                "total_usd:Float64:false",
+                "ts:Timestamp:false",
+            ]
+        );
+    }
+
+    #[test]
+    fn csv_writes_header_then_pages() {
+        let mut writer = CsvWriter::new(Vec::new()).expect("header");
+        let pages: Vec<std::result::Result<_, crate::api::Error>> = vec![
+            Ok(vec![FundingRateHistoryView {
+                timestamp: 1,
+                funding_rate: Some(0.5),
+            }]),
+            Ok(vec![FundingRateHistoryView {
+                timestamp: 2,
+                funding_rate: None,
+            }]),
+        ];
+
+        assert_eq!(writer.write_pages(pages).expect("written"), 2);
+
+        let csv = String::from_utf8(writer.finish().expect("flushed")).expect("utf-8");
+        assert_eq!(csv, "timestamp,funding_rate\n1,0.5\n2,\n");
+    }
+
+    #[test]
+    fn parquet_honors_row_group_size() {
+        let candles: Vec<CexCandleView> = (0..5)
+            .map(|i| CexCandleView {
+                timestamp: i * 60_000,
+                ..Default::default()
+            })
+            .collect();
+        let mut writer =
+            ParquetWriter::new(Vec::new(), ParquetWriterOptions::new().row_group_size(2))
+                .expect("writer");
+        writer.write(&candles[..3]).expect("page 1");
+        writer.write(&candles[3..]).expect("page 2");
+
+        let file = Bytes::from(writer.finish().expect("footer"));
+        let reader = SerializedFileReader::new(file).expect("valid parquet");
+        let metadata = reader.metadata();
+
+        assert_eq!(metadata.file_metadata().num_rows(), 5);
+        assert_eq!(metadata.num_row_groups(), 3);
+    }
+}
@@ -0,0 +1,349 @@
+//! Funding rate analytics across venues with different schedules.
+//!
+//! Funding rates are quoted per interval, and venues settle every 1h, 4h or
+//! 8h, so raw rates are not comparable. This module normalizes them:
+//!
+//! - [`apr`](crate::funding::apr) and
+//!   [`FundingRateLatestResponse::apr`](crate::FundingRateLatestResponse::apr)
+//!   annualize a per-interval rate;
+//! - [`next_funding_at`](crate::funding::next_funding_at) predicts the next
+//!   settlement on an interval grid aligned to UTC midnight;
+//! - [`carry`](crate::funding::carry) sums the funding a position paid or
+//!   received over a window of [`FundingRateHistoryView`]s;
+//! - [`FundingSpreadMatrix`](crate::funding::FundingSpreadMatrix) compares
+//!   the annualized rates of one base asset across exchanges, and
+//!   [`Client::funding_spreads`](crate::Client::funding_spreads) fetches
+//!   one.
+//!
+//! Rates keep the unit the API reports them in: a fraction in, a fraction
+//! out. Timestamps are UTC milliseconds.
+//!
+//! ```
+//! use datamaxi::funding::{apr, carry, FundingSpreadMatrix};
+//! use datamaxi::{FundingRateHistoryView, FundingRateLatestResponse};
+//!
+//! // 0.01% every 8h is ~10.95% a year; every 1h, eight times that.
+//! assert!((apr(0.0001, 8.0) - 0.1095).abs() < 1e-12);
+//!
+//! let latest = |exchange: &str, rate: f64, hours: i64| FundingRateLatestResponse {
+//!     base: "BTC".into(),
+//!     exchange: exchange.into(),
+//!     funding_rate: Some(rate),
+//!     interval_hours: Some(hours),
+//!     ..Default::default()
+//! };
+//! let matrix = FundingSpreadMatrix::new("BTC", &[latest("binance", 0.0001, 8), latest("bybit", 0.00005, 1)]);
+//! let (short, long, spread) = matrix.widest().expect("two venues");
+//! assert_eq!((short.exchange.as_str(), long.exchange.as_str()), ("bybit", "binance"));
+//! assert!((spread - 0.3285).abs() < 1e-12);
+//!
+//! // A 10k long over two 8h settlements of 0.01% pays 2 in funding.
+//! let history = [3_600_000, 32_400_000].map(|timestamp| FundingRateHistoryView {
+//!     timestamp,
+//!     funding_rate: Some(0.0001),
+//! });
+//! assert!((carry(&history, 10_000.0, 0, 86_400_000).pnl + 2.0).abs() < 1e-9);
+//! ```
+
+use crate::api::{Error, Result};
+use crate::exchange::Exchange;
+use crate::generated::{FundingRateHistoryView, FundingRateLatestResponse};
+use serde::{Deserialize, Serialize};
+
+/// Hours in a (365-day) year.
+pub const HOURS_PER_YEAR: f64 = 24.0 * 365.0;
+
+const HOUR_MS: i64 = 3_600_000;
+
+/// Annualizes a rate paid every `interval_hours` (simple, not compounded).
+pub fn apr(rate: f64, interval_hours: f64) -> f64 {
+    rate * HOURS_PER_YEAR / interval_hours
+}
+
+/// The first settlement strictly after `after`, on a grid of
+/// `interval_hours` aligned to UTC midnight (e.g. 00:00, 08:00 and 16:00
+/// for 8h). `None` for a non-positive interval.
+pub fn next_funding_at(interval_hours: i64, after: i64) -> Option<i64> {
+    if interval_hours <= 0 {
+        return None;
+    }
+    let step = interval_hours * HOUR_MS;
+    Some((after.div_euclid(step) + 1) * step)
+}
+
+/// The settlement interval of a history, in whole hours: the median gap
+/// between consecutive timestamps, rounded. `None` with fewer than two
+/// points.
+pub fn infer_interval_hours(history: &[FundingRateHistoryView]) -> Option<i64> {
+    let mut timestamps: Vec<i64> = history.iter().map(|point| point.timestamp).collect();
+    timestamps.sort_unstable();
+    timestamps.dedup();
+    let mut gaps: Vec<i64> = timestamps
+        .windows(2)
+        .map(|pair| pair[1] - pair[0])
+        .collect();
+    if gaps.is_empty() {
+        return None;
+    }
+    gaps.sort_unstable();
+    let median = gaps[gaps.len() / 2];
+    Some(((median + HOUR_MS / 2) / HOUR_MS).max(1))
+}
+
+impl FundingRateLatestResponse {
+    /// The funding rate annualized over [`interval_hours`](Self::interval_hours);
+    /// `None` if either is missing.
+    pub fn apr(&self) -> Option<f64> {
+        let rate = self.funding_rate?;
+        let hours = self.interval_hours.filter(|hours| *hours > 0)?;
+        Some(apr(rate, hours as f64))
+    }
+
+    /// The next settlement after `after` (see [`next_funding_at`]); `None`
+    /// without an interval.
+    pub fn next_funding_at(&self, after: i64) -> Option<i64> {
+        next_funding_at(self.interval_hours?, after)
+    }
+}
+
+/// Funding paid or received by a position over a window.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Carry {
+    /// Settlements in the window.
+    pub payments: usize,
+    /// Sum of the settled rates.
+    pub cumulative_rate: f64,
+    /// Funding received (positive) or paid (negative) by the position.
+    pub pnl: f64,
+    /// [`cumulative_rate`](Self::cumulative_rate) annualized over the
+    /// window's length.
+    pub annualized: f64,
+}
+
+/// The funding carry of a position of `notional` (positive long, negative
+/// short) over the settlements in `from < timestamp <= to`.
+///
+/// Longs pay positive funding and shorts receive it. Points without a rate
+/// are skipped.
+pub fn carry(history: &[FundingRateHistoryView], notional: f64, from: i64, to: i64) -> Carry {
+    let (payments, cumulative_rate) = history
+        .iter()
+        .filter(|point| point.timestamp > from && point.timestamp <= to)
+        .filter_map(|point| point.funding_rate)
+        .fold((0, 0.0), |(count, sum), rate| (count + 1, sum + rate));
+    let hours = (to - from) as f64 / HOUR_MS as f64;
+    Carry {
+        payments,
+        cumulative_rate,
+        pnl: -notional * cumulative_rate,
+        annualized: if hours > 0.0 {
+            cumulative_rate * HOURS_PER_YEAR / hours
+        } else {
+            0.0
+        },
+    }
+}
+
+/// The latest funding of one base asset on one exchange.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FundingVenue {
+    /// The exchange.
+    pub exchange: Exchange,
+    /// The exchange's symbol, e.g. `BTC-USDT`.
+    pub symbol: String,
+    /// The per-interval rate.
+    pub rate: f64,
+    /// The settlement interval, in hours.
+    pub interval_hours: i64,
+    /// The annualized rate.
+    pub apr: f64,
+    /// The next settlement after the rate was read, UTC milliseconds.
+    pub next_funding_at: i64,
+}
+
+/// Annualized funding of one base asset across exchanges.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FundingSpreadMatrix {
+    /// The base asset, upper-cased.
+    pub base: String,
+    /// One entry per exchange, sorted by exchange.
+    pub venues: Vec<FundingVenue>,
+}
+
+impl FundingSpreadMatrix {
+    /// Builds the matrix from the latest rates of `base`; rows of other
+    /// bases, or without a rate or interval, are left out. When an exchange
+    /// appears twice, the most recent row wins.
+    pub fn new(base: &str, latest: &[FundingRateLatestResponse]) -> Self {
+        let base = base.trim().to_ascii_uppercase();
+        let mut rows: Vec<&FundingRateLatestResponse> = latest
+            .iter()
+            .filter(|row| row.base.eq_ignore_ascii_case(&base))
+            .collect();
+        rows.sort_by_key(|row| {
+            (
+                Exchange::new(&row.exchange),
+                std::cmp::Reverse(row.timestamp),
+            )
+        });
+        rows.dedup_by_key(|row| Exchange::new(&row.exchange));
+        let venues = rows
+            .into_iter()
+            .filter_map(|row| {
+                Some(FundingVenue {
+                    exchange: Exchange::new(&row.exchange),
+                    symbol: row.symbol.clone(),
+                    rate: row.funding_rate?,
+                    interval_hours: row.interval_hours?,
+                    apr: row.apr()?,
+                    next_funding_at: row.next_funding_at(row.timestamp)?,
+                })
+            })
+            .collect();
+        FundingSpreadMatrix { base, venues }
+    }
+
+    fn venue(&self, exchange: &str) -> Option<&FundingVenue> {
+        let exchange = Exchange::new(exchange);
+        self.venues.iter().find(|venue| venue.exchange == exchange)
+    }
+
+    /// The annualized spread earned by shorting on `short` and going long on
+    /// `long`: `apr(short) - apr(long)`.
+    pub fn spread(&self, short: &str, long: &str) -> Option<f64> {
+        Some(self.venue(short)?.apr - self.venue(long)?.apr)
+    }
+
+    /// Every pairwise spread: `matrix()[i][j]` shorts `venues[i]` and goes
+    /// long `venues[j]`.
+    pub fn matrix(&self) -> Vec<Vec<f64>> {
+        self.venues
+            .iter()
+            .map(|short| {
+                self.venues
+                    .iter()
+                    .map(|long| short.apr - long.apr)
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// The widest spread, as (short venue, long venue, spread); `None` with
+    /// fewer than two venues.
+    pub fn widest(&self) -> Option<(&FundingVenue, &FundingVenue, f64)> {
+        let short = self.venues.iter().max_by(|a, b| a.apr.total_cmp(&b.apr))?;
+        let long = self.venues.iter().min_by(|a, b| a.apr.total_cmp(&b.apr))?;
+        (self.venues.len() > 1).then_some((short, long, short.apr - long.apr))
+    }
+}
+
+/// Body of the async and blocking `funding_spreads`; see `get_loop!` in [`crate::api`].
+macro_rules! funding_spreads {
+    ($client:expr, $base:expr, $quote:expr $(, $aw:ident)?) => {{
+        let funding = $client.funding_rate();
+        let (base, quote): (&str, &str) = ($base, $quote);
+        let symbol = format!("{}-{}", base.trim(), quote.trim()).to_ascii_uppercase();
+        let exchanges = funding.exchanges()$(.$aw)?;
+        let mut latest = Vec::new();
+        for exchange in exchanges? {
+            let response = funding.latest(exchange, symbol.as_str())$(.$aw)?;
+            match response {
+                Ok(response) => latest.push(response),
+                // Exchanges not listing the symbol are left out.
+                Err(Error::NotFound { .. }) => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(FundingSpreadMatrix::new(base, &latest))
+    }};
+}
+
+impl crate::api::Client {
+    /// Builds a [`FundingSpreadMatrix`] for `base` quoted in `quote` (e.g.
+    /// `BTC`, `USDT`) from the latest rate on every funding exchange (one
+    /// request per exchange, plus the exchange list).
+    pub async fn funding_spreads(&self, base: &str, quote: &str) -> Result<FundingSpreadMatrix> {
+        funding_spreads!(self, base, quote, await)
+    }
+}
+
+#[cfg(feature = "sync")]
+impl crate::api::sync::Client {
+    /// Blocking mirror of the async `Client::funding_spreads`.
+    pub fn funding_spreads(&self, base: &str, quote: &str) -> Result<FundingSpreadMatrix> {
+        funding_spreads!(self, base, quote)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(timestamp: i64, rate: f64) -> FundingRateHistoryView {
+        FundingRateHistoryView {
+            timestamp,
+            funding_rate: Some(rate),
+        }
+    }
+
+    #[test]
+    fn intervals_and_next_settlement() {
+        let history = [0, 8, 16, 24, 40].map(|hours| point(hours * HOUR_MS, 0.0));
+        assert_eq!(infer_interval_hours(&history), Some(8));
+        assert_eq!(infer_interval_hours(&history[..1]), None);
+
+        // 05:30 UTC: next 8h settlement at 08:00, next 1h at 06:00.
+        let after = 5 * HOUR_MS + HOUR_MS / 2;
+        assert_eq!(next_funding_at(8, after), Some(8 * HOUR_MS));
+        assert_eq!(next_funding_at(1, after), Some(6 * HOUR_MS));
+        assert_eq!(next_funding_at(8, 8 * HOUR_MS), Some(16 * HOUR_MS));
+        assert_eq!(next_funding_at(0, after), None);
+    }
+
+    #[test]
+    fn carry_signs_and_window() {
+        let history = [
+            point(0, 0.001),
+            point(8 * HOUR_MS, 0.0002),
+            point(16 * HOUR_MS, -0.0001),
+            FundingRateHistoryView {
+                timestamp: 20 * HOUR_MS,
+                funding_rate: None,
+            },
+            point(24 * HOUR_MS, 0.5),
+        ];
+        let short = carry(&history, -1_000.0, 0, 16 * HOUR_MS);
+        assert_eq!(short.payments, 2);
+        assert!((short.cumulative_rate - 0.0001).abs() < 1e-15);
+        assert!((short.pnl - 0.1).abs() < 1e-12);
+        assert!((short.annualized - 0.0001 * HOURS_PER_YEAR / 16.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn matrix_dedups_and_skips_incomplete_rows() {
+        let row = |exchange: &str, timestamp: i64, rate: Option<f64>| FundingRateLatestResponse {
+            base: "btc".into(),
+            exchange: exchange.into(),
+            timestamp,
+            funding_rate: rate,
+            interval_hours: Some(4),
+            ..Default::default()
+        };
+        let matrix = FundingSpreadMatrix::new(
+            "BTC",
+            &[
+                row("okx", 0, Some(0.0001)),
+                row("Binance", 0, Some(0.0)),
+                row("binance", HOUR_MS, Some(0.0002)),
+                row("bybit", 0, None),
+            ],
+        );
+        let exchanges: Vec<&str> = matrix.venues.iter().map(|v| v.exchange.as_str()).collect();
+        assert_eq!(exchanges, ["binance", "okx"]);
+        assert_eq!(matrix.venues[0].next_funding_at, 4 * HOUR_MS);
+        let spread = matrix.spread("binance", "okx").expect("both present");
+        assert!((spread - apr(0.0001, 4.0)).abs() < 1e-12);
+        assert_eq!(matrix.matrix()[1][0], -spread);
+        assert!(matrix.spread("binance", "bybit").is_none());
+    }
+}
@@ -0,0 +1,423 @@
+//! Re-denominating responses into another currency at forex rates.
+//!
+//! Candles, tickers and open-interest stats come back in the currency chosen
+//! with [`CexCandleCurrency`](crate::CexCandleCurrency),
+//! [`TickerCurrency`](crate::TickerCurrency) or
+//! [`CexSymbolOiStatsCurrency`](crate::CexSymbolOiStatsCurrency). A
+//! [`RateTable`](crate::fx::RateTable) records
+//! [`Forex`](crate::Forex) rates over time and converts values after the
+//! fact, each at the rate in force at its own timestamp; the result is
+//! [`Converted`](crate::fx::Converted), which carries the
+//! [`AppliedRate`](crate::fx::AppliedRate) next to the value.
+//!
+//! The forex endpoint only serves the latest rate, so a table's history is
+//! whatever has been recorded into it: keep one table alive and refresh it
+//! with [`Client::refresh_rate`](crate::Client::refresh_rate), which fetches
+//! at most once per time-to-live. Values older than the first recorded rate
+//! are converted at that first rate and flagged
+//! [`extrapolated`](crate::fx::AppliedRate::extrapolated).
+//!
+//! ```no_run
+//! use datamaxi::enums::Currency;
+//! use datamaxi::fx::{RateTable, USD_KRW};
+//! use datamaxi::{CexCandleMarket, CexCandleOptions, Client};
+//! use std::time::Duration;
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = Client::new("my_api_key");
+//! let mut rates = RateTable::new().ttl(Duration::from_secs(300));
+//! client.refresh_rate(&mut rates, USD_KRW).await?;
+//!
+//! let candles = client
+//!     .cex_candle()
+//!     .get("binance", "BTC-USDT", CexCandleOptions::new().market(CexCandleMarket::Spot))
+//!     .await?;
+//! let in_krw = rates
+//!     .convert_all(candles.data, &Currency::USD, &Currency::KRW)
+//!     .expect("a USD-KRW rate was recorded");
+//! for candle in &in_krw {
+//!     println!("{} {} (at {})", candle.value.timestamp, candle.value.close, candle.rate.rate);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::api::Result;
+use crate::enums::Currency;
+use crate::generated::{
+    CexCandleView, CexSymbolOiStatsView, CexSymbolOiView, ForexResponse, TickerView,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, Instant};
+
+/// The forex symbol quoting KRW per USD.
+pub const USD_KRW: &str = "USD-KRW";
+
+/// Rates kept per currency pair, unless configured.
+pub const DEFAULT_MAX_RATES: usize = 10_000;
+
+/// A unix timestamp below this is in seconds rather than milliseconds.
+const MILLIS_THRESHOLD: i64 = 100_000_000_000;
+
+/// The rate a value was converted at.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AppliedRate {
+    /// The currency converted from.
+    pub from: Currency,
+    /// The currency converted to.
+    pub to: Currency,
+    /// Units of `to` per unit of `from`; `1` when the two are the same.
+    pub rate: f64,
+    /// When the rate was recorded, UTC milliseconds (the value's own
+    /// timestamp for an identity conversion).
+    pub timestamp: i64,
+    /// Whether the recorded symbol was `to-from` and its rate inverted.
+    pub inverted: bool,
+    /// Whether no rate was recorded at or before the value's timestamp, so
+    /// the earliest later rate was used instead.
+    pub extrapolated: bool,
+}
+
+/// A value re-denominated into another currency, with the rate used.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Converted<T> {
+    /// The value, with its currency fields converted.
+    pub value: T,
+    /// The rate the fields were multiplied by.
+    pub rate: AppliedRate,
+}
+
+/// A response row whose currency-denominated fields can be converted.
+pub trait Convert {
+    /// When the value was observed, UTC milliseconds.
+    fn timestamp_millis(&self) -> i64;
+
+    /// Multiplies every currency-denominated field by `rate`, leaving
+    /// amounts in base units and percentages untouched.
+    fn scale(&mut self, rate: f64);
+}
+
+fn scale(field: &mut Option<f64>, rate: f64) {
+    if let Some(value) = field {
+        *value *= rate;
+    }
+}
+
+impl Convert for CexCandleView {
+    fn timestamp_millis(&self) -> i64 {
+        self.timestamp
+    }
+
+    /// Scales open, high, low and close; volume is in base units.
+    fn scale(&mut self, rate: f64) {
+        self.open *= rate;
+        self.high *= rate;
+        self.low *= rate;
+        self.close *= rate;
+    }
+}
+
+impl Convert for TickerView {
+    fn timestamp_millis(&self) -> i64 {
+        self.timestamp
+    }
+
+    /// Scales the price, the price 24h ago, the best bid and ask, and the
+    /// 2% order-book depths, which are currency amounts too.
+    fn scale(&mut self, rate: f64) {
+        scale(&mut self.price, rate);
+        scale(&mut self.price_24h, rate);
+        scale(&mut self.highest_bid, rate);
+        scale(&mut self.lowest_ask, rate);
+        scale(&mut self.lower_depth, rate);
+        scale(&mut self.upper_depth, rate);
+    }
+}
+
+impl Convert for CexSymbolOiStatsView {
+    fn timestamp_millis(&self) -> i64 {
+        self.ts
+    }
+
+    /// Scales `open_interest_usd` and `volume_24h_usd`, which then hold the
+    /// target currency, as they do when the API converts with `currency`.
+    fn scale(&mut self, rate: f64) {
+        scale(&mut self.open_interest_usd, rate);
+        scale(&mut self.volume_24h_usd, rate);
+    }
+}
+
+impl Convert for CexSymbolOiView {
+    fn timestamp_millis(&self) -> i64 {
+        self.ts
+    }
+
+    /// Scales `open_interest_usd`.
+    fn scale(&mut self, rate: f64) {
+        scale(&mut self.open_interest_usd, rate);
+    }
+}
+
+/// Forex rates recorded over time, per currency pair.
+#[derive(Debug, Clone)]
+pub struct RateTable {
+    rates: HashMap<(Currency, Currency), BTreeMap<i64, f64>>,
+    fetched: BTreeMap<String, Instant>,
+    ttl: Duration,
+    max_rates: usize,
+}
+
+impl Default for RateTable {
+    fn default() -> Self {
+        RateTable::new()
+    }
+}
+
+impl RateTable {
+    /// An empty table that refreshes a symbol at most once a minute.
+    pub fn new() -> Self {
+        RateTable {
+            rates: HashMap::new(),
+            fetched: BTreeMap::new(),
+            ttl: Duration::from_secs(60),
+            max_rates: DEFAULT_MAX_RATES,
+        }
+    }
+
+    /// How long a fetched rate is reused before
+    /// [`Client::refresh_rate`](crate::Client::refresh_rate) fetches again.
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Keeps at most `max` rates per pair, dropping the oldest. Defaults to
+    /// [`DEFAULT_MAX_RATES`].
+    pub fn max_rates(mut self, max: usize) -> Self {
+        self.max_rates = max.max(1);
+        self
+    }
+
+    /// Records a forex response. Its symbol is read as `FROM-TO` (units of
+    /// `TO` per `FROM`), and a timestamp in seconds as such. Returns `false`
+    /// for a symbol that is not two currencies joined by `-`.
+    pub fn record(&mut self, forex: &ForexResponse) -> bool {
+        let Some((from, to)) = forex.symbol.split_once('-') else {
+            return false;
+        };
+        if from.is_empty() || to.is_empty() {
+            return false;
+        }
+        let timestamp = if forex.timestamp.abs() < MILLIS_THRESHOLD {
+            forex.timestamp * 1000
+        } else {
+            forex.timestamp
+        };
+        self.insert(
+            Currency::from(from),
+            Currency::from(to),
+            timestamp,
+            forex.rate,
+        );
+        true
+    }
+
+    /// Records `rate` units of `to` per `from` at `timestamp` (UTC
+    /// milliseconds), e.g. from a rate history kept elsewhere.
+    pub fn insert(&mut self, from: Currency, to: Currency, timestamp: i64, rate: f64) {
+        let rates = self.rates.entry((from, to)).or_default();
+        rates.insert(timestamp, rate);
+        while rates.len() > self.max_rates {
+            rates.pop_first();
+        }
+    }
+
+    /// The rate from `from` to `to` in force at `timestamp`: the latest
+    /// recorded at or before it, or the earliest if all are later (flagged
+    /// [`extrapolated`](AppliedRate::extrapolated)). Falls back to the
+    /// inverse of the `to-from` rate; `None` if neither was recorded.
+    pub fn rate_at(&self, from: &Currency, to: &Currency, timestamp: i64) -> Option<AppliedRate> {
+        if from == to {
+            return Some(AppliedRate {
+                from: from.clone(),
+                to: to.clone(),
+                rate: 1.0,
+                timestamp,
+                inverted: false,
+                extrapolated: false,
+            });
+        }
+        let lookup = |key: (Currency, Currency)| {
+            let rates = self.rates.get(&key)?;
+            rates
+                .range(..=timestamp)
+                .next_back()
+                .or_else(|| rates.iter().next())
+                .map(|(at, rate)| (*at, *rate))
+        };
+        let (at, rate, inverted) = match lookup((from.clone(), to.clone())) {
+            Some((at, rate)) => (at, rate, false),
+            None => {
+                let (at, rate) = lookup((to.clone(), from.clone()))?;
+                (at, 1.0 / rate, true)
+            }
+        };
+        Some(AppliedRate {
+            from: from.clone(),
+            to: to.clone(),
+            rate,
+            timestamp: at,
+            inverted,
+            extrapolated: at > timestamp,
+        })
+    }
+
+    /// Converts `value` from `from` to `to` at the rate in force at its
+    /// timestamp, or `None` if no rate between the two was recorded.
+    pub fn convert<T: Convert>(
+        &self,
+        mut value: T,
+        from: &Currency,
+        to: &Currency,
+    ) -> Option<Converted<T>> {
+        let rate = self.rate_at(from, to, value.timestamp_millis())?;
+        value.scale(rate.rate);
+        Some(Converted { value, rate })
+    }
+
+    /// Converts every value of a series, each at its own rate; `None` if no
+    /// rate between the two currencies was recorded.
+    pub fn convert_all<T: Convert>(
+        &self,
+        values: impl IntoIterator<Item = T>,
+        from: &Currency,
+        to: &Currency,
+    ) -> Option<Vec<Converted<T>>> {
+        values
+            .into_iter()
+            .map(|value| self.convert(value, from, to))
+            .collect()
+    }
+
+    /// Whether `symbol` was never fetched, or longer ago than the TTL.
+    fn is_stale(&self, symbol: &str) -> bool {
+        self.fetched
+            .get(symbol)
+            .is_none_or(|at| at.elapsed() >= self.ttl)
+    }
+}
+
+/// Body of the async and blocking `refresh_rate`; see `get_loop!` in [`crate::api`].
+macro_rules! refresh_rate {
+    ($client:expr, $table:expr, $symbol:expr $(, $aw:ident)?) => {{
+        let table: &mut RateTable = $table;
+        let symbol: &str = $symbol;
+        if table.is_stale(symbol) {
+            let forex = $client.forex().get(symbol)$(.$aw)?;
+            table.record(&forex?);
+            table.fetched.insert(symbol.to_string(), Instant::now());
+        }
+        Ok(())
+    }};
+}
+
+impl crate::api::Client {
+    /// Fetches the `symbol` rate (e.g. [`USD_KRW`]) into `table`, unless it
+    /// was fetched within the table's TTL.
+    pub async fn refresh_rate(&self, table: &mut RateTable, symbol: &str) -> Result<()> {
+        refresh_rate!(self, table, symbol, await)
+    }
+}
+
+#[cfg(feature = "sync")]
+impl crate::api::sync::Client {
+    /// Blocking mirror of the async `Client::refresh_rate`.
+    pub fn refresh_rate(&self, table: &mut RateTable, symbol: &str) -> Result<()> {
+        refresh_rate!(self, table, symbol)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const T: i64 = 1_735_689_600_000;
+
+    fn table() -> RateTable {
+        let mut table = RateTable::new();
+        assert!(table.record(&ForexResponse {
+            timestamp: T / 1000,
+            rate: 1_300.0,
+            symbol: USD_KRW.into(),
+        }));
+        table.insert(Currency::USD, Currency::KRW, T + 60_000, 1_400.0);
+        table
+    }
+
+    fn candle(timestamp: i64, close: f64) -> CexCandleView {
+        CexCandleView {
+            timestamp,
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: 2.0,
+        }
+    }
+
+    #[test]
+    fn series_are_converted_at_each_points_rate() {
+        let table = table();
+        let converted = table
+            .convert_all(
+                [
+                    candle(T - 60_000, 1.0),
+                    candle(T + 30_000, 1.0),
+                    candle(T + 90_000, 1.0),
+                ],
+                &Currency::USD,
+                &Currency::KRW,
+            )
+            .expect("rate recorded");
+
+        let closes: Vec<f64> = converted.iter().map(|c| c.value.close).collect();
+        assert_eq!(closes, [1_300.0, 1_300.0, 1_400.0]);
+        let extrapolated: Vec<bool> = converted.iter().map(|c| c.rate.extrapolated).collect();
+        assert_eq!(extrapolated, [true, false, false]);
+        assert_eq!(converted[2].rate.timestamp, T + 60_000);
+        assert_eq!(converted[2].value.volume, 2.0);
+    }
+
+    #[test]
+    fn inverse_identity_and_missing_rates() {
+        let table = table();
+        let ticker = TickerView {
+            timestamp: T,
+            price: Some(130_000.0),
+            highest_bid: None,
+            lower_depth: Some(2_600_000.0),
+            upper_depth: Some(3_900_000.0),
+            ..Default::default()
+        };
+        let usd = table
+            .convert(ticker, &Currency::KRW, &Currency::USD)
+            .expect("inverse of USD-KRW");
+        assert!(usd.rate.inverted);
+        assert!((usd.value.price.expect("price kept") - 100.0).abs() < 1e-9);
+        assert_eq!(usd.value.highest_bid, None);
+        let depths = (usd.value.lower_depth, usd.value.upper_depth);
+        assert!(matches!(depths, (Some(lower), Some(upper))
+            if (lower - 2_000.0).abs() < 1e-9 && (upper - 3_000.0).abs() < 1e-9));
+
+        let same = table.rate_at(&Currency::KRW, &Currency::from("krw"), T);
+        assert_eq!(same.map(|rate| rate.rate), Some(1.0));
+        assert!(table
+            .rate_at(&Currency::USD, &Currency::from("EUR"), T)
+            .is_none());
+        assert!(!RateTable::new().record(&ForexResponse {
+            symbol: "USDKRW".into(),
+            ..Default::default()
+        }));
+    }
+}
@@ -0,0 +1,480 @@
+//! Dense exchange × token matrices from a [`LiquidationHeatmapResponse`].
+//!
+//! The heatmap arrives as a flat list of cells next to per-exchange and
+//! per-token summaries. [`LiquidationHeatmapResponse::pivot`] indexes the
+//! cells into a [`HeatmapMatrix`](crate::heatmap::HeatmapMatrix), one row
+//! per exchange and one column per token, with row, column and grand totals
+//! recomputed from the cells. [`HeatmapMatrix::top`](crate::heatmap::HeatmapMatrix::top)
+//! keeps the largest rows and columns, and
+//! [`LiquidationHeatmapResponse::reconcile`] lists every place the cells
+//! disagree with the server-provided summaries.
+//!
+//! ```
+//! use datamaxi::{LiquidationHeatmapCell, LiquidationHeatmapResponse};
+//!
+//! let cell = |exchange: &str, token_id: &str, long_usd: f64, short_usd: f64| {
+//!     LiquidationHeatmapCell {
+//!         exchange: exchange.into(),
+//!         token_id: token_id.into(),
+//!         long_usd,
+//!         short_usd,
+//!         total_usd: long_usd + short_usd,
+//!         ..Default::default()
+//!     }
+//! };
+//! let heatmap = LiquidationHeatmapResponse {
+//!     cells: vec![
+//!         cell("binance", "bitcoin", 30.0, 10.0),
+//!         cell("bybit", "bitcoin", 5.0, 5.0),
+//!         cell("binance", "ethereum", 8.0, 2.0),
+//!     ],
+//!     grand_total: 60.0,
+//!     ..Default::default()
+//! };
+//!
+//! let matrix = heatmap.pivot();
+//! assert_eq!(matrix.get("binance", "bitcoin").map(|usd| usd.total), Some(40.0));
+//! assert_eq!(matrix.row_totals[0].total, 50.0);
+//! assert_eq!(matrix.top(1, 1).tokens, ["bitcoin"]);
+//! // No summaries were provided, so every row and column is reported.
+//! assert!(!heatmap.reconcile(1e-9).is_empty());
+//! ```
+//!
+//! [`LiquidationHeatmapResponse`]: crate::LiquidationHeatmapResponse
+//! [`LiquidationHeatmapResponse::pivot`]: crate::LiquidationHeatmapResponse::pivot
+//! [`LiquidationHeatmapResponse::reconcile`]: crate::LiquidationHeatmapResponse::reconcile
+
+use crate::exchange::Exchange;
+use crate::generated::LiquidationHeatmapResponse;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ops::AddAssign;
+
+/// Liquidated USD split by side.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Usd {
+    /// Long positions liquidated.
+    pub long: f64,
+    /// Short positions liquidated.
+    pub short: f64,
+    /// Both sides, as reported.
+    pub total: f64,
+}
+
+impl AddAssign for Usd {
+    fn add_assign(&mut self, other: Usd) {
+        self.long += other.long;
+        self.short += other.short;
+        self.total += other.total;
+    }
+}
+
+/// A dense exchange × token matrix of liquidated USD.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HeatmapMatrix {
+    /// Row labels.
+    pub exchanges: Vec<Exchange>,
+    /// Column labels: token ids.
+    pub tokens: Vec<String>,
+    /// Base asset of each column, empty when no cell carried one.
+    pub bases: Vec<String>,
+    /// `cells[row][column]`; zero where the heatmap had no cell.
+    pub cells: Vec<Vec<Usd>>,
+    /// Sum of each row.
+    pub row_totals: Vec<Usd>,
+    /// Sum of each column.
+    pub column_totals: Vec<Usd>,
+    /// Sum of every cell.
+    pub grand_total: Usd,
+}
+
+impl HeatmapMatrix {
+    /// The cell of one exchange and token id.
+    pub fn get(&self, exchange: &str, token_id: &str) -> Option<Usd> {
+        let row = self.row_index(exchange)?;
+        let column = self.column_index(token_id)?;
+        Some(self.cells[row][column])
+    }
+
+    /// Position of an exchange among the rows.
+    pub fn row_index(&self, exchange: &str) -> Option<usize> {
+        let exchange = Exchange::new(exchange);
+        self.exchanges.iter().position(|row| *row == exchange)
+    }
+
+    /// Position of a token id among the columns.
+    pub fn column_index(&self, token_id: &str) -> Option<usize> {
+        self.tokens.iter().position(|column| column == token_id)
+    }
+
+    /// The `exchanges` rows and `tokens` columns with the largest totals,
+    /// keeping their order. Totals are recomputed over the cells kept.
+    pub fn top(&self, exchanges: usize, tokens: usize) -> HeatmapMatrix {
+        let rows = largest(&self.row_totals, exchanges);
+        let columns = largest(&self.column_totals, tokens);
+        let mut matrix = HeatmapMatrix {
+            exchanges: rows.iter().map(|&r| self.exchanges[r].clone()).collect(),
+            tokens: columns.iter().map(|&c| self.tokens[c].clone()).collect(),
+            bases: columns.iter().map(|&c| self.bases[c].clone()).collect(),
+            cells: rows
+                .iter()
+                .map(|&r| columns.iter().map(|&c| self.cells[r][c]).collect())
+                .collect(),
+            ..Default::default()
+        };
+        matrix.total();
+        matrix
+    }
+
+    fn total(&mut self) {
+        self.row_totals = vec![Usd::default(); self.exchanges.len()];
+        self.column_totals = vec![Usd::default(); self.tokens.len()];
+        self.grand_total = Usd::default();
+        for (r, row) in self.cells.iter().enumerate() {
+            for (c, &usd) in row.iter().enumerate() {
+                self.row_totals[r] += usd;
+                self.column_totals[c] += usd;
+                self.grand_total += usd;
+            }
+        }
+    }
+}
+
+// Indices of the `k` largest totals, in their original order.
+fn largest(totals: &[Usd], k: usize) -> Vec<usize> {
+    let mut indices: Vec<usize> = (0..totals.len()).collect();
+    indices.sort_by(|&a, &b| totals[b].total.total_cmp(&totals[a].total));
+    indices.truncate(k);
+    indices.sort_unstable();
+    indices
+}
+
+/// Where a [`Mismatch`] was found.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Scope {
+    /// A single cell whose total is not its long plus short.
+    Cell {
+        /// The cell's exchange.
+        exchange: Exchange,
+        /// The cell's token id.
+        token_id: String,
+    },
+    /// An exchange row against its entry in `exchanges`.
+    Exchange(Exchange),
+    /// A token column against its entry in `tokens`.
+    Token(String),
+    /// The sum of every cell against `grand_total`.
+    GrandTotal,
+}
+
+/// Which figure disagrees.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Field {
+    /// Long USD.
+    Long,
+    /// Short USD.
+    Short,
+    /// Total USD.
+    Total,
+}
+
+/// A figure the cells and the server summaries disagree on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Mismatch {
+    /// Where.
+    pub scope: Scope,
+    /// Which figure.
+    pub field: Field,
+    /// The server's figure; `None` when the summary has no such row.
+    pub reported: Option<f64>,
+    /// The figure recomputed from the cells.
+    pub pivoted: f64,
+}
+
+fn differs(reported: f64, pivoted: f64, tolerance: f64) -> bool {
+    (reported - pivoted).abs() > tolerance * reported.abs().max(pivoted.abs()).max(1.0)
+}
+
+/// Pushes a mismatch per figure of `pivoted` that differs from `reported`.
+/// With `partial`, the cells only cover part of the summary, so only a
+/// pivoted figure above the reported one is a mismatch.
+fn compare(
+    out: &mut Vec<Mismatch>,
+    scope: Scope,
+    reported: Option<Usd>,
+    pivoted: Usd,
+    tolerance: f64,
+    partial: bool,
+) {
+    let Some(reported) = reported else {
+        out.push(Mismatch {
+            scope,
+            field: Field::Total,
+            reported: None,
+            pivoted: pivoted.total,
+        });
+        return;
+    };
+    for (field, reported, pivoted) in [
+        (Field::Long, reported.long, pivoted.long),
+        (Field::Short, reported.short, pivoted.short),
+        (Field::Total, reported.total, pivoted.total),
+    ] {
+        if differs(reported, pivoted, tolerance) && (!partial || pivoted > reported) {
+            out.push(Mismatch {
+                scope: scope.clone(),
+                field,
+                reported: Some(reported),
+                pivoted,
+            });
+        }
+    }
+}
+
+impl LiquidationHeatmapResponse {
+    /// Indexes the cells into an exchange × token matrix. Rows follow
+    /// `exchanges` and columns follow `tokens` (both sorted by total);
+    /// exchanges or tokens only present in cells come after, largest first.
+    /// Cells repeating an exchange and token are summed.
+    pub fn pivot(&self) -> HeatmapMatrix {
+        let mut exchanges: Vec<Exchange> = self
+            .exchanges
+            .iter()
+            .map(|summary| Exchange::new(&summary.exchange))
+            .collect();
+        let mut tokens: Vec<String> = self
+            .tokens
+            .iter()
+            .map(|summary| summary.token_id.clone())
+            .collect();
+        let mut extra_rows: HashMap<Exchange, f64> = HashMap::new();
+        let mut extra_columns: HashMap<String, f64> = HashMap::new();
+        for cell in &self.cells {
+            let exchange = Exchange::new(&cell.exchange);
+            if !exchanges.contains(&exchange) {
+                *extra_rows.entry(exchange).or_default() += cell.total_usd;
+            }
+            if !tokens.contains(&cell.token_id) {
+                *extra_columns.entry(cell.token_id.clone()).or_default() += cell.total_usd;
+            }
+        }
+        let mut extra_rows: Vec<_> = extra_rows.into_iter().collect();
+        extra_rows.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        exchanges.extend(extra_rows.into_iter().map(|(exchange, _)| exchange));
+        let mut extra_columns: Vec<_> = extra_columns.into_iter().collect();
+        extra_columns.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        tokens.extend(extra_columns.into_iter().map(|(token_id, _)| token_id));
+
+        let mut bases: Vec<String> = tokens
+            .iter()
+            .map(|token_id| {
+                self.tokens
+                    .iter()
+                    .find(|summary| summary.token_id == *token_id)
+                    .map(|summary| summary.base.clone())
+                    .unwrap_or_default()
+            })
+            .collect();
+        let mut matrix = HeatmapMatrix {
+            cells: vec![vec![Usd::default(); tokens.len()]; exchanges.len()],
+            exchanges,
+            tokens,
+            ..Default::default()
+        };
+        for cell in &self.cells {
+            let (Some(r), Some(c)) = (
+                matrix.row_index(&cell.exchange),
+                matrix.column_index(&cell.token_id),
+            ) else {
+                continue;
+            };
+            matrix.cells[r][c] += Usd {
+                long: cell.long_usd,
+                short: cell.short_usd,
+                total: cell.total_usd,
+            };
+            if bases[c].is_empty() {
+                bases[c] = cell.base.clone();
+            }
+        }
+        matrix.bases = bases;
+        matrix.total();
+        matrix
+    }
+
+    /// Checks the cells against the summaries: each cell's total against
+    /// its long plus short, each matrix column against its `tokens` entry,
+    /// each matrix row against its `exchanges` entry, and the sum of every
+    /// cell against `grand_total`. Figures within `tolerance` (relative,
+    /// against the larger of the two or 1 USD) agree.
+    ///
+    /// The server limits cells to the top tokens while `exchanges` and
+    /// `grand_total` cover every token, so rows and the grand total are
+    /// only reported when the cells add up to *more* than the summary.
+    /// Cells and columns are checked strictly.
+    pub fn reconcile(&self, tolerance: f64) -> Vec<Mismatch> {
+        let mut out = Vec::new();
+        for cell in &self.cells {
+            let sum = cell.long_usd + cell.short_usd;
+            if differs(cell.total_usd, sum, tolerance) {
+                out.push(Mismatch {
+                    scope: Scope::Cell {
+                        exchange: Exchange::new(&cell.exchange),
+                        token_id: cell.token_id.clone(),
+                    },
+                    field: Field::Total,
+                    reported: Some(cell.total_usd),
+                    pivoted: sum,
+                });
+            }
+        }
+
+        let matrix = self.pivot();
+        for (exchange, &pivoted) in matrix.exchanges.iter().zip(&matrix.row_totals) {
+            let reported = self
+                .exchanges
+                .iter()
+                .find(|summary| Exchange::new(&summary.exchange) == *exchange)
+                .map(|summary| Usd {
+                    long: summary.long_usd,
+                    short: summary.short_usd,
+                    total: summary.total_usd,
+                });
+            let scope = Scope::Exchange(exchange.clone());
+            compare(&mut out, scope, reported, pivoted, tolerance, true);
+        }
+        for (token_id, &pivoted) in matrix.tokens.iter().zip(&matrix.column_totals) {
+            let reported = self
+                .tokens
+                .iter()
+                .find(|summary| summary.token_id == *token_id)
+                .map(|summary| Usd {
+                    long: summary.long_usd,
+                    short: summary.short_usd,
+                    total: summary.total_usd,
+                });
+            let scope = Scope::Token(token_id.clone());
+            compare(&mut out, scope, reported, pivoted, tolerance, false);
+        }
+        let pivoted = matrix.grand_total.total;
+        if differs(self.grand_total, pivoted, tolerance) && pivoted > self.grand_total {
+            out.push(Mismatch {
+                scope: Scope::GrandTotal,
+                field: Field::Total,
+                reported: Some(self.grand_total),
+                pivoted,
+            });
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generated::{
+        LiquidationHeatmapCell, LiquidationHeatmapExchangesummary, LiquidationHeatmapTokensummary,
+    };
+
+    fn cell(
+        exchange: &str,
+        token_id: &str,
+        long_usd: f64,
+        short_usd: f64,
+    ) -> LiquidationHeatmapCell {
+        LiquidationHeatmapCell {
+            base: token_id[..3].to_ascii_uppercase(),
+            exchange: exchange.into(),
+            token_id: token_id.into(),
+            long_usd,
+            short_usd,
+            total_usd: long_usd + short_usd,
+        }
+    }
+
+    fn heatmap() -> LiquidationHeatmapResponse {
+        let exchange =
+            |exchange: &str, long_usd: f64, short_usd: f64| LiquidationHeatmapExchangesummary {
+                exchange: exchange.into(),
+                long_usd,
+                short_usd,
+                total_usd: long_usd + short_usd,
+            };
+        let token =
+            |token_id: &str, long_usd: f64, short_usd: f64| LiquidationHeatmapTokensummary {
+                token_id: token_id.into(),
+                long_usd,
+                short_usd,
+                total_usd: long_usd + short_usd,
+                ..Default::default()
+            };
+        LiquidationHeatmapResponse {
+            cells: vec![
+                cell("okx", "solana", 1.0, 1.0),
+                cell("Binance", "bitcoin", 30.0, 10.0),
+                cell("bybit", "bitcoin", 5.0, 5.0),
+                cell("binance", "ethereum", 8.0, 2.0),
+            ],
+            exchanges: vec![exchange("binance", 38.0, 12.0), exchange("bybit", 5.0, 5.0)],
+            tokens: vec![token("bitcoin", 35.0, 15.0), token("ethereum", 8.0, 2.0)],
+            grand_total: 62.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn pivot_orders_by_summaries_then_appends_the_rest() {
+        let matrix = heatmap().pivot();
+        assert_eq!(
+            matrix.exchanges,
+            ["binance", "bybit", "okx"].map(Exchange::new)
+        );
+        assert_eq!(matrix.tokens, ["bitcoin", "ethereum", "solana"]);
+        assert_eq!(matrix.bases, ["BIT", "ETH", "SOL"]);
+        assert_eq!(matrix.get("bybit", "ethereum"), Some(Usd::default()));
+        assert_eq!(matrix.column_totals[0].long, 35.0);
+        assert_eq!(matrix.grand_total.total, 62.0);
+
+        let top = matrix.top(2, 1);
+        assert_eq!(top.exchanges, ["binance", "bybit"].map(Exchange::new));
+        assert_eq!(top.cells, [[matrix.cells[0][0]], [matrix.cells[1][0]]]);
+        assert_eq!(top.grand_total.total, 50.0);
+    }
+
+    #[test]
+    fn reconcile_reports_missing_rows_and_bad_cells() {
+        let mut heatmap = heatmap();
+        heatmap.cells[2].total_usd = 11.0;
+        // Tokens outside the cells only add to the row and the grand total.
+        heatmap.exchanges[0].total_usd = 80.0;
+        heatmap.grand_total = 60.0;
+
+        let scopes: Vec<_> = heatmap
+            .reconcile(1e-9)
+            .into_iter()
+            .map(|mismatch| (mismatch.scope, mismatch.field, mismatch.reported))
+            .collect();
+        assert_eq!(
+            scopes,
+            [
+                (
+                    Scope::Cell {
+                        exchange: Exchange::new("bybit"),
+                        token_id: "bitcoin".into(),
+                    },
+                    Field::Total,
+                    Some(11.0),
+                ),
+                (
+                    Scope::Exchange(Exchange::new("bybit")),
+                    Field::Total,
+                    Some(10.0)
+                ),
+                (Scope::Exchange(Exchange::new("okx")), Field::Total, None),
+                (Scope::Token("bitcoin".into()), Field::Total, Some(50.0)),
+                (Scope::Token("solana".into()), Field::Total, None),
+                (Scope::GrandTotal, Field::Total, Some(60.0)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,579 @@
+//! Technical indicators over [`CexCandleView`] series (feature
+//! `indicators`).
+//!
+//! Every indicator is incremental: feed it one candle at a time with
+//! [`Indicator::update`](crate::indicators::Indicator::update) and it returns the latest value once warmed up
+//! (`None` before that).
+//! [`Indicator::compute`](crate::indicators::Indicator::compute) runs one over a whole slice,
+//! and [`Streaming`](crate::indicators::Streaming) wraps one for a polling
+//! loop, where the newest candle is re-delivered while it is still forming.
+//!
+//! | Indicator | Input | Warm-up |
+//! |---|---|---|
+//! | [`Sma`](crate::indicators::Sma) | close | `period` candles |
+//! | [`Ema`](crate::indicators::Ema) | close, seeded with the SMA | `period` candles |
+//! | [`Rsi`](crate::indicators::Rsi) | close, Wilder smoothing | `period + 1` candles |
+//! | [`Atr`](crate::indicators::Atr) | high/low/close, Wilder smoothing | `period` candles |
+//! | [`Bollinger`](crate::indicators::Bollinger) | close, population σ | `period` candles |
+//! | [`Vwap`](crate::indicators::Vwap) | typical price × volume | first candle with volume |
+//!
+//! ```
+//! use datamaxi::indicators::{Indicator, Rsi, Streaming};
+//! # let candles: Vec<datamaxi::CexCandleView> = Vec::new();
+//!
+//! // Batch: one output per input candle.
+//! let rsi: Vec<Option<f64>> = Rsi::new(14).compute(&candles);
+//!
+//! // Polling: re-delivering the forming candle replaces its contribution.
+//! let mut live = Streaming::new(Rsi::new(14));
+//! for candle in &candles {
+//!     let _latest = live.update(candle);
+//! }
+//! ```
+
+use crate::generated::CexCandleView;
+use crate::resample::Resampler;
+use std::collections::VecDeque;
+
+/// An incremental indicator fed one candle at a time, in time order.
+pub trait Indicator {
+    /// The value produced per candle once warmed up.
+    type Output;
+
+    /// Folds in the next candle and returns the indicator's latest value, or
+    /// `None` while it is still warming up.
+    fn update(&mut self, candle: &CexCandleView) -> Option<Self::Output>;
+
+    /// Runs the indicator over `candles`, one output per input.
+    fn compute(mut self, candles: &[CexCandleView]) -> Vec<Option<Self::Output>>
+    where
+        Self: Sized,
+    {
+        candles.iter().map(|candle| self.update(candle)).collect()
+    }
+}
+
+/// Fixed-size window of recent values with a running sum.
+#[derive(Debug, Clone)]
+struct Window {
+    period: usize,
+    values: VecDeque<f64>,
+    sum: f64,
+}
+
+impl Window {
+    fn new(period: usize) -> Self {
+        assert!(period > 0, "indicator period must be at least 1");
+        Window {
+            period,
+            values: VecDeque::with_capacity(period + 1),
+            sum: 0.0,
+        }
+    }
+
+    fn push(&mut self, value: f64) {
+        self.values.push_back(value);
+        self.sum += value;
+        if self.values.len() > self.period {
+            if let Some(old) = self.values.pop_front() {
+                self.sum -= old;
+            }
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.values.len() == self.period
+    }
+
+    fn mean(&self) -> f64 {
+        self.sum / self.values.len() as f64
+    }
+}
+
+/// Simple moving average of the close.
+#[derive(Debug, Clone)]
+pub struct Sma {
+    window: Window,
+}
+
+impl Sma {
+    /// An SMA over the last `period` closes.
+    ///
+    /// # Panics
+    ///
+    /// If `period` is zero.
+    pub fn new(period: usize) -> Self {
+        Sma {
+            window: Window::new(period),
+        }
+    }
+
+    /// Folds in one value and returns the average once `period` have been
+    /// seen.
+    pub fn push(&mut self, value: f64) -> Option<f64> {
+        self.window.push(value);
+        self.window.is_full().then(|| self.window.mean())
+    }
+}
+
+impl Indicator for Sma {
+    type Output = f64;
+
+    fn update(&mut self, candle: &CexCandleView) -> Option<f64> {
+        self.push(candle.close)
+    }
+}
+
+/// Exponential moving average of the close, smoothing `2 / (period + 1)`,
+/// seeded with the SMA of the first `period` closes.
+#[derive(Debug, Clone)]
+pub struct Ema {
+    alpha: f64,
+    seed: Sma,
+    value: Option<f64>,
+}
+
+impl Ema {
+    /// An EMA with the conventional `2 / (period + 1)` smoothing.
+    ///
+    /// # Panics
+    ///
+    /// If `period` is zero.
+    pub fn new(period: usize) -> Self {
+        Ema {
+            alpha: 2.0 / (period as f64 + 1.0),
+            seed: Sma::new(period),
+            value: None,
+        }
+    }
+
+    /// Folds in one value and returns the average once seeded.
+    pub fn push(&mut self, value: f64) -> Option<f64> {
+        self.value = match self.value {
+            Some(previous) => Some(previous + self.alpha * (value - previous)),
+            None => self.seed.push(value),
+        };
+        self.value
+    }
+}
+
+impl Indicator for Ema {
+    type Output = f64;
+
+    fn update(&mut self, candle: &CexCandleView) -> Option<f64> {
+        self.push(candle.close)
+    }
+}
+
+/// Wilder's running average: the plain mean of the first `period` values,
+/// then `(previous * (period - 1) + value) / period`.
+#[derive(Debug, Clone)]
+struct WilderAverage {
+    period: usize,
+    seed: Sma,
+    value: Option<f64>,
+}
+
+impl WilderAverage {
+    fn new(period: usize) -> Self {
+        WilderAverage {
+            period,
+            seed: Sma::new(period),
+            value: None,
+        }
+    }
+
+    fn push(&mut self, value: f64) -> Option<f64> {
+        let n = self.period as f64;
+        self.value = match self.value {
+            Some(previous) => Some((previous * (n - 1.0) + value) / n),
+            None => self.seed.push(value),
+        };
+        self.value
+    }
+}
+
+/// Relative Strength Index of the close (0–100), Wilder smoothing.
+#[derive(Debug, Clone)]
+pub struct Rsi {
+    previous: Option<f64>,
+    gain: WilderAverage,
+    loss: WilderAverage,
+}
+
+impl Rsi {
+    /// An RSI over `period` close-to-close changes (14 is customary).
+    ///
+    /// # Panics
+    ///
+    /// If `period` is zero.
+    pub fn new(period: usize) -> Self {
+        Rsi {
+            previous: None,
+            gain: WilderAverage::new(period),
+            loss: WilderAverage::new(period),
+        }
+    }
+
+    /// Folds in one value and returns the RSI once `period` changes have
+    /// been seen. A window with no losses reads 100.
+    pub fn push(&mut self, value: f64) -> Option<f64> {
+        let previous = self.previous.replace(value)?;
+        let change = value - previous;
+        let gain = self.gain.push(change.max(0.0));
+        let loss = self.loss.push((-change).max(0.0));
+        match (gain, loss) {
+            (Some(_), Some(0.0)) => Some(100.0),
+            (Some(gain), Some(loss)) => Some(100.0 - 100.0 / (1.0 + gain / loss)),
+            _ => None,
+        }
+    }
+}
+
+impl Indicator for Rsi {
+    type Output = f64;
+
+    fn update(&mut self, candle: &CexCandleView) -> Option<f64> {
+        self.push(candle.close)
+    }
+}
+
+/// Average True Range, Wilder smoothing.
+#[derive(Debug, Clone)]
+pub struct Atr {
+    previous_close: Option<f64>,
+    average: WilderAverage,
+}
+
+impl Atr {
+    /// An ATR over `period` true ranges (14 is customary). The first
+    /// candle's true range is its high − low.
+    ///
+    /// # Panics
+    ///
+    /// If `period` is zero.
+    pub fn new(period: usize) -> Self {
+        Atr {
+            previous_close: None,
+            average: WilderAverage::new(period),
+        }
+    }
+}
+
+impl Indicator for Atr {
+    type Output = f64;
+
+    fn update(&mut self, candle: &CexCandleView) -> Option<f64> {
+        let range = candle.high - candle.low;
+        let true_range = match self.previous_close.replace(candle.close) {
+            Some(close) => range
+                .max((candle.high - close).abs())
+                .max((candle.low - close).abs()),
+            None => range,
+        };
+        self.average.push(true_range)
+    }
+}
+
+/// One reading of [`Bollinger`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BollingerBands {
+    /// `middle - k·σ`.
+    pub lower: f64,
+    /// The SMA of the close.
+    pub middle: f64,
+    /// `middle + k·σ`.
+    pub upper: f64,
+}
+
+/// Bollinger Bands: SMA of the close ± `k` population standard deviations.
+#[derive(Debug, Clone)]
+pub struct Bollinger {
+    window: Window,
+    k: f64,
+}
+
+impl Bollinger {
+    /// Bands over `period` closes at `k` deviations (20 and 2.0 are
+    /// customary).
+    ///
+    /// # Panics
+    ///
+    /// If `period` is zero.
+    pub fn new(period: usize, k: f64) -> Self {
+        Bollinger {
+            window: Window::new(period),
+            k,
+        }
+    }
+
+    /// Folds in one value and returns the bands once `period` have been
+    /// seen.
+    pub fn push(&mut self, value: f64) -> Option<BollingerBands> {
+        self.window.push(value);
+        if !self.window.is_full() {
+            return None;
+        }
+        let middle = self.window.mean();
+        let variance = self
+            .window
+            .values
+            .iter()
+            .map(|v| (v - middle).powi(2))
+            .sum::<f64>()
+            / self.window.period as f64;
+        let width = self.k * variance.sqrt();
+        Some(BollingerBands {
+            lower: middle - width,
+            middle,
+            upper: middle + width,
+        })
+    }
+}
+
+impl Indicator for Bollinger {
+    type Output = BollingerBands;
+
+    fn update(&mut self, candle: &CexCandleView) -> Option<BollingerBands> {
+        self.push(candle.close)
+    }
+}
+
+/// Volume-weighted average of the typical price `(high + low + close) / 3`.
+///
+/// Cumulative from the first candle by default; [`Vwap::anchored`] restarts
+/// it at every bucket boundary (e.g. each KST trading day).
+#[derive(Debug, Clone, Default)]
+pub struct Vwap {
+    anchor: Option<Resampler>,
+    session: Option<i64>,
+    price_volume: f64,
+    volume: f64,
+}
+
+impl Vwap {
+    /// A VWAP accumulated over every candle fed to it.
+    pub fn new() -> Self {
+        Vwap::default()
+    }
+
+    /// A VWAP that restarts whenever a candle falls into a new bucket of
+    /// `anchor`.
+    pub fn anchored(anchor: Resampler) -> Self {
+        Vwap {
+            anchor: Some(anchor),
+            ..Vwap::default()
+        }
+    }
+}
+
+impl Indicator for Vwap {
+    type Output = f64;
+
+    fn update(&mut self, candle: &CexCandleView) -> Option<f64> {
+        if let Some(anchor) = &self.anchor {
+            let session = anchor.bucket_start(candle.timestamp);
+            if self.session.replace(session) != Some(session) {
+                self.price_volume = 0.0;
+                self.volume = 0.0;
+            }
+        }
+        let typical = (candle.high + candle.low + candle.close) / 3.0;
+        self.price_volume += typical * candle.volume;
+        self.volume += candle.volume;
+        (self.volume > 0.0).then(|| self.price_volume / self.volume)
+    }
+}
+
+/// Drives an [`Indicator`] from a polling loop.
+///
+/// Polling the latest candles re-delivers the newest one, still forming,
+/// with updated prices. A candle with the same timestamp as the previous
+/// one replaces its contribution instead of being counted twice; candles
+/// older than that are ignored.
+#[derive(Debug, Clone)]
+pub struct Streaming<I: Indicator + Clone> {
+    indicator: I,
+    before_last: Option<I>,
+    last_timestamp: Option<i64>,
+    last_output: Option<I::Output>,
+}
+
+impl<I> Streaming<I>
+where
+    I: Indicator + Clone,
+    I::Output: Clone,
+{
+    /// Wraps a fresh `indicator`.
+    pub fn new(indicator: I) -> Self {
+        Streaming {
+            indicator,
+            before_last: None,
+            last_timestamp: None,
+            last_output: None,
+        }
+    }
+
+    /// Folds in `candle` and returns the latest value.
+    pub fn update(&mut self, candle: &CexCandleView) -> Option<I::Output> {
+        match self.last_timestamp {
+            Some(last) if candle.timestamp < last => return self.last_output.clone(),
+            Some(last) if candle.timestamp == last => {
+                if let Some(before) = &self.before_last {
+                    self.indicator = before.clone();
+                }
+            }
+            _ => self.before_last = Some(self.indicator.clone()),
+        }
+        self.last_timestamp = Some(candle.timestamp);
+        self.last_output = self.indicator.update(candle);
+        self.last_output.clone()
+    }
+
+    /// Feeds every candle in `candles` through [`update`](Self::update) and
+    /// returns the latest value, e.g. for each page returned by a poll.
+    pub fn extend(&mut self, candles: &[CexCandleView]) -> Option<I::Output> {
+        for candle in candles {
+            self.update(candle);
+        }
+        self.last_output.clone()
+    }
+
+    /// The value after the most recent accepted candle.
+    pub fn value(&self) -> Option<I::Output> {
+        self.last_output.clone()
+    }
+
+    /// Timestamp of the most recent accepted candle.
+    pub fn last_timestamp(&self) -> Option<i64> {
+        self.last_timestamp
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn closes(values: &[f64]) -> Vec<CexCandleView> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, &close)| CexCandleView {
+                close,
+                timestamp: i as i64 * 60_000,
+                high: close,
+                low: close,
+                open: close,
+                volume: 1.0,
+            })
+            .collect()
+    }
+
+    fn ohlcv(timestamp: i64, high: f64, low: f64, close: f64, volume: f64) -> CexCandleView {
+        CexCandleView {
+            close,
+            timestamp,
+            high,
+            low,
+            open: close,
+            volume,
+        }
+    }
+
+    fn rounded(values: Vec<Option<f64>>, digits: i32) -> Vec<Option<f64>> {
+        let scale = 10f64.powi(digits);
+        values
+            .into_iter()
+            .map(|v| v.map(|v| (v * scale).round() / scale))
+            .collect()
+    }
+
+    /// Wilder's 14-period RSI worked example, as published by StockCharts.
+    const WILDER_CLOSES: [f64; 20] = [
+        44.34, 44.09, 44.15, 43.61, 44.33, 44.83, 45.10, 45.42, 45.84, 46.08, 45.89, 46.03, 45.61,
+        46.28, 46.28, 46.00, 46.03, 46.41, 46.22, 45.64,
+    ];
+
+    #[test]
+    fn rsi_matches_wilder_reference() {
+        let rsi = rounded(Rsi::new(14).compute(&closes(&WILDER_CLOSES)), 2);
+        assert!(rsi[..14].iter().all(Option::is_none));
+        assert_eq!(
+            &rsi[14..],
+            &[70.46, 66.25, 66.48, 69.35, 66.29, 57.92].map(Some)
+        );
+    }
+
+    #[test]
+    fn sma_and_ema_warm_up_then_track() {
+        let candles = closes(&[1.0, 2.0, 3.0, 4.0, 5.0]);
+        assert_eq!(
+            Sma::new(3).compute(&candles),
+            [None, None, Some(2.0), Some(3.0), Some(4.0)]
+        );
+        // Seeded with SMA(1, 2, 3) = 2, then alpha = 0.5.
+        assert_eq!(
+            Ema::new(3).compute(&candles),
+            [None, None, Some(2.0), Some(3.0), Some(4.0)]
+        );
+        assert_eq!(
+            Ema::new(3).compute(&closes(&[1.0, 2.0, 3.0, 6.0]))[3],
+            Some(4.0)
+        );
+    }
+
+    #[test]
+    fn atr_uses_true_range_and_wilder_smoothing() {
+        let candles = [
+            ohlcv(0, 10.0, 8.0, 9.0, 1.0),
+            // Gap up: true range is 13 - 9 = 4, not 13 - 12 = 1.
+            ohlcv(1, 13.0, 12.0, 12.5, 1.0),
+            ohlcv(2, 13.0, 12.0, 12.5, 1.0),
+        ];
+        // First ATR = mean(2, 4) = 3; next = (3 * 1 + 1) / 2 = 2.
+        assert_eq!(Atr::new(2).compute(&candles), [None, Some(3.0), Some(2.0)]);
+    }
+
+    #[test]
+    fn bollinger_uses_population_deviation() {
+        let bands = Bollinger::new(5, 2.0).compute(&closes(&[1.0, 2.0, 3.0, 4.0, 5.0]));
+        let last = bands[4].expect("warmed up");
+        assert_eq!(last.middle, 3.0);
+        assert!((last.upper - (3.0 + 2.0 * 2f64.sqrt())).abs() < 1e-12);
+        assert!((last.lower - (3.0 - 2.0 * 2f64.sqrt())).abs() < 1e-12);
+        assert!(bands[..4].iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn vwap_weights_typical_price_and_resets_per_session() {
+        let day = 86_400_000;
+        let candles = [
+            ohlcv(0, 12.0, 9.0, 9.0, 1.0),   // typical 10
+            ohlcv(1, 22.0, 19.0, 19.0, 3.0), // typical 20
+            ohlcv(day, 6.0, 3.0, 3.0, 2.0),  // typical 4, next UTC day
+        ];
+
+        assert_eq!(
+            Vwap::new().compute(&candles),
+            [Some(10.0), Some(17.5), Some(78.0 / 6.0)]
+        );
+        let daily = Resampler::every(Duration::from_secs(86_400));
+        assert_eq!(Vwap::anchored(daily).compute(&candles)[2], Some(4.0));
+    }
+
+    #[test]
+    fn streaming_replaces_the_forming_candle() {
+        let mut live = Streaming::new(Sma::new(2));
+        let mut candles = closes(&[1.0, 2.0]);
+        assert_eq!(live.extend(&candles), Some(1.5));
+
+        // The forming candle is polled again with a new close.
+        candles[1].close = 4.0;
+        assert_eq!(live.extend(&candles), Some(2.5));
+
+        candles.extend(closes(&[0.0, 0.0, 6.0]).into_iter().skip(2));
+        assert_eq!(live.extend(&candles), Some(5.0));
+        assert_eq!(live.last_timestamp(), Some(2 * 60_000));
+    }
+}
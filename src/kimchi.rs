@@ -0,0 +1,470 @@
+//! Kimchi premium: Korean KRW prices against global USD prices.
+//!
+//! The premium of an asset on a Korean exchange over a global one is its KRW
+//! price divided by the global USD price converted at the `USD-KRW` forex
+//! rate, minus one. A [`KimchiTracker`](crate::kimchi::KimchiTracker) keeps
+//! the forex rates it has seen (in a [`RateTable`](crate::fx::RateTable))
+//! and one time series per
+//! asset × Korean exchange × global exchange, converting every observation
+//! at the rate in force when it was made.
+//!
+//! A [`KimchiPoller`](crate::kimchi::KimchiPoller) drives a tracker from the
+//! API: each [`next_update`](crate::kimchi::KimchiPoller::next_update)
+//! fetches the forex rate and one [`Ticker`](crate::Ticker) per asset and
+//! exchange (Korean legs in KRW, global legs in USD), then returns the new
+//! points. Call it in a loop to follow the premium.
+//!
+//! ```no_run
+//! use datamaxi::kimchi::{KimchiPoller, KimchiTracker};
+//! use datamaxi::Client;
+//! use std::time::Duration;
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let tracker = KimchiTracker::new()
+//!     .asset("BTC")
+//!     .asset("ETH")
+//!     .korean("upbit", "KRW")
+//!     .korean("bithumb", "KRW")
+//!     .global("binance", "USDT");
+//! let mut poller = KimchiPoller::new(Client::new("my_api_key"), tracker, Duration::from_secs(60));
+//!
+//! loop {
+//!     for point in poller.next_update().await? {
+//!         println!(
+//!             "{} {}/{}: {:+.2}%",
+//!             point.asset, point.korean, point.global, point.premium_pct
+//!         );
+//!     }
+//! }
+//! # }
+//! ```
+
+use crate::api::{Client, Error, Result};
+use crate::enums::Currency;
+use crate::exchange::Exchange;
+use crate::fx::RateTable;
+use crate::generated::{ForexResponse, TickerCurrency, TickerMarket, TickerOptions, TickerView};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+pub use crate::fx::USD_KRW;
+
+/// Points kept per series, and forex rates kept, unless configured.
+pub const DEFAULT_MAX_POINTS: usize = 10_000;
+
+/// A market an asset is priced on: an exchange and its quote asset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Venue {
+    exchange: Exchange,
+    quote: String,
+}
+
+/// Identifies one premium series.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct KimchiRoute {
+    /// Base asset, upper-cased.
+    pub asset: String,
+    /// The Korean exchange.
+    pub korean: Exchange,
+    /// The global exchange.
+    pub global: Exchange,
+}
+
+/// One observation of the premium.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KimchiPoint {
+    /// Base asset, upper-cased.
+    pub asset: String,
+    /// The Korean exchange.
+    pub korean: Exchange,
+    /// The global exchange.
+    pub global: Exchange,
+    /// The later of the two ticker timestamps, UTC milliseconds.
+    pub timestamp: i64,
+    /// Price on the Korean exchange, KRW.
+    pub korean_price: f64,
+    /// Price on the global exchange, USD.
+    pub global_price: f64,
+    /// The `USD-KRW` rate in force at `timestamp`.
+    pub usd_krw: f64,
+    /// `korean_price / (global_price * usd_krw) - 1`, percent.
+    pub premium_pct: f64,
+}
+
+impl KimchiPoint {
+    /// The series this point belongs to.
+    pub fn route(&self) -> KimchiRoute {
+        KimchiRoute {
+            asset: self.asset.clone(),
+            korean: self.korean.clone(),
+            global: self.global.clone(),
+        }
+    }
+}
+
+/// Premium series per asset and exchange pair, with the forex rates seen.
+#[derive(Debug, Clone)]
+pub struct KimchiTracker {
+    assets: Vec<String>,
+    korean: Vec<Venue>,
+    global: Vec<Venue>,
+    market: TickerMarket,
+    max_points: usize,
+    rates: RateTable,
+    series: BTreeMap<KimchiRoute, Vec<KimchiPoint>>,
+}
+
+impl Default for KimchiTracker {
+    fn default() -> Self {
+        KimchiTracker::new()
+    }
+}
+
+impl KimchiTracker {
+    /// An empty tracker on spot markets; add assets and exchanges.
+    pub fn new() -> Self {
+        KimchiTracker {
+            assets: Vec::new(),
+            korean: Vec::new(),
+            global: Vec::new(),
+            market: TickerMarket::Spot,
+            max_points: DEFAULT_MAX_POINTS,
+            rates: RateTable::new(),
+            series: BTreeMap::new(),
+        }
+    }
+
+    /// Tracks `base`, e.g. `BTC`.
+    pub fn asset(mut self, base: impl AsRef<str>) -> Self {
+        self.assets.push(base.as_ref().trim().to_ascii_uppercase());
+        self
+    }
+
+    /// Adds a Korean exchange, priced in KRW against `quote` (usually `KRW`).
+    pub fn korean(mut self, exchange: impl AsRef<str>, quote: impl AsRef<str>) -> Self {
+        self.korean.push(venue(exchange, quote));
+        self
+    }
+
+    /// Adds a global exchange, priced in USD against `quote` (e.g. `USDT`).
+    pub fn global(mut self, exchange: impl AsRef<str>, quote: impl AsRef<str>) -> Self {
+        self.global.push(venue(exchange, quote));
+        self
+    }
+
+    /// The market tickers are read from. Defaults to spot.
+    pub fn market(mut self, market: TickerMarket) -> Self {
+        self.market = market;
+        self
+    }
+
+    /// Keeps at most `max` points per series and `max` forex rates, dropping
+    /// the oldest. Defaults to [`DEFAULT_MAX_POINTS`].
+    pub fn max_points(mut self, max: usize) -> Self {
+        self.max_points = max.max(1);
+        self.rates = self.rates.max_rates(self.max_points);
+        self
+    }
+
+    /// Records a `USD-KRW` rate. Second timestamps are read as such.
+    pub fn record_forex(&mut self, forex: &ForexResponse) {
+        self.rates.record(forex);
+    }
+
+    /// The rate in force at `timestamp`: the latest recorded at or before
+    /// it, or the earliest recorded if all are later.
+    pub fn rate_at(&self, timestamp: i64) -> Option<f64> {
+        self.rates
+            .rate_at(&Currency::USD, &Currency::KRW, timestamp)
+            .map(|applied| applied.rate)
+    }
+
+    /// Pairs every Korean ticker (priced in KRW) of `asset` with every
+    /// global ticker (priced in USD), and appends the points newer than
+    /// each series' last one. Returns the appended points.
+    ///
+    /// Tickers without a price are skipped, and nothing is appended before
+    /// a forex rate has been recorded.
+    pub fn observe(
+        &mut self,
+        asset: &str,
+        korean: &[TickerView],
+        global: &[TickerView],
+    ) -> Vec<KimchiPoint> {
+        let asset = asset.trim().to_ascii_uppercase();
+        let mut appended = Vec::new();
+        for korean in korean {
+            for global in global {
+                let (Some(korean_price), Some(global_price)) = (korean.price, global.price) else {
+                    continue;
+                };
+                let timestamp = korean.timestamp.max(global.timestamp);
+                let Some(usd_krw) = self.rate_at(timestamp) else {
+                    continue;
+                };
+                let point = KimchiPoint {
+                    asset: asset.clone(),
+                    korean: Exchange::new(&korean.exchange),
+                    global: Exchange::new(&global.exchange),
+                    timestamp,
+                    korean_price,
+                    global_price,
+                    usd_krw,
+                    premium_pct: (korean_price / (global_price * usd_krw) - 1.0) * 100.0,
+                };
+                let series = self.series.entry(point.route()).or_default();
+                if series
+                    .last()
+                    .is_some_and(|last| last.timestamp >= timestamp)
+                {
+                    continue;
+                }
+                series.push(point.clone());
+                if series.len() > self.max_points {
+                    series.remove(0);
+                }
+                appended.push(point);
+            }
+        }
+        appended
+    }
+
+    /// Every series observed so far.
+    pub fn routes(&self) -> impl Iterator<Item = &KimchiRoute> {
+        self.series.keys()
+    }
+
+    /// The points of one series, oldest first.
+    pub fn series(&self, route: &KimchiRoute) -> &[KimchiPoint] {
+        self.series.get(route).map_or(&[], Vec::as_slice)
+    }
+
+    /// The latest point of every series of `asset`.
+    pub fn latest(&self, asset: &str) -> Vec<&KimchiPoint> {
+        let asset = asset.trim().to_ascii_uppercase();
+        self.series
+            .iter()
+            .filter(|(route, _)| route.asset == asset)
+            .filter_map(|(_, points)| points.last())
+            .collect()
+    }
+}
+
+fn venue(exchange: impl AsRef<str>, quote: impl AsRef<str>) -> Venue {
+    Venue {
+        exchange: Exchange::new(exchange),
+        quote: quote.as_ref().trim().to_ascii_uppercase(),
+    }
+}
+
+/// Body of the async and blocking kimchi pollers; see `get_loop!` in [`crate::api`].
+macro_rules! poll {
+    ($client:expr, $tracker:expr $(, $aw:ident)?) => {{
+        let client = $client;
+        let tracker: &mut KimchiTracker = $tracker;
+        let forex = client.forex().get(USD_KRW)$(.$aw)?;
+        tracker.record_forex(&forex?);
+
+        let ticker = client.ticker();
+        let mut updates = Vec::new();
+        for asset in tracker.assets.clone() {
+            let mut legs = [Vec::new(), Vec::new()];
+            let sides = [
+                (&tracker.korean, TickerCurrency::KRW),
+                (&tracker.global, TickerCurrency::USD),
+            ];
+            for (leg, (venues, currency)) in legs.iter_mut().zip(sides) {
+                for venue in venues {
+                    let response = ticker
+                        .get(
+                            &venue.exchange,
+                            format!("{asset}-{}", venue.quote),
+                            tracker.market,
+                            TickerOptions::new().currency(currency),
+                        )$(.$aw)?;
+                    match response {
+                        Ok(response) => leg.push(response.data),
+                        // An asset not listed on one exchange must not
+                        // stop the others.
+                        Err(Error::NotFound { .. }) => {}
+                        Err(err) => return Err(err),
+                    }
+                }
+            }
+            let [korean, global] = legs;
+            updates.extend(tracker.observe(&asset, &korean, &global));
+        }
+        Ok(updates)
+    }};
+}
+
+/// Follows a [`KimchiTracker`] by polling the API at a fixed interval.
+pub struct KimchiPoller {
+    client: Client,
+    tracker: KimchiTracker,
+    interval: Duration,
+    polled: bool,
+}
+
+impl KimchiPoller {
+    /// Polls through `client` every `interval`.
+    pub fn new(client: Client, tracker: KimchiTracker, interval: Duration) -> Self {
+        KimchiPoller {
+            client,
+            tracker,
+            interval,
+            polled: false,
+        }
+    }
+
+    /// Waits for the interval (except on the first call), fetches a round
+    /// of forex and tickers, and returns the points it appended (possibly
+    /// none, if no ticker moved).
+    pub async fn next_update(&mut self) -> Result<Vec<KimchiPoint>> {
+        if self.polled {
+            tokio::time::sleep(self.interval).await;
+        }
+        self.polled = true;
+        poll!(&self.client, &mut self.tracker, await)
+    }
+
+    /// The tracker, with every series observed so far.
+    pub fn tracker(&self) -> &KimchiTracker {
+        &self.tracker
+    }
+}
+
+/// Blocking mirror of the poller (feature `sync`).
+#[cfg(feature = "sync")]
+#[cfg_attr(docsrs, doc(cfg(feature = "sync")))]
+pub mod sync {
+    use super::{KimchiPoint, KimchiTracker, USD_KRW};
+    use crate::api::sync::Client;
+    use crate::api::{Error, Result};
+    use crate::generated::{TickerCurrency, TickerOptions};
+    use std::time::Duration;
+
+    /// Blocking mirror of [`KimchiPoller`](super::KimchiPoller).
+    pub struct KimchiPoller {
+        client: Client,
+        tracker: KimchiTracker,
+        interval: Duration,
+        polled: bool,
+    }
+
+    impl KimchiPoller {
+        /// Polls through `client` every `interval`.
+        pub fn new(client: Client, tracker: KimchiTracker, interval: Duration) -> Self {
+            KimchiPoller {
+                client,
+                tracker,
+                interval,
+                polled: false,
+            }
+        }
+
+        /// Blocking mirror of
+        /// [`KimchiPoller::next_update`](super::KimchiPoller::next_update).
+        pub fn next_update(&mut self) -> Result<Vec<KimchiPoint>> {
+            if self.polled {
+                std::thread::sleep(self.interval);
+            }
+            self.polled = true;
+            poll!(&self.client, &mut self.tracker)
+        }
+
+        /// The tracker, with every series observed so far.
+        pub fn tracker(&self) -> &KimchiTracker {
+            &self.tracker
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ticker(exchange: &str, timestamp: i64, price: f64) -> TickerView {
+        TickerView {
+            exchange: exchange.into(),
+            timestamp,
+            price: Some(price),
+            ..Default::default()
+        }
+    }
+
+    fn forex(timestamp: i64, rate: f64) -> ForexResponse {
+        ForexResponse {
+            timestamp,
+            rate,
+            symbol: USD_KRW.into(),
+        }
+    }
+
+    #[test]
+    fn each_observation_uses_the_rate_in_force() {
+        const T: i64 = 1_735_689_600_000;
+        let mut tracker = KimchiTracker::new();
+        assert!(tracker
+            .observe(
+                "btc",
+                &[ticker("upbit", T, 1.0)],
+                &[ticker("binance", T, 1.0)]
+            )
+            .is_empty());
+
+        // Seconds and milliseconds both land on the millisecond axis.
+        tracker.record_forex(&forex(T / 1000, 1_300.0));
+        tracker.record_forex(&forex(T + 60_000, 1_400.0));
+        assert_eq!(tracker.rate_at(T - 1), Some(1_300.0));
+        assert_eq!(tracker.rate_at(T + 59_999), Some(1_300.0));
+        assert_eq!(tracker.rate_at(T + 60_000), Some(1_400.0));
+
+        let early = tracker.observe(
+            "btc",
+            &[ticker("Upbit", T + 30_000, 136_500.0)],
+            &[ticker("binance", T + 29_000, 100.0)],
+        );
+        assert_eq!(early[0].usd_krw, 1_300.0);
+        assert!((early[0].premium_pct - 5.0).abs() < 1e-9);
+
+        let late = tracker.observe(
+            "BTC",
+            &[ticker("upbit", T + 61_000, 140_000.0)],
+            &[ticker("binance", T + 61_000, 100.0)],
+        );
+        assert_eq!(late[0].usd_krw, 1_400.0);
+        assert!(late[0].premium_pct.abs() < 1e-9);
+
+        let route = late[0].route();
+        assert_eq!(route.korean.as_str(), "upbit");
+        assert_eq!(tracker.series(&route).len(), 2);
+        assert_eq!(tracker.latest("btc"), [&late[0]]);
+    }
+
+    #[test]
+    fn stale_tickers_and_history_cap() {
+        let mut tracker = KimchiTracker::new().max_points(2);
+        tracker.record_forex(&forex(0, 1_000.0));
+        for timestamp in [1_000, 2_000, 2_000, 3_000] {
+            tracker.observe(
+                "ETH",
+                &[
+                    ticker("upbit", timestamp, 1_000.0),
+                    ticker("bithumb", timestamp, 990.0),
+                ],
+                &[ticker("binance", timestamp, 1.0)],
+            );
+        }
+
+        let routes: Vec<&KimchiRoute> = tracker.routes().collect();
+        assert_eq!(routes.len(), 2);
+        let timestamps: Vec<i64> = tracker
+            .series(routes[1])
+            .iter()
+            .map(|point| point.timestamp)
+            .collect();
+        assert_eq!(timestamps, [2_000, 3_000]);
+    }
+}
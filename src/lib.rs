@@ -88,6 +88,109 @@
 /// API definitions and related utilities.
 pub mod api;
 
+/// Candle series utilities: gap detection and repair.
+pub mod candles;
+
+/// Resampling of candles, index prices and liquidation history to coarser
+/// or calendar-aligned buckets.
+pub mod resample;
+
+/// Incremental local candle store with per-series watermarks.
+pub mod dataset;
+
+/// Incremental technical indicators (SMA, EMA, RSI, ATR, Bollinger, VWAP)
+/// over candle series.
+#[cfg(feature = "indicators")]
+#[cfg_attr(docsrs, doc(cfg(feature = "indicators")))]
+pub mod indicators;
+
+/// Columnar schema of the response models, shared by the `arrow`/`polars`
+/// backends.
+pub mod table;
+
+/// Apache Arrow `RecordBatch` conversion of the response models.
+#[cfg(feature = "arrow")]
+#[cfg_attr(docsrs, doc(cfg(feature = "arrow")))]
+pub mod arrow;
+
+/// Polars `DataFrame` conversion of the response models.
+#[cfg(feature = "polars")]
+#[cfg_attr(docsrs, doc(cfg(feature = "polars")))]
+pub mod polars;
+
+/// Streaming CSV and Parquet writers for historical datasets.
+#[cfg(feature = "export")]
+#[cfg_attr(docsrs, doc(cfg(feature = "export")))]
+pub mod export;
+
+/// Exact `Decimal` mirrors of the models' `f64` fields, decoded from the JSON text.
+#[cfg(feature = "rust_decimal")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rust_decimal")))]
+pub mod decimal;
+
+/// Typed enums, with an `Unknown` catch-all, for string-valued response
+/// fields such as liquidation sides, symbol statuses and markets.
+pub mod enums;
+
+/// A parsed, normalized trading pair accepted wherever a wrapper takes a
+/// symbol.
+pub mod pair;
+
+/// Typed exchange ids and a registry of the data products each exchange
+/// supports.
+pub mod exchange;
+
+/// A readable, grouped view of the wire-named `PremiumDetail` fields.
+pub mod premium;
+
+/// Fee- and transferability-aware arbitrage ranking over premium data.
+pub mod arbitrage;
+
+/// Currency conversion of candles, tickers and OI stats at time-matched
+/// forex rates.
+pub mod fx;
+
+/// Funding rate analytics: APR normalization, carry, next settlement and
+/// cross-exchange spreads.
+pub mod funding;
+
+/// Symbol universe joining metadata, tags, cautions, delistings, fees and
+/// wallet status, with filters and snapshot diffs.
+pub mod universe;
+
+/// Consolidated top of book across exchanges, with best-price routing.
+pub mod quote;
+
+/// Open interest analytics: OI aligned with candles and volume, deltas,
+/// divergences and market share.
+pub mod oi;
+
+/// Exchange × token matrices pivoted from the liquidation heatmap, with
+/// reconciliation against its summaries.
+pub mod heatmap;
+
+/// Liquidation map helpers: cumulative curves, nearest clusters, custom
+/// price grids and terminal rendering.
+pub mod liquidation_map;
+
+/// Liquidation cascade detection: per-token USD bursts against a rolling
+/// baseline or open interest.
+pub mod cascade;
+
+/// Kimchi premium tracking: Korean KRW prices against global USD prices at
+/// the forex rate of each observation.
+pub mod kimchi;
+
+/// Client-side validation of endpoint options, run before every request.
+pub mod validate;
+
+/// Typed timestamps: `since`/`until`/`last` range builders and, behind the
+/// `chrono` / `time` features, date-time accessors on every model.
+pub mod datetime;
+
+/// Typed companions for response payloads the API schema leaves as raw JSON.
+pub mod models;
+
 // `generated.rs` is code-generated (DO NOT EDIT). Its contents are re-exported
 // at the crate root (below), so callers write `datamaxi::CexCandle` rather than
 // through this module path. Hidden from the docs but kept `pub` for backward
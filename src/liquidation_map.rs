@@ -0,0 +1,371 @@
+//! Helpers around the current price of a [`LiquidationMapResponse`].
+//!
+//! The map lists leverage-tiered [`LiquidationMapBucket`]s: long
+//! liquidations below the current price, short liquidations above. The
+//! inherent methods added here answer the usual dashboard questions:
+//!
+//! - [`cumulative_curve`](crate::LiquidationMapResponse::cumulative_curve)
+//!   walks one side outward from the current price, summing USD;
+//! - [`next_cluster`](crate::LiquidationMapResponse::next_cluster) finds the
+//!   nearest bucket on one side holding at least some USD, and how far away
+//!   it is;
+//! - [`rebucket`](crate::LiquidationMapResponse::rebucket) re-aggregates the
+//!   buckets onto a coarser [`PriceGrid`](crate::liquidation_map::PriceGrid);
+//! - [`render`](crate::LiquidationMapResponse::render) draws the map as
+//!   plain-text bars for terminal dashboards.
+//!
+//! ```
+//! use datamaxi::enums::Side;
+//! use datamaxi::liquidation_map::PriceGrid;
+//! use datamaxi::{LiquidationMapBucket, LiquidationMapResponse};
+//!
+//! let bucket = |price: f64, side: &str, total_usd: f64| LiquidationMapBucket {
+//!     price,
+//!     side: side.into(),
+//!     total_usd,
+//!     ..Default::default()
+//! };
+//! let map = LiquidationMapResponse {
+//!     current_price: 100.0,
+//!     buckets: vec![
+//!         bucket(90.0, "long", 5_000_000.0),
+//!         bucket(95.0, "long", 1_000_000.0),
+//!         bucket(104.0, "short", 2_000_000.0),
+//!     ],
+//!     ..Default::default()
+//! };
+//!
+//! let below = map.next_cluster(Side::Long, 2_000_000.0).expect("a dense bucket below");
+//! assert_eq!((below.price, below.distance), (90.0, -10.0));
+//! assert_eq!(map.cumulative_curve(Side::Long)[1].cumulative_usd, 6_000_000.0);
+//!
+//! let coarse = map.rebucket(&PriceGrid::Step(10.0)).expect("valid grid");
+//! assert_eq!(coarse.len(), 2);
+//! println!("{}", map.render(20));
+//! ```
+//!
+//! [`LiquidationMapBucket`]: crate::LiquidationMapBucket
+//! [`LiquidationMapResponse`]: crate::LiquidationMapResponse
+
+use crate::api::{Error, Result};
+use crate::enums::Side;
+use crate::generated::{LiquidationMapBucket, LiquidationMapResponse};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// One step of a cumulative liquidation curve.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CurvePoint {
+    /// Bucket price.
+    pub price: f64,
+    /// `price / current_price - 1`, percent (negative below the price).
+    pub distance_pct: f64,
+    /// USD of this bucket.
+    pub usd: f64,
+    /// USD of this bucket and every bucket between it and the current price.
+    pub cumulative_usd: f64,
+}
+
+/// The nearest bucket on one side holding at least the requested USD.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Cluster {
+    /// Bucket price.
+    pub price: f64,
+    /// USD liquidated at this bucket.
+    pub usd: f64,
+    /// `price - current_price` (negative below the price).
+    pub distance: f64,
+    /// `price / current_price - 1`, percent.
+    pub distance_pct: f64,
+    /// USD liquidated on the way, this bucket included.
+    pub cumulative_usd: f64,
+}
+
+/// A price grid to re-aggregate buckets onto.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PriceGrid {
+    /// Cells of a fixed width aligned to multiples of it: a bucket at `p`
+    /// lands in the cell starting at `floor(p / step) * step`.
+    Step(f64),
+    /// Cells between consecutive, strictly increasing edges; buckets outside
+    /// the first and last edge are dropped.
+    Edges(Vec<f64>),
+}
+
+impl PriceGrid {
+    fn validate(&self) -> Result<()> {
+        let reason = match self {
+            PriceGrid::Step(step) if !(step.is_finite() && *step > 0.0) => {
+                format!("step must be a positive number, got {step}")
+            }
+            PriceGrid::Edges(edges) if edges.len() < 2 => {
+                "at least two edges are needed".to_string()
+            }
+            PriceGrid::Edges(edges)
+                if edges
+                    .windows(2)
+                    .any(|w| w[0].partial_cmp(&w[1]) != Some(Ordering::Less)) =>
+            {
+                "edges must be strictly increasing".to_string()
+            }
+            _ => return Ok(()),
+        };
+        Err(Error::InvalidArgument {
+            argument: "grid".to_string(),
+            reason,
+        })
+    }
+
+    // The lower edge of the cell holding `price`, if any.
+    fn cell(&self, price: f64) -> Option<f64> {
+        match self {
+            PriceGrid::Step(step) => Some((price / step).floor() * step),
+            PriceGrid::Edges(edges) => {
+                let index = edges.partition_point(|&edge| edge <= price);
+                (index > 0 && index < edges.len()).then(|| edges[index - 1])
+            }
+        }
+    }
+}
+
+impl LiquidationMapResponse {
+    fn side_buckets(&self, side: &Side) -> Vec<&LiquidationMapBucket> {
+        let mut buckets: Vec<_> = self
+            .buckets
+            .iter()
+            .filter(|bucket| bucket.side_kind() == *side)
+            .collect();
+        buckets.sort_by(|a, b| {
+            let distance =
+                |bucket: &LiquidationMapBucket| (bucket.price - self.current_price).abs();
+            distance(a).total_cmp(&distance(b))
+        });
+        buckets
+    }
+
+    fn distance_pct(&self, price: f64) -> f64 {
+        (price / self.current_price - 1.0) * 100.0
+    }
+
+    /// The buckets of one side, nearest to the current price first, with
+    /// the USD summed outward.
+    pub fn cumulative_curve(&self, side: Side) -> Vec<CurvePoint> {
+        let mut cumulative_usd = 0.0;
+        self.side_buckets(&side)
+            .into_iter()
+            .map(|bucket| {
+                cumulative_usd += bucket.total_usd;
+                CurvePoint {
+                    price: bucket.price,
+                    distance_pct: self.distance_pct(bucket.price),
+                    usd: bucket.total_usd,
+                    cumulative_usd,
+                }
+            })
+            .collect()
+    }
+
+    /// The nearest bucket of one side (longs below the price, shorts above)
+    /// holding at least `min_usd`.
+    pub fn next_cluster(&self, side: Side, min_usd: f64) -> Option<Cluster> {
+        self.cumulative_curve(side)
+            .into_iter()
+            .find(|point| point.usd >= min_usd)
+            .map(|point| Cluster {
+                price: point.price,
+                usd: point.usd,
+                distance: point.price - self.current_price,
+                distance_pct: point.distance_pct,
+                cumulative_usd: point.cumulative_usd,
+            })
+    }
+
+    /// Sums the buckets per grid cell and side, tier by tier. Each returned
+    /// bucket is priced at its cell's lower edge; they are sorted by price,
+    /// longs before shorts within a cell.
+    pub fn rebucket(&self, grid: &PriceGrid) -> Result<Vec<LiquidationMapBucket>> {
+        grid.validate()?;
+        let mut cells: BTreeMap<(u64, bool), LiquidationMapBucket> = BTreeMap::new();
+        for bucket in &self.buckets {
+            let Some(price) = grid.cell(bucket.price) else {
+                continue;
+            };
+            let short = bucket.side_kind() == Side::Short;
+            let cell =
+                cells
+                    .entry((price.to_bits(), short))
+                    .or_insert_with(|| LiquidationMapBucket {
+                        price,
+                        side: bucket.side.clone(),
+                        ..Default::default()
+                    });
+            cell.l10x_usd += bucket.l10x_usd;
+            cell.l25x_usd += bucket.l25x_usd;
+            cell.l50x_usd += bucket.l50x_usd;
+            cell.l100x_usd += bucket.l100x_usd;
+            cell.total_usd += bucket.total_usd;
+        }
+        // Stable, so longs stay ahead of shorts within a cell.
+        let mut buckets: Vec<_> = cells.into_values().collect();
+        buckets.sort_by(|a, b| a.price.total_cmp(&b.price));
+        Ok(buckets)
+    }
+
+    /// Draws the buckets highest price first, one `#` bar per line scaled to
+    /// `bar_width` columns at the largest bucket, with a marker line at the
+    /// current price. Rebucket first to keep the output short.
+    pub fn render(&self, bar_width: usize) -> String {
+        let mut buckets: Vec<_> = self.buckets.iter().collect();
+        buckets.sort_by(|a, b| b.price.total_cmp(&a.price));
+        let max = buckets
+            .iter()
+            .map(|bucket| bucket.total_usd)
+            .fold(0.0, f64::max);
+        let decimals = price_decimals(self.current_price);
+
+        let mut out = String::new();
+        let mut marked = false;
+        for bucket in buckets {
+            if !marked && bucket.price < self.current_price {
+                let _ = writeln!(
+                    out,
+                    "{:>14.decimals$} > {} current",
+                    self.current_price,
+                    "-".repeat(bar_width)
+                );
+                marked = true;
+            }
+            let len = if max > 0.0 {
+                (bucket.total_usd / max * bar_width as f64).round() as usize
+            } else {
+                0
+            };
+            let side = match bucket.side_kind() {
+                Side::Long => 'L',
+                Side::Short => 'S',
+                _ => '?',
+            };
+            let _ = writeln!(
+                out,
+                "{:>14.decimals$} {side} {:<bar_width$} {}",
+                bucket.price,
+                "#".repeat(len),
+                compact_usd(bucket.total_usd),
+            );
+        }
+        if !marked {
+            let _ = writeln!(
+                out,
+                "{:>14.decimals$} > {} current",
+                self.current_price,
+                "-".repeat(bar_width)
+            );
+        }
+        out
+    }
+}
+
+// Enough decimals for about five significant digits at `price`.
+fn price_decimals(price: f64) -> usize {
+    if price > 0.0 {
+        (4 - price.log10().floor() as i32).clamp(0, 8) as usize
+    } else {
+        2
+    }
+}
+
+fn compact_usd(usd: f64) -> String {
+    let abs = usd.abs();
+    if abs >= 1e9 {
+        format!("{:.1}B", usd / 1e9)
+    } else if abs >= 1e6 {
+        format!("{:.1}M", usd / 1e6)
+    } else if abs >= 1e3 {
+        format!("{:.1}K", usd / 1e3)
+    } else {
+        format!("{usd:.0}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bucket(price: f64, side: &str, l10x_usd: f64, l100x_usd: f64) -> LiquidationMapBucket {
+        LiquidationMapBucket {
+            price,
+            side: side.into(),
+            l10x_usd,
+            l100x_usd,
+            total_usd: l10x_usd + l100x_usd,
+            ..Default::default()
+        }
+    }
+
+    fn map() -> LiquidationMapResponse {
+        LiquidationMapResponse {
+            current_price: 100.0,
+            buckets: vec![
+                bucket(91.0, "long", 4.0, 0.0),
+                bucket(97.0, "long", 1.0, 1.0),
+                bucket(99.0, "long", 0.0, 1.0),
+                bucket(102.0, "short", 2.0, 0.0),
+                bucket(108.0, "short", 1.0, 5.0),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn clusters_are_found_walking_away_from_the_price() {
+        let map = map();
+        let curve = map.cumulative_curve(Side::Long);
+        let prices: Vec<_> = curve.iter().map(|point| point.price).collect();
+        assert_eq!(prices, [99.0, 97.0, 91.0]);
+        assert_eq!(curve[2].cumulative_usd, 7.0);
+
+        let above = map.next_cluster(Side::Short, 3.0).expect("108 holds 6");
+        assert_eq!(
+            (above.price, above.distance, above.cumulative_usd),
+            (108.0, 8.0, 8.0)
+        );
+        assert!(map.next_cluster(Side::Long, 10.0).is_none());
+    }
+
+    #[test]
+    fn rebucket_sums_tiers_per_cell_and_side() {
+        let map = map();
+        let step = map.rebucket(&PriceGrid::Step(5.0)).expect("valid grid");
+        let cells: Vec<_> = step
+            .iter()
+            .map(|b| (b.price, b.side.as_str(), b.l10x_usd, b.l100x_usd))
+            .collect();
+        assert_eq!(
+            cells,
+            [
+                (90.0, "long", 4.0, 0.0),
+                (95.0, "long", 1.0, 2.0),
+                (100.0, "short", 2.0, 0.0),
+                (105.0, "short", 1.0, 5.0),
+            ]
+        );
+
+        let edges = map
+            .rebucket(&PriceGrid::Edges(vec![95.0, 100.0, 105.0]))
+            .expect("valid grid");
+        assert_eq!(edges.len(), 2);
+        assert!(map.rebucket(&PriceGrid::Edges(vec![1.0, 1.0])).is_err());
+        assert!(map.rebucket(&PriceGrid::Step(0.0)).is_err());
+    }
+
+    #[test]
+    fn render_marks_the_current_price_between_sides() {
+        let lines: Vec<_> = map().render(6).lines().map(str::to_owned).collect();
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[0], "        108.00 S ###### 6");
+        assert_eq!(lines[2], "        100.00 > ------ current");
+        assert_eq!(lines[5], "         91.00 L ####   4");
+    }
+}
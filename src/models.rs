@@ -0,0 +1,250 @@
+//! Hand-written companion models for generated payloads that the API schema
+//! types only as raw JSON (`serde_json::Value`).
+//!
+//! The generated response structs keep their raw fields untouched, so
+//! decoding never breaks when the server reshapes one of these payloads;
+//! the accessors here parse the raw value on demand instead. Every typed
+//! entry also keeps the JSON it was read from in a `raw` field, as an escape
+//! hatch for attributes the typed model does not cover yet.
+//!
+//! | Raw field | Accessor | Typed entry |
+//! |---|---|---|
+//! | [`OpenInterestHistoryAggregatedResponse::data`](crate::OpenInterestHistoryAggregatedResponse::data) | [`points`](crate::OpenInterestHistoryAggregatedResponse::points), [`series`](crate::OpenInterestHistoryAggregatedResponse::series) | [`OpenInterestHistoryPoint`](crate::models::OpenInterestHistoryPoint), [`OpenInterestSeries`](crate::models::OpenInterestSeries) |
+//! | [`OpenInterestHistoryAggregatedResponse::exchange_url`](crate::OpenInterestHistoryAggregatedResponse::exchange_url) | [`exchange_urls`](crate::OpenInterestHistoryAggregatedResponse::exchange_urls) | `BTreeMap<String, String>` |
+//! | [`OpenInterestOverviewView::exchanges`](crate::OpenInterestOverviewView::exchanges) | [`exchange_entries`](crate::OpenInterestOverviewView::exchange_entries) | [`OpenInterestOverviewExchange`](crate::models::OpenInterestOverviewExchange) |
+//!
+//! Only shapes the API documents are modeled.
+//! [`MarginBorrowResponse::cross`](crate::MarginBorrowResponse::cross) and
+//! [`isolated`](crate::MarginBorrowResponse::isolated) have no documented
+//! shape yet, so they stay raw.
+
+use crate::generated::{OpenInterestHistoryAggregatedResponse, OpenInterestOverviewView};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// One point of a per-exchange Open Interest time series, as found under
+/// [`OpenInterestHistoryAggregatedResponse::data`] (keyed by exchange id,
+/// ordered by `t` ascending).
+///
+/// The API documents no other field of a point, so the open interest itself
+/// is only in [`raw`](Self::raw).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OpenInterestHistoryPoint {
+    /// The exchange id this point belongs to (the key it was found under).
+    pub exchange: String,
+    /// Bucket timestamp in UTC milliseconds (the point's `t`).
+    pub timestamp: i64,
+    /// The point exactly as received, including fields not modeled here.
+    pub raw: serde_json::Value,
+}
+
+/// The Open Interest time series of one exchange.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OpenInterestSeries {
+    /// The exchange id.
+    pub exchange: String,
+    /// The exchange's page for the token, from
+    /// [`exchange_url`](OpenInterestHistoryAggregatedResponse::exchange_url).
+    pub url: Option<String>,
+    /// The points, by timestamp.
+    pub points: Vec<OpenInterestHistoryPoint>,
+}
+
+impl OpenInterestHistoryAggregatedResponse {
+    /// Flattens [`data`](Self::data) into one [`OpenInterestHistoryPoint`]
+    /// per exchange and bucket, in exchange-id order and then by timestamp.
+    ///
+    /// Points without a numeric `t` are skipped, as is the whole payload if
+    /// `data` is not a JSON object.
+    pub fn points(&self) -> Vec<OpenInterestHistoryPoint> {
+        let Some(exchanges) = self.data.as_object() else {
+            return Vec::new();
+        };
+
+        let mut points = Vec::new();
+        for (exchange, series) in exchanges {
+            let Some(series) = series.as_array() else {
+                continue;
+            };
+            for point in series {
+                let Some(timestamp) = point.get("t").and_then(json_timestamp) else {
+                    continue;
+                };
+                points.push(OpenInterestHistoryPoint {
+                    exchange: exchange.clone(),
+                    timestamp,
+                    raw: point.clone(),
+                });
+            }
+        }
+        points
+    }
+
+    /// [`points`](Self::points) grouped into one [`OpenInterestSeries`] per
+    /// exchange, in exchange-id order, each with its URL when listed.
+    pub fn series(&self) -> Vec<OpenInterestSeries> {
+        let mut urls = self.exchange_urls();
+        let mut series: Vec<OpenInterestSeries> = Vec::new();
+        for point in self.points() {
+            match series.last_mut() {
+                Some(last) if last.exchange == point.exchange => last.points.push(point),
+                _ => series.push(OpenInterestSeries {
+                    exchange: point.exchange.clone(),
+                    url: urls.remove(&point.exchange),
+                    points: vec![point],
+                }),
+            }
+        }
+        series
+    }
+
+    /// [`exchange_url`](Self::exchange_url) as exchange id → URL. Entries
+    /// whose value is not a string are skipped, as is the whole payload if
+    /// it is not a JSON object.
+    pub fn exchange_urls(&self) -> BTreeMap<String, String> {
+        self.exchange_url
+            .as_object()
+            .into_iter()
+            .flatten()
+            .filter_map(|(exchange, url)| Some((exchange.clone(), url.as_str()?.to_string())))
+            .collect()
+    }
+}
+
+/// One exchange's Open Interest in a token, as found under
+/// [`OpenInterestOverviewView::exchanges`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OpenInterestOverviewExchange {
+    /// The exchange id.
+    pub exchange: String,
+    /// Notional open interest in USD; `None` where the exchange does not
+    /// trade the token (`null` on the wire) or the value is not a number.
+    pub open_interest_usd: Option<f64>,
+    /// The value exactly as received.
+    pub raw: serde_json::Value,
+}
+
+impl OpenInterestOverviewView {
+    /// [`exchanges`](Self::exchanges) as one
+    /// [`OpenInterestOverviewExchange`] per exchange, in exchange-id order.
+    ///
+    /// The API documents `exchanges` as an object from exchange id to
+    /// notional OI in USD, `null` where the exchange does not trade the
+    /// token. Anything that is not an object gives no entries.
+    pub fn exchange_entries(&self) -> Vec<OpenInterestOverviewExchange> {
+        self.exchanges
+            .as_object()
+            .into_iter()
+            .flatten()
+            .map(|(exchange, value)| OpenInterestOverviewExchange {
+                exchange: exchange.clone(),
+                open_interest_usd: json_number(value),
+                raw: value.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Reads a JSON number, or a string holding one, as a finite `f64`.
+fn json_number(value: &serde_json::Value) -> Option<f64> {
+    match value {
+        serde_json::Value::String(text) => text.trim().parse().ok(),
+        _ => value.as_f64(),
+    }
+    .filter(|f: &f64| f.is_finite())
+}
+
+/// Reads a JSON number as an `i64` timestamp, accepting integral floats
+/// (`1.7e12`) as well as integers.
+fn json_timestamp(value: &serde_json::Value) -> Option<i64> {
+    value
+        .as_i64()
+        .or_else(|| value.as_f64().filter(|f| f.is_finite()).map(|f| f as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn points_flatten_per_exchange_series() {
+        let response = OpenInterestHistoryAggregatedResponse {
+            data: serde_json::json!({
+                "bybit": [{"t": 2000, "v": 1.0}],
+                "binance": [{"t": 1000, "v": 2.0}, {"t": 2000.0, "v": 3.0}, {"v": 4.0}],
+            }),
+            ..Default::default()
+        };
+
+        let points = response.points();
+
+        let keys: Vec<(&str, i64)> = points
+            .iter()
+            .map(|p| (p.exchange.as_str(), p.timestamp))
+            .collect();
+        assert_eq!(
+            keys,
+            [("binance", 1000), ("binance", 2000), ("bybit", 2000)]
+        );
+        assert_eq!(points[0].raw, serde_json::json!({"t": 1000, "v": 2.0}));
+    }
+
+    #[test]
+    fn points_of_non_object_data_is_empty() {
+        let response = OpenInterestHistoryAggregatedResponse {
+            data: serde_json::json!("s"),
+            ..Default::default()
+        };
+        assert!(response.points().is_empty());
+    }
+
+    #[test]
+    fn series_group_points_and_attach_urls() {
+        let response = OpenInterestHistoryAggregatedResponse {
+            data: serde_json::json!({
+                "binance": [{"t": 1000, "v": "2.5"}, {"t": 2000, "v": null}],
+                "okx": [{"t": 1000, "v": 1}],
+            }),
+            exchange_url: serde_json::json!({"binance": "https://binance.example", "okx": 7}),
+            ..Default::default()
+        };
+
+        let series = response.series();
+
+        assert_eq!(series.len(), 2);
+        assert_eq!(series[0].url.as_deref(), Some("https://binance.example"));
+        let times: Vec<i64> = series[0].points.iter().map(|p| p.timestamp).collect();
+        assert_eq!(times, [1000, 2000]);
+        assert_eq!(
+            (series[1].exchange.as_str(), &series[1].url),
+            ("okx", &None)
+        );
+    }
+
+    #[test]
+    fn overview_exchanges_read_the_documented_matrix_row() {
+        let row = OpenInterestOverviewView {
+            exchanges: serde_json::json!({"bybit": 7.25e8, "binance": 1.5e9, "upbit": null}),
+            ..Default::default()
+        };
+        let unexpected = OpenInterestOverviewView {
+            exchanges: serde_json::json!([{"exchange": "okx", "oi": 3e8}]),
+            ..Default::default()
+        };
+
+        let entries: Vec<(String, Option<f64>)> = row
+            .exchange_entries()
+            .into_iter()
+            .map(|e| (e.exchange, e.open_interest_usd))
+            .collect();
+        assert_eq!(
+            entries,
+            [
+                ("binance".into(), Some(1.5e9)),
+                ("bybit".into(), Some(7.25e8)),
+                ("upbit".into(), None)
+            ]
+        );
+        assert!(unexpected.exchange_entries().is_empty());
+    }
+}
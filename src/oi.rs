@@ -0,0 +1,543 @@
+//! Open interest analytics: OI aligned with price and volume.
+//!
+//! Three views are combined here:
+//!
+//! - [`OiSeries`](crate::oi::OiSeries) aligns the per-exchange (or summed)
+//!   series of [`OpenInterest::history_aggregated`](crate::OpenInterest::history_aggregated)
+//!   with candles of the same interval, row by row on the bucket timestamp,
+//!   and derives the OI delta per interval, the OI-to-volume ratio, an
+//!   OI-weighted price and [divergences](crate::oi::OiSeries::divergences)
+//!   between OI and price;
+//! - [`snapshots`](crate::oi::snapshots) joins
+//!   [`CexSymbol::oi_stats`](crate::CexSymbol::oi_stats) with
+//!   [`CexSymbol::volume`](crate::CexSymbol::volume) per exchange and quote;
+//! - [`OpenInterestSummaryResponse::market_share`](crate::OpenInterestSummaryResponse::market_share)
+//!   gives a token's share of the open interest of every token.
+//!
+//! [`Client::oi_report`](crate::Client::oi_report) fetches all of them for
+//! one base asset. Timestamps are UTC milliseconds.
+//!
+//! The API documents the history as per-exchange points ordered by `t`, but
+//! not which field of a point carries the open interest. Both
+//! [`OiSeries::align`](crate::oi::OiSeries::align) and `oi_report` therefore
+//! take a `value` reader for the raw point, and count the points it could
+//! not read.
+//!
+//! ```
+//! use datamaxi::oi::{DivergenceKind, OiSeries};
+//! use datamaxi::{CexCandleView, OpenInterestHistoryAggregatedResponse};
+//!
+//! let history = OpenInterestHistoryAggregatedResponse {
+//!     data: serde_json::json!({
+//!         "binance": [{"t": 0, "v": 100.0}, {"t": 3_600_000, "v": 110.0}],
+//!     }),
+//!     ..Default::default()
+//! };
+//! let candle = |timestamp: i64, close: f64| CexCandleView {
+//!     timestamp,
+//!     close,
+//!     volume: 1.0,
+//!     ..Default::default()
+//! };
+//! let value = |point: &serde_json::Value| point["v"].as_f64();
+//! let candles = [candle(0, 50.0), candle(3_600_000, 45.0)];
+//! let series = OiSeries::align(&history, Some("binance"), &candles, value);
+//!
+//! assert_eq!(series.rows[1].oi_delta, Some(10.0));
+//! assert_eq!(series.skipped_points, 0);
+//! let flags = series.divergences(5.0, 5.0);
+//! assert_eq!(flags[0].kind, DivergenceKind::OiUpPriceDown);
+//! ```
+
+use crate::api::{Error, Result};
+use crate::exchange::Exchange;
+use crate::generated::{
+    CexCandleInterval, CexCandleMarket, CexCandleOptions, CexCandleView, CexSymbolOiStatsOptions,
+    CexSymbolOiStatsView, CexSymbolVolumeMarket, CexSymbolVolumeOptions, CexSymbolVolumeView,
+    OpenInterestHistoryAggregatedInterval, OpenInterestHistoryAggregatedOptions,
+    OpenInterestHistoryAggregatedResponse, OpenInterestSummaryOptions, OpenInterestSummaryResponse,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// One bucket of an [`OiSeries`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct OiRow {
+    /// Bucket start, UTC milliseconds.
+    pub timestamp: i64,
+    /// Open interest, in the unit of the history.
+    pub open_interest: Option<f64>,
+    /// Close of the candle opening at `timestamp`.
+    pub close: Option<f64>,
+    /// Quote volume of that candle (`volume * close`).
+    pub quote_volume: Option<f64>,
+    /// Change in open interest since the previous row.
+    pub oi_delta: Option<f64>,
+    /// `oi_delta` relative to the previous open interest, percent.
+    pub oi_change_pct: Option<f64>,
+    /// Change in close since the previous row, percent.
+    pub price_change_pct: Option<f64>,
+    /// `open_interest / quote_volume`.
+    pub oi_to_volume: Option<f64>,
+}
+
+/// How open interest and price moved against each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DivergenceKind {
+    /// Positions opened into a falling price: fresh shorts, or longs
+    /// averaging down.
+    OiUpPriceDown,
+    /// Positions closed into a rising price, typically short covering.
+    OiDownPriceUp,
+}
+
+/// A row where open interest and price moved in opposite directions.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Divergence {
+    /// Bucket start, UTC milliseconds.
+    pub timestamp: i64,
+    /// Which way round.
+    pub kind: DivergenceKind,
+    /// OI change over the interval, percent.
+    pub oi_change_pct: f64,
+    /// Price change over the interval, percent.
+    pub price_change_pct: f64,
+}
+
+/// Open interest history and candles aligned on the bucket timestamp.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OiSeries {
+    /// The exchange the history was taken from; `None` when summed over
+    /// every exchange.
+    pub exchange: Option<Exchange>,
+    /// One row per timestamp in either input, ascending.
+    pub rows: Vec<OiRow>,
+    /// History points of the selected exchange(s) whose open interest the
+    /// `value` reader could not read; they leave no OI in their row.
+    pub skipped_points: usize,
+}
+
+impl OiSeries {
+    /// Aligns the history of `exchange` (or the sum over every exchange,
+    /// for `None`) with `candles`. Candles must use the history's interval:
+    /// a candle only meets the OI bucket starting at its open.
+    ///
+    /// `value` reads the open interest from a point as received
+    /// ([`OpenInterestHistoryPoint::raw`](crate::models::OpenInterestHistoryPoint::raw));
+    /// points it returns `None` for are counted in
+    /// [`skipped_points`](Self::skipped_points).
+    pub fn align(
+        history: &OpenInterestHistoryAggregatedResponse,
+        exchange: Option<&str>,
+        candles: &[CexCandleView],
+        value: impl Fn(&serde_json::Value) -> Option<f64>,
+    ) -> OiSeries {
+        let exchange = exchange.map(Exchange::new);
+        let mut skipped_points = 0;
+        let mut rows: BTreeMap<i64, OiRow> = BTreeMap::new();
+        for point in history.points() {
+            if exchange
+                .as_ref()
+                .is_some_and(|exchange| *exchange != Exchange::new(&point.exchange))
+            {
+                continue;
+            }
+            let row = rows.entry(point.timestamp).or_default();
+            match value(&point.raw) {
+                Some(value) => *row.open_interest.get_or_insert(0.0) += value,
+                None => skipped_points += 1,
+            }
+        }
+        for candle in candles {
+            let row = rows.entry(candle.timestamp).or_default();
+            row.close = Some(candle.close);
+            row.quote_volume = Some(candle.volume * candle.close);
+        }
+
+        let mut previous: Option<OiRow> = None;
+        let rows = rows
+            .into_iter()
+            .map(|(timestamp, mut row)| {
+                row.timestamp = timestamp;
+                if let (Some(prev), Some(oi)) =
+                    (previous.and_then(|p| p.open_interest), row.open_interest)
+                {
+                    row.oi_delta = Some(oi - prev);
+                    row.oi_change_pct = (prev != 0.0).then(|| (oi / prev - 1.0) * 100.0);
+                }
+                if let (Some(prev), Some(close)) = (previous.and_then(|p| p.close), row.close) {
+                    row.price_change_pct = (prev != 0.0).then(|| (close / prev - 1.0) * 100.0);
+                }
+                row.oi_to_volume = match (row.open_interest, row.quote_volume) {
+                    (Some(oi), Some(volume)) if volume > 0.0 => Some(oi / volume),
+                    _ => None,
+                };
+                previous = Some(row);
+                row
+            })
+            .collect();
+        OiSeries {
+            exchange,
+            rows,
+            skipped_points,
+        }
+    }
+
+    /// Mean close weighted by open interest, over rows carrying both.
+    pub fn oi_weighted_price(&self) -> Option<f64> {
+        let (weighted, weight) = self
+            .rows
+            .iter()
+            .filter_map(|row| Some((row.open_interest?, row.close?)))
+            .fold((0.0, 0.0), |(weighted, weight), (oi, close)| {
+                (weighted + oi * close, weight + oi)
+            });
+        (weight > 0.0).then(|| weighted / weight)
+    }
+
+    /// Rows where OI moved at least `min_oi_pct` percent one way and price
+    /// at least `min_price_pct` percent the other.
+    pub fn divergences(&self, min_oi_pct: f64, min_price_pct: f64) -> Vec<Divergence> {
+        self.rows
+            .iter()
+            .filter_map(|row| {
+                let (oi, price) = (row.oi_change_pct?, row.price_change_pct?);
+                if oi.abs() < min_oi_pct || price.abs() < min_price_pct {
+                    return None;
+                }
+                let kind = if oi > 0.0 && price < 0.0 {
+                    DivergenceKind::OiUpPriceDown
+                } else if oi < 0.0 && price > 0.0 {
+                    DivergenceKind::OiDownPriceUp
+                } else {
+                    return None;
+                };
+                Some(Divergence {
+                    timestamp: row.timestamp,
+                    kind,
+                    oi_change_pct: oi,
+                    price_change_pct: price,
+                })
+            })
+            .collect()
+    }
+}
+
+/// The latest open interest of one exchange and quote, with its volume.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OiSnapshot {
+    /// The exchange.
+    pub exchange: Exchange,
+    /// Quote asset, upper-cased.
+    pub quote: String,
+    /// Open interest, USD.
+    pub open_interest_usd: Option<f64>,
+    /// 24h futures volume, USD: from the volume endpoint when it lists the
+    /// pair, else from the OI stats.
+    pub volume_24h_usd: Option<f64>,
+    /// `open_interest_usd / volume_24h_usd`, else the server's ratio.
+    pub oi_to_volume: Option<f64>,
+    /// OI change over 1h, percent.
+    pub change_1h: Option<f64>,
+    /// OI change over 4h, percent.
+    pub change_4h: Option<f64>,
+    /// OI change over 24h, percent.
+    pub change_24h: Option<f64>,
+    /// This pair's share of the open interest over every snapshot.
+    pub share: Option<f64>,
+}
+
+/// Joins OI stats with futures volumes on exchange and quote, largest open
+/// interest first.
+pub fn snapshots(
+    stats: &[CexSymbolOiStatsView],
+    volumes: &[CexSymbolVolumeView],
+) -> Vec<OiSnapshot> {
+    let total: f64 = stats.iter().filter_map(|s| s.open_interest_usd).sum();
+    let mut snapshots: Vec<OiSnapshot> = stats
+        .iter()
+        .map(|stat| {
+            let exchange = Exchange::new(&stat.exchange);
+            let volume_24h_usd = volumes
+                .iter()
+                .find(|volume| {
+                    volume.market.eq_ignore_ascii_case("futures")
+                        && Exchange::new(&volume.exchange) == exchange
+                        && volume.quote.eq_ignore_ascii_case(&stat.quote)
+                })
+                .map(|volume| volume.quote_volume)
+                .or(stat.volume_24h_usd);
+            let oi_to_volume = match (stat.open_interest_usd, volume_24h_usd) {
+                (Some(oi), Some(volume)) if volume > 0.0 => Some(oi / volume),
+                _ => stat.oi_to_vol_ratio,
+            };
+            OiSnapshot {
+                exchange,
+                quote: stat.quote.to_ascii_uppercase(),
+                open_interest_usd: stat.open_interest_usd,
+                volume_24h_usd,
+                oi_to_volume,
+                change_1h: stat.change_1h,
+                change_4h: stat.change_4h,
+                change_24h: stat.change_24h,
+                share: stat
+                    .open_interest_usd
+                    .filter(|_| total > 0.0)
+                    .map(|oi| oi / total),
+            }
+        })
+        .collect();
+    snapshots.sort_by(|a, b| {
+        let oi = |s: &OiSnapshot| s.open_interest_usd.unwrap_or(f64::NEG_INFINITY);
+        oi(b).total_cmp(&oi(a))
+    });
+    snapshots
+}
+
+impl OpenInterestSummaryResponse {
+    /// The token's open interest over
+    /// [`grand_total`](crate::OpenInterestSummaryResponse::grand_total), or
+    /// `None` when it is not among the summary's top tokens.
+    pub fn market_share(&self, token_id: &str) -> Option<f64> {
+        let token = self
+            .tokens
+            .iter()
+            .find(|token| token.token_id == token_id)?;
+        (self.grand_total > 0.0).then(|| token.open_interest_usd / self.grand_total)
+    }
+}
+
+/// Everything [`Client::oi_report`](crate::Client::oi_report) fetched for
+/// one base asset.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OiReport {
+    /// The token id the OI stats resolved the base to.
+    pub token_id: String,
+    /// Per exchange and quote, largest open interest first.
+    pub snapshots: Vec<OiSnapshot>,
+    /// The token's share of all open interest, when in the summary.
+    pub market_share: Option<f64>,
+    /// The history of the requested exchange, aligned with its candles.
+    pub series: OiSeries,
+}
+
+/// Body of the async and blocking `oi_report`; see `get_loop!` in [`crate::api`].
+macro_rules! oi_report {
+    ($client:expr, $base:expr, $exchange:expr, $interval:expr, $value:expr $(, $aw:ident)?) => {{
+        let (base, exchange): (&str, &str) = ($base, $exchange);
+        let base = base.trim().to_ascii_uppercase();
+        let interval: OpenInterestHistoryAggregatedInterval = $interval;
+        let candle_interval = CexCandleInterval::from_wire(interval.as_str()).ok_or_else(|| {
+            Error::InvalidArgument {
+                argument: "interval".to_string(),
+                reason: format!("candles have no {interval} interval to align with"),
+            }
+        })?;
+        let cex_symbol = $client.cex_symbol();
+        let stats = cex_symbol.oi_stats(base.as_str(), CexSymbolOiStatsOptions::new())$(.$aw)?;
+        let stats = stats?;
+        let volume_options = CexSymbolVolumeOptions::new().market(CexSymbolVolumeMarket::Futures);
+        let volumes = cex_symbol.volume(base.as_str(), volume_options)$(.$aw)?;
+        let volumes = volumes?;
+
+        let venue = stats
+            .iter()
+            .find(|stat| Exchange::new(&stat.exchange) == Exchange::new(exchange))
+            .ok_or_else(|| Error::InvalidArgument {
+                argument: "exchange".to_string(),
+                reason: format!("{exchange} reports no open interest for {base}"),
+            })?;
+        let token_id = stats
+            .iter()
+            .find_map(|stat| stat.token_id.clone())
+            .ok_or_else(|| Error::InvalidArgument {
+                argument: "base".to_string(),
+                reason: format!("no token id is known for {base}"),
+            })?;
+        let symbol = format!("{base}-{}", venue.quote).to_ascii_uppercase();
+
+        let open_interest = $client.open_interest();
+        let summary = open_interest.summary(OpenInterestSummaryOptions::new())$(.$aw)?;
+        let market_share = summary?.market_share(&token_id);
+        let history_options = OpenInterestHistoryAggregatedOptions::new().interval(interval);
+        let history = open_interest.history_aggregated(token_id.as_str(), history_options)$(.$aw)?;
+        let history = history?;
+
+        let points = history.points();
+        let candles = match (points.first(), points.last()) {
+            (Some(first), Some(last)) => {
+                let options = CexCandleOptions::new()
+                    .market(CexCandleMarket::Futures)
+                    .interval(candle_interval)
+                    .from(first.timestamp / 1000)
+                    .to(last.timestamp / 1000);
+                let response = $client.cex_candle().get(exchange, symbol.as_str(), options)$(.$aw)?;
+                response?.data
+            }
+            _ => Vec::new(),
+        };
+
+        let series = OiSeries::align(&history, Some(exchange), &candles, $value);
+        let read = series.rows.iter().any(|row| row.open_interest.is_some());
+        if series.skipped_points > 0 && !read {
+            return Err(Error::InvalidArgument {
+                argument: "value".to_string(),
+                reason: format!(
+                    "no open interest could be read from the {} history points of {exchange}",
+                    series.skipped_points
+                ),
+            });
+        }
+
+        Ok(OiReport {
+            series,
+            snapshots: snapshots(&stats, &volumes),
+            market_share,
+            token_id,
+        })
+    }};
+}
+
+impl crate::api::Client {
+    /// Builds an [`OiReport`] for `base` (e.g. `BTC`): the OI stats and
+    /// futures volumes of every exchange, the token's share of all open
+    /// interest, and the OI history of `exchange` at `interval` aligned
+    /// with its futures candles (five requests). `value` reads the open
+    /// interest of a history point, as in [`OiSeries::align`].
+    ///
+    /// Fails with [`Error::InvalidArgument`] when `interval` has no candle
+    /// counterpart, or when `value` reads none of the exchange's points.
+    pub async fn oi_report(
+        &self,
+        base: &str,
+        exchange: &str,
+        interval: OpenInterestHistoryAggregatedInterval,
+        value: fn(&serde_json::Value) -> Option<f64>,
+    ) -> Result<OiReport> {
+        oi_report!(self, base, exchange, interval, value, await)
+    }
+}
+
+#[cfg(feature = "sync")]
+impl crate::api::sync::Client {
+    /// Blocking mirror of the async `Client::oi_report`.
+    pub fn oi_report(
+        &self,
+        base: &str,
+        exchange: &str,
+        interval: OpenInterestHistoryAggregatedInterval,
+        value: fn(&serde_json::Value) -> Option<f64>,
+    ) -> Result<OiReport> {
+        oi_report!(self, base, exchange, interval, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(point: &serde_json::Value) -> Option<f64> {
+        point["v"].as_f64()
+    }
+
+    fn candle(timestamp: i64, close: f64, volume: f64) -> CexCandleView {
+        CexCandleView {
+            timestamp,
+            close,
+            volume,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn align_sums_exchanges_and_derives_deltas() {
+        let history = OpenInterestHistoryAggregatedResponse {
+            data: serde_json::json!({
+                "binance": [{"t": 0, "v": 60.0}, {"t": 1000, "v": 50.0}, {"t": 2000, "v": 80.0}],
+                "okx": [{"t": 0, "v": 40.0}, {"t": 1000, "v": 40.0}, {"t": 2000, "v": 40.0}],
+            }),
+            ..Default::default()
+        };
+        let candles = [
+            candle(0, 10.0, 5.0),
+            candle(1000, 12.0, 1.0),
+            candle(2000, 9.0, 2.0),
+        ];
+        let series = OiSeries::align(&history, None, &candles, value);
+
+        let deltas: Vec<_> = series.rows.iter().map(|row| row.oi_delta).collect();
+        assert_eq!(deltas, [None, Some(-10.0), Some(30.0)]);
+        assert_eq!(series.rows[0].oi_to_volume, Some(2.0));
+        assert_eq!(series.rows[2].price_change_pct, Some(-25.0));
+        assert_eq!(
+            series.oi_weighted_price(),
+            Some((1000.0 + 1080.0 + 1080.0) / 310.0)
+        );
+
+        let kinds: Vec<_> = series
+            .divergences(5.0, 5.0)
+            .iter()
+            .map(|d| d.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            [DivergenceKind::OiDownPriceUp, DivergenceKind::OiUpPriceDown]
+        );
+        assert_eq!(
+            OiSeries::align(&history, Some("OKX"), &[], value).rows[2].oi_delta,
+            Some(0.0)
+        );
+    }
+
+    #[test]
+    fn align_counts_points_it_cannot_read() {
+        let history = OpenInterestHistoryAggregatedResponse {
+            data: serde_json::json!({
+                "binance": [{"t": 0, "v": 60.0}, {"t": 1000, "oi": 50.0}],
+                "okx": [{"t": 0}],
+            }),
+            ..Default::default()
+        };
+
+        let series = OiSeries::align(&history, Some("binance"), &[], value);
+        let oi: Vec<_> = series.rows.iter().map(|row| row.open_interest).collect();
+        assert_eq!(oi, [Some(60.0), None]);
+        assert_eq!(series.skipped_points, 1);
+        assert_eq!(
+            OiSeries::align(&history, None, &[], value).skipped_points,
+            2
+        );
+    }
+
+    #[test]
+    fn snapshots_prefer_futures_volume_and_compute_shares() {
+        let stat = |exchange: &str, oi: f64| CexSymbolOiStatsView {
+            exchange: exchange.into(),
+            quote: "usdt".into(),
+            open_interest_usd: Some(oi),
+            volume_24h_usd: Some(1.0),
+            ..Default::default()
+        };
+        let volume = |market: &str, quote_volume: f64| CexSymbolVolumeView {
+            exchange: "binance".into(),
+            market: market.into(),
+            quote: "USDT".into(),
+            quote_volume,
+            ..Default::default()
+        };
+        let rows = snapshots(
+            &[stat("bybit", 25.0), stat("binance", 75.0)],
+            &[volume("spot", 10.0), volume("futures", 150.0)],
+        );
+
+        assert_eq!(rows[0].exchange, Exchange::new("binance"));
+        assert_eq!(
+            (rows[0].volume_24h_usd, rows[0].oi_to_volume),
+            (Some(150.0), Some(0.5))
+        );
+        assert_eq!(
+            (rows[1].volume_24h_usd, rows[1].share),
+            (Some(1.0), Some(0.25))
+        );
+    }
+}
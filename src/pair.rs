@@ -0,0 +1,291 @@
+//! A parsed trading pair.
+//!
+//! The endpoint wrappers take symbols as strings in the API's canonical
+//! `BASE-QUOTE` form (`"BTC-USDT"`), while responses split them into
+//! `base` / `quote` / `symbol` fields. A [`Pair`](crate::pair::Pair) holds
+//! the base and quote assets, normalized to upper case, and parses the usual
+//! spellings of a symbol: any of `-`, `/`, `_`, `:` as separator, any case,
+//! concatenated exchange-native forms such as `BTCUSDT` (split on a known
+//! quote asset), and a trailing `SWAP` / `PERP` marker.
+//!
+//! `Pair` converts into `String` in the canonical form, so it can be passed
+//! wherever a wrapper takes a symbol:
+//!
+//! ```no_run
+//! use datamaxi::pair::Pair;
+//! use datamaxi::{CexCandleOptions, Client};
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let pair: Pair = "btc/usdt".parse()?;
+//! let candles = Client::new("my_api_key")
+//!     .cex_candle()
+//!     .get("binance", &pair, CexCandleOptions::new())
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`Pair::parse_native`](crate::pair::Pair::parse_native) and
+//! [`Pair::to_native`](crate::pair::Pair::to_native) translate from and to
+//! the formats individual exchanges use on their own APIs (`KRW-BTC` on
+//! Upbit, `BTC_KRW` on Bithumb, `BTCUSDT` on Binance, …).
+
+use crate::generated::{CexCandleSymbolsView, FundingRateSymbolsView, TickerView};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Quote assets recognized when splitting a concatenated symbol such as
+/// `BTCUSDT`, longest first so `FDUSD` wins over `USD`.
+const KNOWN_QUOTES: &[&str] = &[
+    "FDUSD", "USDT", "USDC", "BUSD", "TUSD", "USDE", "USD", "DAI", "KRW", "EUR", "TRY", "BRL",
+    "JPY", "GBP", "AUD", "BTC", "ETH", "BNB",
+];
+
+/// Trailing contract markers dropped while parsing (`BTC-USDT-SWAP`).
+const CONTRACT_SUFFIXES: &[&str] = &["SWAP", "PERP", "PERPETUAL"];
+
+const SEPARATORS: &[char] = &['-', '/', '_', ':'];
+
+/// A symbol that could not be parsed as a trading pair.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("invalid trading pair {input:?}: {reason}")]
+pub struct ParsePairError {
+    input: String,
+    reason: &'static str,
+}
+
+impl ParsePairError {
+    fn new(input: &str, reason: &'static str) -> Self {
+        ParsePairError {
+            input: input.to_string(),
+            reason,
+        }
+    }
+
+    /// The text that failed to parse.
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+}
+
+/// A base / quote trading pair, e.g. `BTC-USDT`.
+///
+/// Both assets are trimmed and upper-cased. Displays, serializes and
+/// converts into `String` as the canonical `BASE-QUOTE` symbol.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Pair {
+    base: String,
+    quote: String,
+}
+
+impl Pair {
+    /// A pair of `base` priced in `quote`.
+    pub fn new(base: impl AsRef<str>, quote: impl AsRef<str>) -> Self {
+        Pair {
+            base: base.as_ref().trim().to_ascii_uppercase(),
+            quote: quote.as_ref().trim().to_ascii_uppercase(),
+        }
+    }
+
+    /// The traded asset (`BTC` in `BTC-USDT`).
+    pub fn base(&self) -> &str {
+        &self.base
+    }
+
+    /// The asset prices are quoted in (`USDT` in `BTC-USDT`).
+    pub fn quote(&self) -> &str {
+        &self.quote
+    }
+
+    /// The canonical API symbol, `BASE-QUOTE`.
+    pub fn symbol(&self) -> String {
+        format!("{}-{}", self.base, self.quote)
+    }
+
+    /// Parses `symbol` as written on `exchange`'s own API.
+    ///
+    /// Upbit lists the quote first (`KRW-BTC`); every other exchange is
+    /// parsed as by [`FromStr`].
+    pub fn parse_native(exchange: &str, symbol: &str) -> Result<Pair, ParsePairError> {
+        let pair: Pair = symbol.parse()?;
+        if exchange.eq_ignore_ascii_case("upbit") {
+            Ok(Pair::new(pair.quote, pair.base))
+        } else {
+            Ok(pair)
+        }
+    }
+
+    /// The symbol as written on `exchange`'s own API: `KRW-BTC` on Upbit,
+    /// `BTC_KRW` on Bithumb and Gate, `BTCUSDT` on Binance, Bybit, Bitget and
+    /// MEXC, and the canonical `BTC-USDT` elsewhere.
+    pub fn to_native(&self, exchange: &str) -> String {
+        match exchange.to_ascii_lowercase().as_str() {
+            "upbit" => format!("{}-{}", self.quote, self.base),
+            "bithumb" | "gate" | "gateio" => format!("{}_{}", self.base, self.quote),
+            "binance" | "bybit" | "bitget" | "mexc" => format!("{}{}", self.base, self.quote),
+            _ => self.symbol(),
+        }
+    }
+}
+
+impl FromStr for Pair {
+    type Err = ParsePairError;
+
+    fn from_str(input: &str) -> Result<Self, ParsePairError> {
+        let upper = input.trim().to_ascii_uppercase();
+        let mut parts: Vec<&str> = upper.split(SEPARATORS).collect();
+        if parts.len() >= 2 && CONTRACT_SUFFIXES.contains(&parts[parts.len() - 1]) {
+            parts.pop();
+        }
+
+        let (base, quote) = match parts.as_slice() {
+            [base, quote] => (*base, *quote),
+            [joined] => {
+                let joined = CONTRACT_SUFFIXES
+                    .iter()
+                    .find_map(|suffix| joined.strip_suffix(suffix).filter(|rest| !rest.is_empty()))
+                    .unwrap_or(joined);
+                KNOWN_QUOTES
+                    .iter()
+                    .find_map(|quote| {
+                        joined
+                            .strip_suffix(quote)
+                            .filter(|base| !base.is_empty())
+                            .map(|base| (base, *quote))
+                    })
+                    .ok_or_else(|| {
+                        ParsePairError::new(input, "no separator or known quote asset")
+                    })?
+            }
+            _ => return Err(ParsePairError::new(input, "more than two assets")),
+        };
+
+        if base.is_empty() || quote.is_empty() {
+            return Err(ParsePairError::new(input, "empty base or quote asset"));
+        }
+        Ok(Pair::new(base, quote))
+    }
+}
+
+impl fmt::Display for Pair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.base, self.quote)
+    }
+}
+
+impl From<Pair> for String {
+    fn from(pair: Pair) -> Self {
+        pair.symbol()
+    }
+}
+
+impl From<&Pair> for String {
+    fn from(pair: &Pair) -> Self {
+        pair.symbol()
+    }
+}
+
+impl Serialize for Pair {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Pair {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Adds `pair()` to views carrying `base` / `quote` / `symbol` fields.
+macro_rules! impl_pair_accessor {
+    ($($view:ident)*) => {$(
+        impl $view {
+            /// The pair from [`base`](Self::base) and [`quote`](Self::quote),
+            /// or parsed from [`symbol`](Self::symbol) when either is empty.
+            pub fn pair(&self) -> Result<Pair, ParsePairError> {
+                if self.base.is_empty() || self.quote.is_empty() {
+                    self.symbol.parse()
+                } else {
+                    Ok(Pair::new(&self.base, &self.quote))
+                }
+            }
+        }
+    )*};
+}
+
+impl_pair_accessor! {
+    CexCandleSymbolsView
+    FundingRateSymbolsView
+    TickerView
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_common_spellings() {
+        let btc_usdt = Pair::new("BTC", "USDT");
+        for input in [
+            "BTC-USDT",
+            "btc/usdt",
+            " Btc_Usdt ",
+            "BTC:USDT",
+            "BTCUSDT",
+            "BTC-USDT-SWAP",
+            "BTCUSDT_PERP",
+        ] {
+            assert_eq!(input.parse::<Pair>(), Ok(btc_usdt.clone()), "{input}");
+        }
+        assert_eq!("ethfdusd".parse(), Ok(Pair::new("ETH", "FDUSD")));
+    }
+
+    #[test]
+    fn rejects_unsplittable_symbols() {
+        for input in ["BTC", "USDT", "-USDT", "A-B-C", ""] {
+            let err = input.parse::<Pair>().expect_err(input);
+            assert_eq!(err.input(), input);
+        }
+    }
+
+    #[test]
+    fn native_formats_round_trip() {
+        let pair = Pair::new("btc", "krw");
+        for exchange in ["upbit", "bithumb", "binance", "okx"] {
+            let native = pair.to_native(exchange);
+            assert_eq!(Pair::parse_native(exchange, &native), Ok(pair.clone()));
+        }
+        assert_eq!(pair.to_native("Upbit"), "KRW-BTC");
+        assert_eq!(String::from(&pair), "BTC-KRW");
+    }
+
+    #[test]
+    fn views_prefer_split_fields_and_serde_uses_the_symbol() {
+        let ticker = TickerView {
+            base: "btc".into(),
+            quote: "usdt".into(),
+            symbol: "ignored".into(),
+            ..Default::default()
+        };
+        assert_eq!(ticker.pair(), Ok(Pair::new("BTC", "USDT")));
+
+        let symbols = CexCandleSymbolsView {
+            symbol: "ETH-KRW".into(),
+            ..Default::default()
+        };
+        let pair = symbols.pair().expect("parsed from symbol");
+        assert_eq!(
+            serde_json::to_string(&pair).expect("serializes"),
+            r#""ETH-KRW""#
+        );
+        assert_eq!(
+            serde_json::from_str::<Pair>(r#""eth_krw""#).ok(),
+            Some(pair)
+        );
+    }
+}
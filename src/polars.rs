@@ -0,0 +1,111 @@
+//! Polars conversion of the response models (feature `polars`).
+//!
+//! Any slice of [`Tabular`](crate::table::Tabular) rows converts to a typed
+//! [`DataFrame`](::polars::prelude::DataFrame) via
+//! [`ToDataFrame`](crate::polars::ToDataFrame), following the
+//! [`table`](crate::table) schema rules: timestamps are `datetime[ms]`,
+//! `Option<_>` fields hold nulls, and string lists are `list[str]`.
+//!
+//! Timestamp columns carry no time zone (that needs Polars' `timezones`
+//! feature, which this crate does not enable); their values are UTC. Tag
+//! them with `dt.replace_time_zone(Some("UTC"))` if you need an aware
+//! column.
+//!
+//! ```
+//! use datamaxi::polars::ToDataFrame;
+//! use datamaxi::IndexPriceView;
+//!
+//! let prices = vec![IndexPriceView::default()]; // e.g. `IndexPrice::get(..).data`
+//! let frame = prices.to_dataframe()?;
+//! assert_eq!(frame.height(), 1);
+//! # Ok::<(), datamaxi::polars::PolarsError>(())
+//! ```
+
+use crate::table::{Column, ColumnType, ColumnValues, Tabular};
+use ::polars::prelude::{DataFrame, DataType, IntoColumn, NamedFrom, PlSmallStr, Series, TimeUnit};
+
+/// Re-exported so callers can name the conversion error without depending
+/// on `polars` directly.
+pub use ::polars::prelude::PolarsError;
+
+/// Converts a slice of rows into a [`DataFrame`].
+pub trait ToDataFrame {
+    /// One data frame with a row per element.
+    fn to_dataframe(&self) -> Result<DataFrame, PolarsError>;
+}
+
+impl<T: Tabular> ToDataFrame for [T] {
+    fn to_dataframe(&self) -> Result<DataFrame, PolarsError> {
+        let columns = T::columns(self)
+            .into_iter()
+            .map(|column| series(column).map(IntoColumn::into_column))
+            .collect::<Result<Vec<_>, _>>()?;
+        DataFrame::new(columns)
+    }
+}
+
+fn series(column: Column) -> Result<Series, PolarsError> {
+    let name = PlSmallStr::from(column.spec.name.as_str());
+    Ok(match column.values {
+        ColumnValues::Int64(values) if column.spec.column_type == ColumnType::Timestamp => {
+            Series::new(name, values).cast(&DataType::Datetime(TimeUnit::Milliseconds, None))?
+        }
+        ColumnValues::Int64(values) => Series::new(name, values),
+        ColumnValues::Float64(values) => Series::new(name, values),
+        ColumnValues::Boolean(values) => Series::new(name, values),
+        ColumnValues::Utf8(values) => Series::new(name, values),
+        ColumnValues::Utf8List(values) => {
+            let lists: Vec<Option<Series>> = values
+                .into_iter()
+                .map(|items| items.map(|items| Series::new(PlSmallStr::EMPTY, items)))
+                .collect();
+            Series::new(name, lists)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generated::{CexSymbolCautionsView, FundingRateHistoryView};
+
+    #[test]
+    fn dataframe_types_timestamps_and_nulls() {
+        let rows = [
+            FundingRateHistoryView {
+                timestamp: 1_700_000_000_000,
+                funding_rate: Some(0.0001),
+            },
+            FundingRateHistoryView {
+                timestamp: 1_700_000_028_800,
+                funding_rate: None,
+            },
+        ];
+
+        let frame = rows.to_dataframe().expect("valid frame");
+
+        assert_eq!(
+            frame.column("timestamp").expect("column").dtype(),
+            &DataType::Datetime(TimeUnit::Milliseconds, None)
+        );
+        assert_eq!(
+            frame.column("funding_rate").expect("column").null_count(),
+            1
+        );
+    }
+
+    #[test]
+    fn string_lists_become_list_columns() {
+        let rows = [CexSymbolCautionsView {
+            reasons: vec!["a".into(), "b".into()],
+            ..Default::default()
+        }];
+
+        let frame = rows.to_dataframe().expect("valid frame");
+
+        assert!(matches!(
+            frame.column("reasons").expect("column").dtype(),
+            DataType::List(_)
+        ));
+    }
+}
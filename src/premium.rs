@@ -0,0 +1,477 @@
+//! A readable, structured view of [`PremiumDetail`].
+//!
+//! [`PremiumDetail`] mirrors the wire format: about 75 flat fields with
+//! abbreviated names (`sp`, `tp`, `pdp1h`, `soich24h`, `tbd2p`, …), `s*` for
+//! the source leg and `t*` for the target leg.
+//! [`PremiumDetailView`](crate::premium::PremiumDetailView) regroups them
+//! into named, documented parts:
+//!
+//! - [`source`](crate::premium::PremiumDetailView::source) /
+//!   [`target`](crate::premium::PremiumDetailView::target): one
+//!   [`PremiumLeg`](crate::premium::PremiumLeg) per exchange, with market,
+//!   prices, recent price changes, [`funding`](crate::premium::LegFunding),
+//!   [`open interest`](crate::premium::LegOpenInterest) and
+//!   [`order-book depth`](crate::premium::LegDepth);
+//! - [`premium`](crate::premium::PremiumDetailView::premium): the premium
+//!   now and 5m … 24h ago;
+//! - the funding gap, net funding rate, premium duration and
+//!   transferability of the pair.
+//!
+//! The view converts losslessly in both directions
+//! ([`PremiumDetail::view`], `From`), and derives serde with the readable
+//! field names, for logging and storage.
+//!
+//! ```
+//! use datamaxi::premium::PremiumDetailView;
+//! use datamaxi::PremiumDetail;
+//!
+//! let detail = PremiumDetail { se: "upbit".into(), sp: Some(101.5), pdp1h: Some(1.2), ..Default::default() };
+//! let view = detail.view();
+//! assert_eq!(view.source.exchange, "upbit");
+//! assert_eq!(view.source.price, Some(101.5));
+//! assert_eq!(view.premium.ago.h1, Some(1.2));
+//!
+//! let json = serde_json::to_string(&view)?;
+//! let back: PremiumDetailView = serde_json::from_str(&json)?;
+//! assert_eq!(PremiumDetail::from(back).sp, Some(101.5));
+//! # Ok::<(), serde_json::Error>(())
+//! ```
+
+use crate::generated::PremiumDetail;
+use serde::{Deserialize, Serialize};
+
+/// [`PremiumDetail`] with readable, grouped fields.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PremiumDetailView {
+    /// The base token's id (wire `bid`).
+    pub base_id: String,
+    /// When the premium was computed, UTC milliseconds (`d`).
+    pub timestamp: i64,
+    /// The source leg (`s*` fields).
+    pub source: PremiumLeg,
+    /// The target leg (`t*` fields).
+    pub target: PremiumLeg,
+    /// Price difference between source and target, percent (`pdp*`).
+    pub premium: PremiumHistory,
+    /// How long the premium has persisted (`pmd`).
+    pub premium_duration: Option<i64>,
+    /// Source minus target funding rate, ignoring funding intervals (`fg`).
+    pub funding_gap: Option<f64>,
+    /// Net funding rate, taking funding intervals into account (`nfr`).
+    pub net_funding_rate: Option<f64>,
+    /// Whether the asset can be transferred between the two exchanges;
+    /// `None` if unknown (`t`).
+    pub transferable: Option<bool>,
+}
+
+/// The premium now and at earlier points.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PremiumHistory {
+    /// The current premium, percent (`pdp`).
+    pub current: Option<f64>,
+    /// The premium 5m … 24h ago, percent (`pdp5m` … `pdp24h`).
+    pub ago: ByWindow,
+}
+
+/// One value per look-back window.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ByWindow {
+    /// 5 minutes.
+    pub m5: Option<f64>,
+    /// 15 minutes.
+    pub m15: Option<f64>,
+    /// 30 minutes.
+    pub m30: Option<f64>,
+    /// 1 hour.
+    pub h1: Option<f64>,
+    /// 4 hours.
+    pub h4: Option<f64>,
+    /// 24 hours.
+    pub h24: Option<f64>,
+}
+
+/// One side of a premium pair. Field comments give the source wire name;
+/// the target's is the same with `t` for `s`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PremiumLeg {
+    /// Exchange id (`se`).
+    pub exchange: String,
+    /// Market type, `spot` or `futures` (`sm`).
+    pub market: String,
+    /// Base token (`sb`).
+    pub base: String,
+    /// Quote token (`sq`).
+    pub quote: String,
+    /// Latest price in the requested currency (`sp`).
+    pub price: Option<f64>,
+    /// When the ticker was taken, UTC milliseconds (`st`).
+    pub ticker_timestamp: i64,
+    /// 24h trading volume in the requested currency (`sv`).
+    pub volume_24h: Option<f64>,
+    /// Best bid (`shb`).
+    pub highest_bid: Option<f64>,
+    /// Best ask (`sla`).
+    pub lowest_ask: Option<f64>,
+    /// Price change over the last 5m … 24h, percent (`spdp5m` …
+    /// `spdp24h`).
+    pub price_change: ByWindow,
+    /// Funding of a futures leg.
+    pub funding: LegFunding,
+    /// Open interest of a futures leg.
+    pub open_interest: LegOpenInterest,
+    /// Order-book depth.
+    pub depth: LegDepth,
+    /// Whether margin trading is supported; spot legs only (`sms`).
+    pub margin_supported: Option<bool>,
+    /// Chain of an AMM ticker (`sc`).
+    pub amm_chain: Option<String>,
+    /// Pool address of an AMM ticker (`spa`).
+    pub amm_pool_address: Option<String>,
+}
+
+/// Funding of a futures leg.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LegFunding {
+    /// Funding rate (`sfr`).
+    pub rate: Option<f64>,
+    /// Funding interval in hours (`sfri`).
+    pub interval_hours: Option<i64>,
+    /// When the rate was read, UTC milliseconds (`sfrt`).
+    pub timestamp: Option<i64>,
+    /// Next funding time, UTC milliseconds (`snd`).
+    pub next_funding_at: Option<i64>,
+}
+
+/// Open interest of a futures leg.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LegOpenInterest {
+    /// Open interest in USD (`soi`).
+    pub usd: Option<f64>,
+    /// Change over the last hour, percent (`soich1h`).
+    pub change_1h: Option<f64>,
+    /// Change over the last 4 hours, percent (`soich4h`).
+    pub change_4h: Option<f64>,
+    /// Change over the last 24 hours, percent (`soich24h`).
+    pub change_24h: Option<f64>,
+    /// Open interest over 24h USD volume (`soivr`).
+    pub to_volume_ratio: Option<f64>,
+}
+
+/// Order-book depth of a leg. The API reports ask depth for the source and
+/// bid depth for the target (the sides an arbitrage would take), so the
+/// other pair of fields is always `None`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LegDepth {
+    /// Ask depth within +2%, in base units (`sad`; source only).
+    pub ask_base: Option<f64>,
+    /// Ask depth within +2%, in quote units (`sadf`; source only).
+    pub ask_quote: Option<f64>,
+    /// Bid depth within -2%, in base units (`tbd`; target only).
+    pub bid_base: Option<f64>,
+    /// Bid depth within -2%, in quote units (`tbdf`; target only).
+    pub bid_quote: Option<f64>,
+    /// Volume depth within -2% of the price, the bid side (`sad2p` /
+    /// `tad2p`).
+    pub minus_2pct: Option<f64>,
+    /// Volume depth within +2% of the price, the ask side (`sbd2p` /
+    /// `tbd2p`).
+    pub plus_2pct: Option<f64>,
+}
+
+impl PremiumDetail {
+    /// This detail as a [`PremiumDetailView`].
+    pub fn view(&self) -> PremiumDetailView {
+        PremiumDetailView::from(self.clone())
+    }
+}
+
+impl From<&PremiumDetail> for PremiumDetailView {
+    fn from(detail: &PremiumDetail) -> Self {
+        PremiumDetailView::from(detail.clone())
+    }
+}
+
+impl From<PremiumDetail> for PremiumDetailView {
+    fn from(d: PremiumDetail) -> Self {
+        PremiumDetailView {
+            base_id: d.bid,
+            timestamp: d.d,
+            source: PremiumLeg {
+                exchange: d.se,
+                market: d.sm,
+                base: d.sb,
+                quote: d.sq,
+                price: d.sp,
+                ticker_timestamp: d.st,
+                volume_24h: d.sv,
+                highest_bid: d.shb,
+                lowest_ask: d.sla,
+                price_change: ByWindow {
+                    m5: d.spdp5m,
+                    m15: d.spdp15m,
+                    m30: d.spdp30m,
+                    h1: d.spdp1h,
+                    h4: d.spdp4h,
+                    h24: d.spdp24h,
+                },
+                funding: LegFunding {
+                    rate: d.sfr,
+                    interval_hours: d.sfri,
+                    timestamp: d.sfrt,
+                    next_funding_at: d.snd,
+                },
+                open_interest: LegOpenInterest {
+                    usd: d.soi,
+                    change_1h: d.soich1h,
+                    change_4h: d.soich4h,
+                    change_24h: d.soich24h,
+                    to_volume_ratio: d.soivr,
+                },
+                depth: LegDepth {
+                    ask_base: d.sad,
+                    ask_quote: d.sadf,
+                    bid_base: None,
+                    bid_quote: None,
+                    minus_2pct: d.sad2p,
+                    plus_2pct: d.sbd2p,
+                },
+                margin_supported: d.sms,
+                amm_chain: d.sc,
+                amm_pool_address: d.spa,
+            },
+            target: PremiumLeg {
+                exchange: d.te,
+                market: d.tm,
+                base: d.tb,
+                quote: d.tq,
+                price: d.tp,
+                ticker_timestamp: d.tt,
+                volume_24h: d.tv,
+                highest_bid: d.thb,
+                lowest_ask: d.tla,
+                price_change: ByWindow {
+                    m5: d.tpdp5m,
+                    m15: d.tpdp15m,
+                    m30: d.tpdp30m,
+                    h1: d.tpdp1h,
+                    h4: d.tpdp4h,
+                    h24: d.tpdp24h,
+                },
+                funding: LegFunding {
+                    rate: d.tfr,
+                    interval_hours: d.tfri,
+                    timestamp: d.tfrt,
+                    next_funding_at: d.tnd,
+                },
+                open_interest: LegOpenInterest {
+                    usd: d.toi,
+                    change_1h: d.toich1h,
+                    change_4h: d.toich4h,
+                    change_24h: d.toich24h,
+                    to_volume_ratio: d.toivr,
+                },
+                depth: LegDepth {
+                    ask_base: None,
+                    ask_quote: None,
+                    bid_base: d.tbd,
+                    bid_quote: d.tbdf,
+                    minus_2pct: d.tad2p,
+                    plus_2pct: d.tbd2p,
+                },
+                margin_supported: d.tms,
+                amm_chain: d.tc,
+                amm_pool_address: d.tpa,
+            },
+            premium: PremiumHistory {
+                current: d.pdp,
+                ago: ByWindow {
+                    m5: d.pdp5m,
+                    m15: d.pdp15m,
+                    m30: d.pdp30m,
+                    h1: d.pdp1h,
+                    h4: d.pdp4h,
+                    h24: d.pdp24h,
+                },
+            },
+            premium_duration: d.pmd,
+            funding_gap: d.fg,
+            net_funding_rate: d.nfr,
+            transferable: d.t,
+        }
+    }
+}
+
+/// The inverse of `From<PremiumDetail>`. The depth fields the API never
+/// reports for a leg (see [`LegDepth`]) are dropped.
+impl From<PremiumDetailView> for PremiumDetail {
+    fn from(view: PremiumDetailView) -> Self {
+        let PremiumDetailView {
+            base_id,
+            timestamp,
+            source: s,
+            target: t,
+            premium,
+            premium_duration,
+            funding_gap,
+            net_funding_rate,
+            transferable,
+        } = view;
+        PremiumDetail {
+            bid: base_id,
+            d: timestamp,
+            fg: funding_gap,
+            nfr: net_funding_rate,
+            pdp: premium.current,
+            pdp5m: premium.ago.m5,
+            pdp15m: premium.ago.m15,
+            pdp30m: premium.ago.m30,
+            pdp1h: premium.ago.h1,
+            pdp4h: premium.ago.h4,
+            pdp24h: premium.ago.h24,
+            pmd: premium_duration,
+            t: transferable,
+
+            se: s.exchange,
+            sm: s.market,
+            sb: s.base,
+            sq: s.quote,
+            sp: s.price,
+            st: s.ticker_timestamp,
+            sv: s.volume_24h,
+            shb: s.highest_bid,
+            sla: s.lowest_ask,
+            spdp5m: s.price_change.m5,
+            spdp15m: s.price_change.m15,
+            spdp30m: s.price_change.m30,
+            spdp1h: s.price_change.h1,
+            spdp4h: s.price_change.h4,
+            spdp24h: s.price_change.h24,
+            sfr: s.funding.rate,
+            sfri: s.funding.interval_hours,
+            sfrt: s.funding.timestamp,
+            snd: s.funding.next_funding_at,
+            soi: s.open_interest.usd,
+            soich1h: s.open_interest.change_1h,
+            soich4h: s.open_interest.change_4h,
+            soich24h: s.open_interest.change_24h,
+            soivr: s.open_interest.to_volume_ratio,
+            sad: s.depth.ask_base,
+            sadf: s.depth.ask_quote,
+            sad2p: s.depth.minus_2pct,
+            sbd2p: s.depth.plus_2pct,
+            sms: s.margin_supported,
+            sc: s.amm_chain,
+            spa: s.amm_pool_address,
+
+            te: t.exchange,
+            tm: t.market,
+            tb: t.base,
+            tq: t.quote,
+            tp: t.price,
+            tt: t.ticker_timestamp,
+            tv: t.volume_24h,
+            thb: t.highest_bid,
+            tla: t.lowest_ask,
+            tpdp5m: t.price_change.m5,
+            tpdp15m: t.price_change.m15,
+            tpdp30m: t.price_change.m30,
+            tpdp1h: t.price_change.h1,
+            tpdp4h: t.price_change.h4,
+            tpdp24h: t.price_change.h24,
+            tfr: t.funding.rate,
+            tfri: t.funding.interval_hours,
+            tfrt: t.funding.timestamp,
+            tnd: t.funding.next_funding_at,
+            toi: t.open_interest.usd,
+            toich1h: t.open_interest.change_1h,
+            toich4h: t.open_interest.change_4h,
+            toich24h: t.open_interest.change_24h,
+            toivr: t.open_interest.to_volume_ratio,
+            tbd: t.depth.bid_base,
+            tbdf: t.depth.bid_quote,
+            tad2p: t.depth.minus_2pct,
+            tbd2p: t.depth.plus_2pct,
+            tms: t.margin_supported,
+            tc: t.amm_chain,
+            tpa: t.amm_pool_address,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A wire object with every field set to a distinct value.
+    fn full_detail() -> PremiumDetail {
+        let mut wire = serde_json::Map::new();
+        let detail = serde_json::to_value(PremiumDetail::default()).expect("serializes");
+        for (i, (key, value)) in detail.as_object().expect("object").iter().enumerate() {
+            let filled = match value {
+                serde_json::Value::String(_) => serde_json::json!(format!("{key}-{i}")),
+                _ if [
+                    "d", "st", "tt", "pmd", "sfri", "sfrt", "snd", "tfri", "tfrt", "tnd",
+                ]
+                .contains(&key.as_str()) =>
+                {
+                    serde_json::json!(1000 + i)
+                }
+                _ if ["sms", "tms", "t"].contains(&key.as_str()) => serde_json::json!(i % 2 == 0),
+                _ if ["sc", "spa", "tc", "tpa"].contains(&key.as_str()) => {
+                    serde_json::json!(format!("{key}-{i}"))
+                }
+                _ => serde_json::json!(i as f64 + 0.5),
+            };
+            wire.insert(key.clone(), filled);
+        }
+        serde_json::from_value(serde_json::Value::Object(wire)).expect("valid detail")
+    }
+
+    #[test]
+    fn view_round_trips_every_wire_field() {
+        let detail = full_detail();
+        let wire = serde_json::to_value(&detail).expect("serializes");
+
+        let back = PremiumDetail::from(detail.view());
+
+        assert_eq!(serde_json::to_value(&back).expect("serializes"), wire);
+    }
+
+    #[test]
+    fn two_percent_depth_follows_the_wire_sign() {
+        let detail = PremiumDetail {
+            sad2p: Some(1.0),
+            sbd2p: Some(2.0),
+            tad2p: Some(3.0),
+            tbd2p: Some(4.0),
+            ..Default::default()
+        };
+
+        let view = detail.view();
+
+        assert_eq!(
+            (view.source.depth.minus_2pct, view.source.depth.plus_2pct),
+            (Some(1.0), Some(2.0))
+        );
+        assert_eq!(
+            (view.target.depth.minus_2pct, view.target.depth.plus_2pct),
+            (Some(3.0), Some(4.0))
+        );
+    }
+
+    #[test]
+    fn view_serde_uses_readable_names() {
+        let view = full_detail().view();
+
+        let json = serde_json::to_value(&view).expect("serializes");
+        assert_eq!(
+            json["source"]["exchange"],
+            serde_json::json!(view.source.exchange)
+        );
+        assert_eq!(json["target"]["depth"]["ask_base"], serde_json::Value::Null);
+        assert_eq!(
+            serde_json::from_value::<PremiumDetailView>(json).expect("deserializes"),
+            view
+        );
+    }
+}
@@ -0,0 +1,531 @@
+//! A consolidated top of book for one symbol across exchanges.
+//!
+//! A [`TickerView`] carries one exchange's best bid and ask and the order
+//! book depth within 2% of the price. A
+//! [`ConsolidatedQuote`](crate::quote::ConsolidatedQuote) puts the tickers
+//! of every exchange side by side in one currency and answers where to
+//! [`route`](crate::quote::ConsolidatedQuote::route) an order for the best
+//! price, how wide the cross-exchange spread is, and which venues have the
+//! most [depth](crate::quote::ConsolidatedQuote::depth_ranking).
+//!
+//! [`Client::consolidated_quote`](crate::Client::consolidated_quote) fetches
+//! one from every exchange listed by
+//! [`Ticker::exchanges`](crate::Ticker::exchanges), with prices converted
+//! server-side to the requested currency, and a
+//! [`QuotePoller`](crate::quote::QuotePoller) refreshes it on an interval.
+//! A ticker that still comes back in another currency is converted through
+//! a [`RateTable`](crate::fx::RateTable) at the `USD-KRW` rate, recorded on
+//! its [`VenueQuote::rate`](crate::quote::VenueQuote::rate).
+//!
+//! The same symbol is requested on every exchange: a `BTC-USDT` quote
+//! covers the exchanges listing `BTC-USDT`, not KRW markets such as
+//! `BTC-KRW` on upbit or bithumb, whatever the currency. Consolidate those
+//! with a separate `BTC-KRW` quote.
+//!
+//! ```no_run
+//! use datamaxi::quote::{OrderSide, QuotePoller};
+//! use datamaxi::{Client, TickerCurrency, TickerMarket};
+//! use std::time::Duration;
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut poller = QuotePoller::new(
+//!     Client::new("my_api_key"),
+//!     "BTC-USDT",
+//!     TickerMarket::Spot,
+//!     TickerCurrency::USD,
+//!     Duration::from_secs(5),
+//! );
+//! loop {
+//!     let quote = poller.next_update().await?;
+//!     if let (Some(buy), Some(sell)) = (quote.route(OrderSide::Buy), quote.route(OrderSide::Sell)) {
+//!         println!("buy on {} at {:?}, sell on {} at {:?}", buy.exchange, buy.ask, sell.exchange, sell.bid);
+//!     }
+//! }
+//! # }
+//! ```
+//!
+//! [`TickerView`]: crate::TickerView
+
+use crate::api::{Client, Error, Result};
+use crate::enums::{Currency, Market};
+use crate::exchange::Exchange;
+use crate::fx::{AppliedRate, RateTable, USD_KRW};
+use crate::generated::{
+    TickerCurrency, TickerExchangesMarket, TickerMarket, TickerOptions, TickerView,
+};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// The side of an order being routed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum OrderSide {
+    /// Buying: lifts asks.
+    Buy,
+    /// Selling: hits bids.
+    Sell,
+}
+
+/// One exchange's top of book.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VenueQuote {
+    /// The exchange.
+    pub exchange: Exchange,
+    /// Ticker time, UTC milliseconds.
+    pub timestamp: i64,
+    /// Last traded price.
+    pub price: Option<f64>,
+    /// Highest bid.
+    pub bid: Option<f64>,
+    /// Lowest ask.
+    pub ask: Option<f64>,
+    /// Depth within 2% below the price (the bid side).
+    pub bid_depth: Option<f64>,
+    /// Depth within 2% above the price (the ask side).
+    pub ask_depth: Option<f64>,
+    /// The rate the ticker was converted at client-side; `None` when it
+    /// came back in the quote's currency.
+    pub rate: Option<AppliedRate>,
+}
+
+impl VenueQuote {
+    /// Builds a venue quote from a ticker.
+    pub fn from_ticker(ticker: &TickerView) -> Self {
+        VenueQuote {
+            exchange: Exchange::new(&ticker.exchange),
+            timestamp: ticker.timestamp,
+            price: ticker.price,
+            bid: ticker.highest_bid,
+            ask: ticker.lowest_ask,
+            bid_depth: ticker.lower_depth,
+            ask_depth: ticker.upper_depth,
+            rate: None,
+        }
+    }
+
+    /// Midpoint of bid and ask.
+    pub fn mid(&self) -> Option<f64> {
+        Some((self.bid? + self.ask?) / 2.0)
+    }
+
+    /// This venue's own spread over its mid, basis points.
+    pub fn spread_bps(&self) -> Option<f64> {
+        let mid = self.mid().filter(|&mid| mid > 0.0)?;
+        Some((self.ask? - self.bid?) / mid * 10_000.0)
+    }
+
+    fn depth(&self, side: OrderSide) -> Option<f64> {
+        match side {
+            OrderSide::Buy => self.ask_depth,
+            OrderSide::Sell => self.bid_depth,
+        }
+    }
+}
+
+/// The tops of book of one symbol on every exchange, in one currency.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConsolidatedQuote {
+    /// The symbol, e.g. `BTC-USDT`.
+    pub symbol: String,
+    /// The currency of every price.
+    pub currency: Currency,
+    /// One quote per exchange, by exchange id.
+    pub venues: Vec<VenueQuote>,
+    /// Exchanges whose ticker came back in another currency with no rate
+    /// to convert it, left out.
+    pub excluded: Vec<Exchange>,
+}
+
+impl ConsolidatedQuote {
+    /// Consolidates tickers already in `currency`, keeping the latest per
+    /// exchange.
+    pub fn from_tickers(
+        symbol: impl Into<String>,
+        currency: Currency,
+        tickers: &[TickerView],
+    ) -> Self {
+        ConsolidatedQuote::from_venues(
+            symbol,
+            currency,
+            tickers.iter().map(VenueQuote::from_ticker),
+        )
+    }
+
+    fn from_venues(
+        symbol: impl Into<String>,
+        currency: Currency,
+        quotes: impl IntoIterator<Item = VenueQuote>,
+    ) -> Self {
+        let mut venues: Vec<VenueQuote> = Vec::new();
+        for quote in quotes {
+            match venues.iter_mut().find(|v| v.exchange == quote.exchange) {
+                Some(venue) if venue.timestamp < quote.timestamp => *venue = quote,
+                Some(_) => {}
+                None => venues.push(quote),
+            }
+        }
+        venues.sort_by(|a, b| a.exchange.cmp(&b.exchange));
+        ConsolidatedQuote {
+            symbol: symbol.into(),
+            currency,
+            venues,
+            excluded: Vec::new(),
+        }
+    }
+
+    /// The venue with the highest bid.
+    pub fn best_bid(&self) -> Option<&VenueQuote> {
+        self.venues
+            .iter()
+            .filter(|venue| venue.bid.is_some())
+            .max_by(|a, b| {
+                a.bid
+                    .unwrap_or_default()
+                    .total_cmp(&b.bid.unwrap_or_default())
+            })
+    }
+
+    /// The venue with the lowest ask.
+    pub fn best_ask(&self) -> Option<&VenueQuote> {
+        self.venues
+            .iter()
+            .filter(|venue| venue.ask.is_some())
+            .min_by(|a, b| {
+                a.ask
+                    .unwrap_or_default()
+                    .total_cmp(&b.ask.unwrap_or_default())
+            })
+    }
+
+    /// Where an order gets the best price: the best ask to buy, the best
+    /// bid to sell.
+    pub fn route(&self, side: OrderSide) -> Option<&VenueQuote> {
+        match side {
+            OrderSide::Buy => self.best_ask(),
+            OrderSide::Sell => self.best_bid(),
+        }
+    }
+
+    /// Best ask minus best bid across exchanges; negative when crossed.
+    pub fn spread(&self) -> Option<f64> {
+        Some(self.best_ask()?.ask? - self.best_bid()?.bid?)
+    }
+
+    /// [`spread`](Self::spread) over the consolidated mid, basis points.
+    pub fn spread_bps(&self) -> Option<f64> {
+        let (bid, ask) = (self.best_bid()?.bid?, self.best_ask()?.ask?);
+        let mid = (bid + ask) / 2.0;
+        (mid > 0.0).then(|| (ask - bid) / mid * 10_000.0)
+    }
+
+    /// Whether one exchange bids above another's ask.
+    pub fn is_crossed(&self) -> bool {
+        self.spread().is_some_and(|spread| spread < 0.0)
+    }
+
+    /// Venues by the depth an order of `side` can take, deepest first
+    /// (ask depth to buy, bid depth to sell); venues without depth are left
+    /// out.
+    pub fn depth_ranking(&self, side: OrderSide) -> Vec<&VenueQuote> {
+        let mut venues: Vec<&VenueQuote> = self
+            .venues
+            .iter()
+            .filter(|venue| venue.depth(side).is_some())
+            .collect();
+        venues.sort_by(|a, b| {
+            let depth = |venue: &VenueQuote| venue.depth(side).unwrap_or_default();
+            depth(b).total_cmp(&depth(a))
+        });
+        venues
+    }
+}
+
+fn exchanges_market(market: TickerMarket) -> Result<TickerExchangesMarket> {
+    TickerExchangesMarket::try_from(Market::from(market)).map_err(|market| Error::InvalidArgument {
+        argument: "market".to_string(),
+        reason: format!("no exchange list for the {market:?} market"),
+    })
+}
+
+/// Body of the async and blocking quote fetches; see `get_loop!` in [`crate::api`].
+/// `$exchanges` caches the exchange list between rounds.
+macro_rules! consolidate {
+    ($client:expr, $symbol:expr, $market:expr, $currency:expr, $rates:expr, $exchanges:expr $(, $aw:ident)?) => {{
+        let client = $client;
+        let ticker = client.ticker();
+        let rates: &mut RateTable = $rates;
+        let (symbol, market, currency): (&str, TickerMarket, TickerCurrency) =
+            ($symbol, $market, $currency);
+        let exchanges: &mut Option<Vec<String>> = $exchanges;
+        if exchanges.is_none() {
+            let listed = ticker.exchanges(exchanges_market(market)?)$(.$aw)?;
+            *exchanges = Some(listed?);
+        }
+        let wanted = Currency::from(currency);
+        let mut venues = Vec::new();
+        let mut refreshed = false;
+        let mut excluded = Vec::new();
+        for exchange in exchanges.iter().flatten() {
+            let response = ticker
+                .get(
+                    exchange.as_str(),
+                    symbol,
+                    market,
+                    TickerOptions::new().currency(currency),
+                )$(.$aw)?;
+            let response = match response {
+                Ok(response) => response,
+                // Exchanges not listing the symbol are left out.
+                Err(Error::NotFound { .. }) => continue,
+                Err(err) => return Err(err),
+            };
+            let received = response.currency_kind();
+            if received == wanted {
+                venues.push(VenueQuote::from_ticker(&response.data));
+                continue;
+            }
+            // KRW and USD are the only ticker currencies.
+            if !refreshed {
+                let refresh = client.refresh_rate(rates, USD_KRW)$(.$aw)?;
+                refresh?;
+                refreshed = true;
+            }
+            match rates.convert(response.data, &received, &wanted) {
+                Some(converted) => {
+                    let mut venue = VenueQuote::from_ticker(&converted.value);
+                    venue.rate = Some(converted.rate);
+                    venues.push(venue);
+                }
+                None => excluded.push(Exchange::new(exchange)),
+            }
+        }
+        let mut quote = ConsolidatedQuote::from_venues(symbol, wanted, venues);
+        quote.excluded = excluded;
+        Ok(quote)
+    }};
+}
+
+impl Client {
+    /// Fetches the ticker of `symbol` (e.g. `BTC-USDT`) on every exchange
+    /// of `market`, priced in `currency` (one request per exchange, plus
+    /// the exchange list). Tickers in another currency are converted at
+    /// the `USD-KRW` rate in `rates`, refreshed when one does (see
+    /// [`Client::refresh_rate`](crate::Client::refresh_rate)).
+    pub async fn consolidated_quote(
+        &self,
+        symbol: &str,
+        market: TickerMarket,
+        currency: TickerCurrency,
+        rates: &mut RateTable,
+    ) -> Result<ConsolidatedQuote> {
+        consolidate!(self, symbol, market, currency, rates, &mut None, await)
+    }
+}
+
+#[cfg(feature = "sync")]
+impl crate::api::sync::Client {
+    /// Blocking mirror of the async `Client::consolidated_quote`.
+    pub fn consolidated_quote(
+        &self,
+        symbol: &str,
+        market: TickerMarket,
+        currency: TickerCurrency,
+        rates: &mut RateTable,
+    ) -> Result<ConsolidatedQuote> {
+        consolidate!(self, symbol, market, currency, rates, &mut None)
+    }
+}
+
+/// Refreshes a [`ConsolidatedQuote`] at a fixed interval.
+///
+/// The exchange list is fetched on the first round only, and the `USD-KRW`
+/// rate at most once per [`RateTable`] TTL.
+pub struct QuotePoller {
+    client: Client,
+    symbol: String,
+    market: TickerMarket,
+    currency: TickerCurrency,
+    interval: Duration,
+    rates: RateTable,
+    exchanges: Option<Vec<String>>,
+    latest: Option<ConsolidatedQuote>,
+}
+
+impl QuotePoller {
+    /// Polls `symbol` through `client` every `interval`.
+    pub fn new(
+        client: Client,
+        symbol: impl Into<String>,
+        market: TickerMarket,
+        currency: TickerCurrency,
+        interval: Duration,
+    ) -> Self {
+        QuotePoller {
+            client,
+            symbol: symbol.into(),
+            market,
+            currency,
+            interval,
+            rates: RateTable::new(),
+            exchanges: None,
+            latest: None,
+        }
+    }
+
+    /// Waits for the interval (except on the first call) and fetches a
+    /// fresh quote.
+    pub async fn next_update(&mut self) -> Result<&ConsolidatedQuote> {
+        if self.latest.is_some() {
+            tokio::time::sleep(self.interval).await;
+        }
+        let quote: Result<ConsolidatedQuote> = consolidate!(
+            &self.client,
+            &self.symbol,
+            self.market,
+            self.currency,
+            &mut self.rates,
+            &mut self.exchanges,
+            await
+        );
+        Ok(self.latest.insert(quote?))
+    }
+
+    /// The last quote fetched.
+    pub fn latest(&self) -> Option<&ConsolidatedQuote> {
+        self.latest.as_ref()
+    }
+}
+
+/// Blocking mirror of the poller (feature `sync`).
+#[cfg(feature = "sync")]
+#[cfg_attr(docsrs, doc(cfg(feature = "sync")))]
+pub mod sync {
+    use super::{exchanges_market, ConsolidatedQuote, VenueQuote};
+    use crate::api::sync::Client;
+    use crate::api::{Error, Result};
+    use crate::enums::Currency;
+    use crate::exchange::Exchange;
+    use crate::fx::{RateTable, USD_KRW};
+    use crate::generated::{TickerCurrency, TickerMarket, TickerOptions};
+    use std::time::Duration;
+
+    /// Blocking mirror of [`QuotePoller`](super::QuotePoller).
+    pub struct QuotePoller {
+        client: Client,
+        symbol: String,
+        market: TickerMarket,
+        currency: TickerCurrency,
+        interval: Duration,
+        rates: RateTable,
+        exchanges: Option<Vec<String>>,
+        latest: Option<ConsolidatedQuote>,
+    }
+
+    impl QuotePoller {
+        /// Polls `symbol` through `client` every `interval`.
+        pub fn new(
+            client: Client,
+            symbol: impl Into<String>,
+            market: TickerMarket,
+            currency: TickerCurrency,
+            interval: Duration,
+        ) -> Self {
+            QuotePoller {
+                client,
+                symbol: symbol.into(),
+                market,
+                currency,
+                interval,
+                rates: RateTable::new(),
+                exchanges: None,
+                latest: None,
+            }
+        }
+
+        /// Blocking mirror of
+        /// [`QuotePoller::next_update`](super::QuotePoller::next_update).
+        pub fn next_update(&mut self) -> Result<&ConsolidatedQuote> {
+            if self.latest.is_some() {
+                std::thread::sleep(self.interval);
+            }
+            let quote: Result<ConsolidatedQuote> = consolidate!(
+                &self.client,
+                &self.symbol,
+                self.market,
+                self.currency,
+                &mut self.rates,
+                &mut self.exchanges
+            );
+            Ok(self.latest.insert(quote?))
+        }
+
+        /// The last quote fetched.
+        pub fn latest(&self) -> Option<&ConsolidatedQuote> {
+            self.latest.as_ref()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ticker(exchange: &str, bid: f64, ask: f64, lower: f64, upper: f64) -> TickerView {
+        TickerView {
+            exchange: exchange.into(),
+            highest_bid: Some(bid),
+            lowest_ask: Some(ask),
+            lower_depth: Some(lower),
+            upper_depth: Some(upper),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn routes_to_the_best_price_on_each_side() {
+        let quote = ConsolidatedQuote::from_tickers(
+            "BTC-USDT",
+            Currency::USD,
+            &[
+                ticker("okx", 99.0, 101.0, 5.0, 50.0),
+                ticker("Binance", 99.5, 100.5, 30.0, 20.0),
+                ticker("bybit", 98.0, 100.2, 10.0, 40.0),
+            ],
+        );
+
+        assert_eq!(
+            quote.route(OrderSide::Sell).unwrap().exchange.as_str(),
+            "binance"
+        );
+        assert_eq!(
+            quote.route(OrderSide::Buy).unwrap().exchange.as_str(),
+            "bybit"
+        );
+        assert!((quote.spread().unwrap() - 0.7).abs() < 1e-9);
+        assert!(!quote.is_crossed());
+
+        let deepest: Vec<_> = quote
+            .depth_ranking(OrderSide::Buy)
+            .iter()
+            .map(|venue| venue.exchange.as_str())
+            .collect();
+        assert_eq!(deepest, ["okx", "bybit", "binance"]);
+    }
+
+    #[test]
+    fn crossed_books_and_latest_ticker_per_exchange() {
+        let mut stale = ticker("okx", 90.0, 91.0, 0.0, 0.0);
+        stale.timestamp = 1;
+        let mut fresh = ticker("okx", 102.0, 103.0, 0.0, 0.0);
+        fresh.timestamp = 2;
+        let quote = ConsolidatedQuote::from_tickers(
+            "BTC-USDT",
+            Currency::USD,
+            &[fresh, stale, ticker("binance", 99.0, 100.0, 0.0, 0.0)],
+        );
+
+        assert_eq!(quote.venues.len(), 2);
+        assert!(quote.is_crossed());
+        assert!((quote.spread_bps().unwrap() + 2.0 / 101.0 * 10_000.0).abs() < 1e-9);
+        assert_eq!(quote.venues[0].spread_bps(), Some(1.0 / 99.5 * 10_000.0));
+    }
+}
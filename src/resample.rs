@@ -0,0 +1,385 @@
+//! Resampling of time series to coarser or custom buckets.
+//!
+//! The API serves candles only at the fixed [`CexCandleInterval`] set, index
+//! prices at [`IndexPriceInterval`] and liquidation history at
+//! [`LiquidationSymbolHistoryInterval`]. A [`Resampler`](crate::resample::Resampler)
+//! aggregates any of those series ([`CexCandleView`], [`IndexPriceView`],
+//! [`LiquidationSymbolHistoryBucket`]) into arbitrary fixed-length buckets
+//! (2h, 3d, …), Monday-aligned weeks or calendar months, optionally aligned
+//! to a fixed UTC offset such as KST for [`CexCandleCurrency::KRW`] markets.
+//!
+//! ```
+//! use datamaxi::resample::Resampler;
+//! use datamaxi::{CexCandleCurrency, CexCandleView};
+//! use std::time::Duration;
+//!
+//! let hourly: Vec<CexCandleView> = Vec::new(); // e.g. `CexCandle::get(..).data`
+//! let two_hour = Resampler::every(Duration::from_secs(2 * 3600)).resample(&hourly);
+//! let krw_daily = Resampler::every(Duration::from_secs(24 * 3600))
+//!     .aligned_to(CexCandleCurrency::KRW)
+//!     .resample(&hourly);
+//! let monthly = Resampler::monthly().resample(&hourly);
+//! ```
+//!
+//! Fixed offsets only: zones with daylight-saving transitions are not
+//! modeled (neither KST nor UTC has any).
+//!
+//! [`CexCandleInterval`]: crate::CexCandleInterval
+//! [`IndexPriceInterval`]: crate::IndexPriceInterval
+//! [`LiquidationSymbolHistoryInterval`]: crate::LiquidationSymbolHistoryInterval
+
+use crate::generated::{
+    CexCandleCurrency, CexCandleView, IndexPriceView, LiquidationSymbolHistoryBucket,
+};
+use std::time::Duration;
+
+const MILLIS_PER_DAY: i64 = 86_400_000;
+
+/// Korea Standard Time (UTC+09:00), in seconds east of UTC.
+pub const KST_UTC_OFFSET: i32 = 9 * 3600;
+
+/// A series item that can be merged into a coarser bucket.
+///
+/// Implemented for [`CexCandleView`] (OHLC, summed volume),
+/// [`IndexPriceView`] (last price, summed volume) and
+/// [`LiquidationSymbolHistoryBucket`] (summed USD, last known price).
+pub trait Resample: Clone {
+    /// The item's timestamp in UTC milliseconds.
+    fn timestamp(&self) -> i64;
+
+    /// Starts a bucket at `start` from its first item.
+    fn open_bucket(&self, start: i64) -> Self;
+
+    /// Folds `next`, a later item in the same bucket, into `self`.
+    fn merge(&mut self, next: &Self);
+}
+
+impl Resample for CexCandleView {
+    fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+
+    fn open_bucket(&self, start: i64) -> Self {
+        CexCandleView {
+            timestamp: start,
+            ..self.clone()
+        }
+    }
+
+    fn merge(&mut self, next: &Self) {
+        self.high = self.high.max(next.high);
+        self.low = self.low.min(next.low);
+        self.close = next.close;
+        self.volume += next.volume;
+    }
+}
+
+impl Resample for IndexPriceView {
+    fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+
+    fn open_bucket(&self, start: i64) -> Self {
+        IndexPriceView {
+            timestamp: start,
+            ..self.clone()
+        }
+    }
+
+    fn merge(&mut self, next: &Self) {
+        self.price = next.price;
+        self.volume += next.volume;
+    }
+}
+
+impl Resample for LiquidationSymbolHistoryBucket {
+    fn timestamp(&self) -> i64 {
+        self.ts
+    }
+
+    fn open_bucket(&self, start: i64) -> Self {
+        LiquidationSymbolHistoryBucket {
+            ts: start,
+            ..self.clone()
+        }
+    }
+
+    fn merge(&mut self, next: &Self) {
+        self.long_usd += next.long_usd;
+        self.short_usd += next.short_usd;
+        self.total_usd += next.total_usd;
+        if next.price.is_some() {
+            self.price = next.price;
+        }
+    }
+}
+
+/// How bucket boundaries are laid out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bucket {
+    /// Fixed-length buckets, aligned to the Unix epoch in the resampler's
+    /// UTC offset.
+    Fixed(Duration),
+    /// Calendar weeks starting Monday 00:00 local time.
+    Weekly,
+    /// Calendar months starting on the 1st at 00:00 local time.
+    Monthly,
+}
+
+/// Aggregates a series into [`Bucket`]s, optionally aligned to a fixed UTC
+/// offset.
+///
+/// Input need not be sorted; output is one item per non-empty bucket, in
+/// ascending order, stamped with the bucket's start (UTC milliseconds).
+/// Empty buckets are skipped rather than synthesized, and the last bucket
+/// may be partial.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Resampler {
+    bucket: Bucket,
+    utc_offset: i32,
+}
+
+impl Resampler {
+    /// Fixed-length buckets of `length` (e.g. 2h, 3d). A zero length is
+    /// treated as one millisecond.
+    pub fn every(length: Duration) -> Self {
+        Resampler::new(Bucket::Fixed(length))
+    }
+
+    /// Monday-aligned calendar weeks.
+    pub fn weekly() -> Self {
+        Resampler::new(Bucket::Weekly)
+    }
+
+    /// Calendar months.
+    pub fn monthly() -> Self {
+        Resampler::new(Bucket::Monthly)
+    }
+
+    /// Buckets laid out per `bucket`, in UTC.
+    pub fn new(bucket: Bucket) -> Self {
+        Resampler {
+            bucket,
+            utc_offset: 0,
+        }
+    }
+
+    /// Aligns bucket boundaries to local midnight at `seconds` east of UTC
+    /// (e.g. [`KST_UTC_OFFSET`]).
+    pub fn utc_offset(mut self, seconds: i32) -> Self {
+        self.utc_offset = seconds;
+        self
+    }
+
+    /// Aligns bucket boundaries to the trading session of markets quoted in
+    /// `currency`: KST for [`CexCandleCurrency::KRW`], UTC otherwise.
+    pub fn aligned_to(self, currency: CexCandleCurrency) -> Self {
+        match currency {
+            CexCandleCurrency::KRW => self.utc_offset(KST_UTC_OFFSET),
+            _ => self.utc_offset(0),
+        }
+    }
+
+    /// The start (UTC milliseconds) of the bucket containing `timestamp`.
+    pub fn bucket_start(&self, timestamp: i64) -> i64 {
+        let offset = i64::from(self.utc_offset) * 1000;
+        let local = timestamp + offset;
+        let start = match self.bucket {
+            Bucket::Fixed(length) => {
+                let length = i64::try_from(length.as_millis()).unwrap_or(i64::MAX).max(1);
+                local - local.rem_euclid(length)
+            }
+            Bucket::Weekly => {
+                let days = local.div_euclid(MILLIS_PER_DAY);
+                // 1970-01-01 was a Thursday: shift so Monday is weekday 0.
+                let weekday = (days + 3).rem_euclid(7);
+                (days - weekday) * MILLIS_PER_DAY
+            }
+            Bucket::Monthly => {
+                let (year, month, _) = civil_from_days(local.div_euclid(MILLIS_PER_DAY));
+                days_from_civil(year, month, 1) * MILLIS_PER_DAY
+            }
+        };
+        start - offset
+    }
+
+    /// Resamples `items` into one aggregated item per bucket.
+    pub fn resample<T: Resample>(&self, items: &[T]) -> Vec<T> {
+        let mut sorted: Vec<&T> = items.iter().collect();
+        sorted.sort_by_key(|item| item.timestamp());
+
+        let mut out: Vec<(i64, T)> = Vec::new();
+        for item in sorted {
+            let start = self.bucket_start(item.timestamp());
+            match out.last_mut() {
+                Some((current, bucket)) if *current == start => bucket.merge(item),
+                _ => out.push((start, item.open_bucket(start))),
+            }
+        }
+        out.into_iter().map(|(_, bucket)| bucket).collect()
+    }
+}
+
+/// Civil `(year, month, day)` for a count of days since 1970-01-01, per
+/// Howard Hinnant's `civil_from_days` algorithm (proleptic Gregorian).
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Days since 1970-01-01 for a civil date; the inverse of
+/// [`civil_from_days`].
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = year - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let month = i64::from(month);
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: i64 = 3_600_000;
+
+    fn candle(timestamp: i64, open: f64, high: f64, low: f64, close: f64) -> CexCandleView {
+        CexCandleView {
+            close,
+            timestamp,
+            high,
+            low,
+            open,
+            volume: 1.0,
+        }
+    }
+
+    #[test]
+    fn civil_dates_round_trip() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        // 2024-02-29, a leap day.
+        assert_eq!(civil_from_days(19_782), (2024, 2, 29));
+        for days in [-800_000, -1, 0, 59, 19_782, 2_000_000] {
+            let (y, m, d) = civil_from_days(days);
+            assert_eq!(days_from_civil(y, m, d), days);
+        }
+    }
+
+    #[test]
+    fn candles_aggregate_ohlcv() {
+        let hourly = [
+            candle(HOUR, 2.0, 5.0, 1.5, 4.0),
+            candle(0, 1.0, 3.0, 0.5, 2.0),
+            candle(2 * HOUR, 4.0, 4.5, 3.0, 3.5),
+        ];
+
+        let bars = Resampler::every(Duration::from_secs(7200)).resample(&hourly);
+
+        assert_eq!(bars.len(), 2);
+        let first = &bars[0];
+        assert_eq!(first.timestamp, 0);
+        assert_eq!(
+            (first.open, first.high, first.low, first.close, first.volume),
+            (1.0, 5.0, 0.5, 4.0, 2.0)
+        );
+        assert_eq!(bars[1].timestamp, 2 * HOUR);
+    }
+
+    #[test]
+    fn kst_daily_buckets_start_at_15_utc() {
+        let resampler =
+            Resampler::every(Duration::from_secs(86_400)).aligned_to(CexCandleCurrency::KRW);
+
+        // 2024-01-01 14:00 UTC is 23:00 KST, in the session that opened at
+        // 2024-01-01 00:00 KST (2023-12-31 15:00 UTC).
+        let jan_1_utc = days_from_civil(2024, 1, 1) * MILLIS_PER_DAY;
+        assert_eq!(
+            resampler.bucket_start(jan_1_utc + 14 * HOUR),
+            jan_1_utc - 9 * HOUR
+        );
+        assert_eq!(
+            resampler.bucket_start(jan_1_utc + 15 * HOUR),
+            jan_1_utc + 15 * HOUR
+        );
+    }
+
+    #[test]
+    fn weekly_buckets_start_on_monday() {
+        // 2024-01-03 is a Wednesday; its week starts Monday 2024-01-01.
+        let wednesday = days_from_civil(2024, 1, 3) * MILLIS_PER_DAY + 5 * HOUR;
+        assert_eq!(
+            Resampler::weekly().bucket_start(wednesday),
+            days_from_civil(2024, 1, 1) * MILLIS_PER_DAY
+        );
+    }
+
+    #[test]
+    fn monthly_buckets_follow_the_calendar() {
+        let resampler = Resampler::monthly();
+        let feb_29 = days_from_civil(2024, 2, 29) * MILLIS_PER_DAY + HOUR;
+        assert_eq!(
+            resampler.bucket_start(feb_29),
+            days_from_civil(2024, 2, 1) * MILLIS_PER_DAY
+        );
+
+        let prices = [
+            IndexPriceView {
+                price: 1.0,
+                timestamp: days_from_civil(2024, 1, 31) * MILLIS_PER_DAY,
+                volume: 1.0,
+            },
+            IndexPriceView {
+                price: 2.0,
+                timestamp: feb_29,
+                volume: 2.0,
+            },
+            IndexPriceView {
+                price: 3.0,
+                timestamp: days_from_civil(2024, 2, 1) * MILLIS_PER_DAY,
+                volume: 3.0,
+            },
+        ];
+        let bars = resampler.resample(&prices);
+        assert_eq!(bars.len(), 2);
+        assert_eq!((bars[1].price, bars[1].volume), (2.0, 5.0));
+    }
+
+    #[test]
+    fn liquidation_buckets_sum_usd_and_keep_last_price() {
+        let buckets = [
+            LiquidationSymbolHistoryBucket {
+                long_usd: 1.0,
+                price: Some(10.0),
+                short_usd: 2.0,
+                total_usd: 3.0,
+                ts: 0,
+            },
+            LiquidationSymbolHistoryBucket {
+                long_usd: 4.0,
+                price: None,
+                short_usd: 5.0,
+                total_usd: 9.0,
+                ts: HOUR,
+            },
+        ];
+
+        let merged = Resampler::every(Duration::from_secs(4 * 3600)).resample(&buckets);
+
+        assert_eq!(merged.len(), 1);
+        assert_eq!(
+            (merged[0].long_usd, merged[0].short_usd, merged[0].total_usd),
+            (5.0, 7.0, 12.0)
+        );
+        assert_eq!(merged[0].price, Some(10.0));
+    }
+}
@@ -0,0 +1,770 @@
+//! Columnar view of the response models, shared by the dataframe and file
+//! export backends.
+//!
+//! Every row-shaped model (the `*View`, `*Entry`, `*Bucket`, … structs that
+//! responses carry in their `data` lists, plus the flat single-object
+//! responses) implements [`Tabular`](crate::table::Tabular): a fixed
+//! [`schema`](crate::table::Tabular::schema) and a conversion of a slice of
+//! rows into typed [`Column`](crate::table::Column)s. The `arrow` and
+//! `polars` features build on it.
+//!
+//! Schema rules, identical for every model:
+//!
+//! - one column per field, named after the Rust field (not the wire name),
+//!   in declaration order;
+//! - nested structs are flattened, joining the path with `_`
+//!   (`PremiumView::detail.sp` becomes `detail_sp`);
+//! - `i64` fields carrying UTC-millisecond instants (`timestamp`, `ts`,
+//!   `*_at`, and the premium `d`/`st`/`tt`/`sfrt`/`snd`/`tfrt`/`tnd`) are
+//!   [`ColumnType::Timestamp`](crate::table::ColumnType::Timestamp);
+//! - `Option<_>` fields are nullable, everything else is not;
+//! - `Vec<String>` is a list of strings, and raw `serde_json::Value` payloads
+//!   are their JSON text (`null` maps to a null cell).
+
+use crate::generated::*;
+use crate::models::{OpenInterestHistoryPoint, OpenInterestOverviewExchange};
+
+/// The logical type of a [`Column`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnType {
+    /// UTC milliseconds since the Unix epoch, stored as
+    /// [`ColumnValues::Int64`].
+    Timestamp,
+    /// 64-bit signed integer.
+    Int64,
+    /// 64-bit float.
+    Float64,
+    /// Boolean.
+    Boolean,
+    /// UTF-8 string.
+    Utf8,
+    /// List of UTF-8 strings.
+    Utf8List,
+}
+
+/// Name, type and nullability of one column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnSpec {
+    /// Column name, see the [module docs](self) for the naming rules.
+    pub name: String,
+    /// Logical type.
+    pub column_type: ColumnType,
+    /// Whether cells may be null.
+    pub nullable: bool,
+}
+
+/// The cells of one column, one per row. Non-nullable columns never hold
+/// `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnValues {
+    /// [`ColumnType::Int64`] and [`ColumnType::Timestamp`] cells.
+    Int64(Vec<Option<i64>>),
+    /// [`ColumnType::Float64`] cells.
+    Float64(Vec<Option<f64>>),
+    /// [`ColumnType::Boolean`] cells.
+    Boolean(Vec<Option<bool>>),
+    /// [`ColumnType::Utf8`] cells.
+    Utf8(Vec<Option<String>>),
+    /// [`ColumnType::Utf8List`] cells.
+    Utf8List(Vec<Option<Vec<String>>>),
+}
+
+/// One typed column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    /// Name, type and nullability.
+    pub spec: ColumnSpec,
+    /// The cells, in row order.
+    pub values: ColumnValues,
+}
+
+/// A model that converts to a fixed set of typed columns.
+pub trait Tabular: Sized {
+    /// The columns [`columns`](Self::columns) produces, in order.
+    fn schema() -> Vec<ColumnSpec>;
+
+    /// Converts `rows` into one [`Column`] per [`schema`](Self::schema)
+    /// entry.
+    fn columns(rows: &[Self]) -> Vec<Column>;
+}
+
+/// A field type with a column representation.
+trait Cell {
+    const TYPE: ColumnType;
+    const NULLABLE: bool;
+
+    fn values<'a>(cells: impl Iterator<Item = &'a Self>) -> ColumnValues
+    where
+        Self: 'a;
+}
+
+macro_rules! impl_cell {
+    ($ty:ty, $column_type:ident, $variant:ident, |$v:ident| $some:expr) => {
+        impl Cell for $ty {
+            const TYPE: ColumnType = ColumnType::$column_type;
+            const NULLABLE: bool = false;
+
+            fn values<'a>(cells: impl Iterator<Item = &'a Self>) -> ColumnValues {
+                ColumnValues::$variant(cells.map(|$v| Some($some)).collect())
+            }
+        }
+
+        impl Cell for Option<$ty> {
+            const TYPE: ColumnType = ColumnType::$column_type;
+            const NULLABLE: bool = true;
+
+            fn values<'a>(cells: impl Iterator<Item = &'a Self>) -> ColumnValues {
+                ColumnValues::$variant(cells.map(|cell| cell.as_ref().map(|$v| $some)).collect())
+            }
+        }
+    };
+}
+
+impl_cell!(i64, Int64, Int64, |v| *v);
+impl_cell!(f64, Float64, Float64, |v| *v);
+impl_cell!(bool, Boolean, Boolean, |v| *v);
+impl_cell!(String, Utf8, Utf8, |v| v.clone());
+impl_cell!(Vec<String>, Utf8List, Utf8List, |v| v.clone());
+
+impl Cell for serde_json::Value {
+    const TYPE: ColumnType = ColumnType::Utf8;
+    const NULLABLE: bool = true;
+
+    fn values<'a>(cells: impl Iterator<Item = &'a Self>) -> ColumnValues {
+        ColumnValues::Utf8(
+            cells
+                .map(|cell| (!cell.is_null()).then(|| cell.to_string()))
+                .collect(),
+        )
+    }
+}
+
+fn spec<R, T: Cell>(path: &[&str], timestamp: bool, _field: fn(&R) -> &T) -> ColumnSpec {
+    debug_assert!(!timestamp || T::TYPE == ColumnType::Int64);
+    ColumnSpec {
+        name: path.join("_"),
+        column_type: if timestamp {
+            ColumnType::Timestamp
+        } else {
+            T::TYPE
+        },
+        nullable: T::NULLABLE,
+    }
+}
+
+fn column<R, T: Cell>(path: &[&str], timestamp: bool, rows: &[R], field: fn(&R) -> &T) -> Column {
+    Column {
+        spec: spec(path, timestamp, field),
+        values: T::values(rows.iter().map(field)),
+    }
+}
+
+/// Implements [`Tabular`] from a field list. A field is a (possibly nested)
+/// path, optionally followed by `: timestamp`.
+macro_rules! impl_tabular {
+    (@timestamp timestamp) => { true };
+    ($($row:ident { $($($path:ident).+ $(: $kind:ident)?),* $(,)? })*) => {$(
+        impl Tabular for $row {
+            fn schema() -> Vec<ColumnSpec> {
+                vec![$(spec(
+                    &[$(stringify!($path)),+],
+                    false $(|| impl_tabular!(@timestamp $kind))?,
+                    |row: &$row| &row.$($path).+,
+                )),*]
+            }
+
+            fn columns(rows: &[Self]) -> Vec<Column> {
+                vec![$(column(
+                    &[$(stringify!($path)),+],
+                    false $(|| impl_tabular!(@timestamp $kind))?,
+                    rows,
+                    |row: &$row| &row.$($path).+,
+                )),*]
+            }
+        }
+    )*};
+}
+
+impl_tabular! {
+    OpenInterestHistoryPoint {
+        exchange,
+        timestamp: timestamp,
+        raw,
+    }
+    OpenInterestOverviewExchange {
+        exchange,
+        open_interest_usd,
+        raw,
+    }
+    CexAnnouncementsView {
+        category,
+        timestamp: timestamp,
+        exchange,
+        summary,
+        title,
+        url,
+    }
+    CexCandleSymbolsView {
+        base,
+        exchange,
+        id,
+        market,
+        quote,
+        symbol,
+    }
+    CexCandleView {
+        close,
+        timestamp: timestamp,
+        high,
+        low,
+        open,
+        volume,
+    }
+    CexFeesView {
+        base,
+        exchange,
+        futures_maker_fee,
+        futures_taker_fee,
+        quote,
+        spot_maker_fee,
+        spot_take_fee,
+        symbol,
+    }
+    CexSymbolCautionsView {
+        base,
+        caution_level,
+        exchange,
+        end_at: timestamp,
+        market,
+        quote,
+        reasons,
+    }
+    CexSymbolDelistingsView {
+        base,
+        delisting_at: timestamp,
+        exchange,
+        listed_at: timestamp,
+        market,
+        quote,
+        status,
+    }
+    CexSymbolLiquidationView {
+        base,
+        exchange,
+        event_count,
+        long_volume,
+        long_volume_usd,
+        market,
+        quote,
+        short_volume,
+        short_volume_usd,
+        total_volume,
+        total_volume_usd,
+    }
+    CexSymbolMetadataView {
+        base,
+        caution_end_at: timestamp,
+        caution_level,
+        caution_reasons,
+        delisting_at: timestamp,
+        exchange,
+        listed_at: timestamp,
+        market,
+        quote,
+        status,
+        tags,
+    }
+    CexSymbolOiStatsView {
+        base,
+        change_1h,
+        change_24h,
+        change_4h,
+        exchange,
+        market,
+        oi_to_vol_ratio,
+        open_interest,
+        open_interest_usd,
+        quote,
+        token_id,
+        ts: timestamp,
+        volume_24h_usd,
+    }
+    CexSymbolOiView {
+        base,
+        exchange,
+        market,
+        open_interest,
+        open_interest_usd,
+        quote,
+        ts: timestamp,
+    }
+    CexSymbolTagsView {
+        base,
+        confidence,
+        exchange,
+        market,
+        quote,
+        source,
+        tag,
+    }
+    CexSymbolVolumeView {
+        base,
+        exchange,
+        market,
+        quote,
+        quote_volume,
+        ts: timestamp,
+        volume,
+    }
+    CexTokenUpdatesView {
+        base,
+        timestamp: timestamp,
+        exchange,
+        market,
+        quote,
+        update_type,
+    }
+    ForexResponse {
+        timestamp: timestamp,
+        rate,
+        symbol,
+    }
+    FundingRateHistoryView {
+        timestamp: timestamp,
+        funding_rate,
+    }
+    FundingRateLatestResponse {
+        base,
+        timestamp: timestamp,
+        exchange,
+        funding_rate,
+        interval_hours,
+        token_id,
+        quote,
+        symbol,
+    }
+    FundingRateSymbolsView {
+        base,
+        exchange,
+        id,
+        market,
+        quote,
+        symbol,
+    }
+    IndexPriceView {
+        price,
+        timestamp: timestamp,
+        volume,
+    }
+    LiquidationEntry {
+        base,
+        exchange,
+        price,
+        price_usd,
+        quote,
+        side,
+        symbol,
+        timestamp: timestamp,
+        token_id,
+        volume,
+        volume_usd,
+    }
+    LiquidationFeedEntry {
+        base,
+        exchange,
+        price,
+        price_usd,
+        quote,
+        side,
+        symbol,
+        timestamp: timestamp,
+        token_id,
+        volume,
+        volume_usd,
+    }
+    LiquidationHeatmapCell {
+        base,
+        exchange,
+        long_usd,
+        short_usd,
+        token_id,
+        total_usd,
+    }
+    LiquidationHeatmapExchangesummary {
+        exchange,
+        long_usd,
+        short_usd,
+        total_usd,
+    }
+    LiquidationHeatmapTokensummary {
+        base,
+        long_usd,
+        name,
+        short_usd,
+        symbol,
+        token_id,
+        total_usd,
+    }
+    LiquidationMapBucket {
+        l100x_usd,
+        l10x_usd,
+        l25x_usd,
+        l50x_usd,
+        price,
+        side,
+        total_usd,
+    }
+    LiquidationMapTierassumption {
+        leverage,
+        share,
+    }
+    LiquidationStatsBiggest {
+        base,
+        exchange,
+        quote,
+        volume_usd,
+    }
+    LiquidationSymbolHistoryBucket {
+        long_usd,
+        price,
+        short_usd,
+        total_usd,
+        ts: timestamp,
+    }
+    ListingsHistoricalView {
+        announced_at: timestamp,
+        base,
+        deposit_at: timestamp,
+        exchange,
+        network,
+        trade_at: timestamp,
+        url,
+    }
+    MarginBorrowResponse {
+        cross,
+        isolated,
+    }
+    NaverTrendView {
+        timestamp: timestamp,
+        value,
+    }
+    OpenInterestListEntry {
+        base,
+        exchange,
+        open_interest,
+        open_interest_usd,
+        quote,
+        symbol,
+        timestamp: timestamp,
+        token_id,
+    }
+    OpenInterestOverviewView {
+        exchanges,
+        id,
+        token.cmc_id,
+        token.icon,
+        token.id,
+        token.name,
+        token.symbol,
+    }
+    OpenInterestResponse {
+        base,
+        exchange,
+        open_interest,
+        open_interest_usd,
+        quote,
+        symbol,
+        timestamp: timestamp,
+        token_id,
+    }
+    OpenInterestSummaryExchangesummary {
+        exchange,
+        open_interest_usd,
+        tokens,
+    }
+    OpenInterestSummaryTokensummary {
+        base,
+        icon,
+        name,
+        open_interest_usd,
+        symbol,
+        token_id,
+        venues,
+    }
+    PremiumDetail {
+        bid,
+        d: timestamp,
+        fg,
+        nfr,
+        pdp,
+        pdp15m,
+        pdp1h,
+        pdp24h,
+        pdp30m,
+        pdp4h,
+        pdp5m,
+        pmd,
+        sad,
+        sad2p,
+        sadf,
+        sb,
+        sbd2p,
+        sc,
+        se,
+        sfr,
+        sfri,
+        sfrt: timestamp,
+        shb,
+        sla,
+        sm,
+        sms,
+        snd: timestamp,
+        soi,
+        soich1h,
+        soich24h,
+        soich4h,
+        soivr,
+        sp,
+        spa,
+        spdp15m,
+        spdp1h,
+        spdp24h,
+        spdp30m,
+        spdp4h,
+        spdp5m,
+        sq,
+        st: timestamp,
+        sv,
+        t,
+        tad2p,
+        tb,
+        tbd,
+        tbd2p,
+        tbdf,
+        tc,
+        te,
+        tfr,
+        tfri,
+        tfrt: timestamp,
+        thb,
+        tla,
+        tm,
+        tms,
+        tnd: timestamp,
+        toi,
+        toich1h,
+        toich24h,
+        toich4h,
+        toivr,
+        tp,
+        tpa,
+        tpdp15m,
+        tpdp1h,
+        tpdp24h,
+        tpdp30m,
+        tpdp4h,
+        tpdp5m,
+        tq,
+        tt: timestamp,
+        tv,
+    }
+    PremiumView {
+        detail.bid,
+        detail.d: timestamp,
+        detail.fg,
+        detail.nfr,
+        detail.pdp,
+        detail.pdp15m,
+        detail.pdp1h,
+        detail.pdp24h,
+        detail.pdp30m,
+        detail.pdp4h,
+        detail.pdp5m,
+        detail.pmd,
+        detail.sad,
+        detail.sad2p,
+        detail.sadf,
+        detail.sb,
+        detail.sbd2p,
+        detail.sc,
+        detail.se,
+        detail.sfr,
+        detail.sfri,
+        detail.sfrt: timestamp,
+        detail.shb,
+        detail.sla,
+        detail.sm,
+        detail.sms,
+        detail.snd: timestamp,
+        detail.soi,
+        detail.soich1h,
+        detail.soich24h,
+        detail.soich4h,
+        detail.soivr,
+        detail.sp,
+        detail.spa,
+        detail.spdp15m,
+        detail.spdp1h,
+        detail.spdp24h,
+        detail.spdp30m,
+        detail.spdp4h,
+        detail.spdp5m,
+        detail.sq,
+        detail.st: timestamp,
+        detail.sv,
+        detail.t,
+        detail.tad2p,
+        detail.tb,
+        detail.tbd,
+        detail.tbd2p,
+        detail.tbdf,
+        detail.tc,
+        detail.te,
+        detail.tfr,
+        detail.tfri,
+        detail.tfrt: timestamp,
+        detail.thb,
+        detail.tla,
+        detail.tm,
+        detail.tms,
+        detail.tnd: timestamp,
+        detail.toi,
+        detail.toich1h,
+        detail.toich24h,
+        detail.toich4h,
+        detail.toivr,
+        detail.tp,
+        detail.tpa,
+        detail.tpdp15m,
+        detail.tpdp1h,
+        detail.tpdp24h,
+        detail.tpdp30m,
+        detail.tpdp4h,
+        detail.tpdp5m,
+        detail.tq,
+        detail.tt: timestamp,
+        detail.tv,
+        source_annualized_funding_rate,
+        target_annualized_funding_rate,
+    }
+    TelegramChannelsView {
+        category,
+        channel_name,
+        channel_title,
+        created_at: timestamp,
+        description,
+        link,
+        subscribers,
+    }
+    TelegramMessagesView {
+        channel_handle,
+        channel_id,
+        channel_name,
+        forwards,
+        message,
+        message_id,
+        message_link,
+        published_at: timestamp,
+        reactions,
+        views,
+    }
+    TickerView {
+        base,
+        timestamp: timestamp,
+        exchange,
+        highest_bid,
+        lowest_ask,
+        lower_depth,
+        market,
+        price,
+        price_24h,
+        price_change,
+        quote,
+        symbol,
+        upper_depth,
+        volume,
+    }
+    TokenDetail {
+        cmc_id,
+        icon,
+        id,
+        name,
+        symbol,
+    }
+    WalletStatusView {
+        currency,
+        deposit_message,
+        deposit_state,
+        exchange,
+        network,
+        updated_at: timestamp,
+        withdraw_message,
+        withdraw_state,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn candle_schema_marks_timestamp_and_non_nullable_floats() {
+        let schema = CexCandleView::schema();
+        let names: Vec<&str> = schema.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["close", "timestamp", "high", "low", "open", "volume"]
+        );
+        assert_eq!(schema[1].column_type, ColumnType::Timestamp);
+        assert!(schema.iter().all(|s| !s.nullable));
+    }
+
+    #[test]
+    fn columns_keep_nulls_and_row_order() {
+        let rows = [
+            FundingRateHistoryView {
+                timestamp: 1,
+                funding_rate: Some(0.5),
+            },
+            FundingRateHistoryView {
+                timestamp: 2,
+                funding_rate: None,
+            },
+        ];
+
+        let columns = FundingRateHistoryView::columns(&rows);
+
+        assert_eq!(
+            columns[0].values,
+            ColumnValues::Int64(vec![Some(1), Some(2)])
+        );
+        assert_eq!(
+            columns[1].values,
+            ColumnValues::Float64(vec![Some(0.5), None])
+        );
+        assert!(columns[1].spec.nullable);
+    }
+
+    #[test]
+    fn nested_fields_are_flattened_and_json_is_text() {
+        let schema = OpenInterestOverviewView::schema();
+        assert!(schema.iter().any(|s| s.name == "token_symbol"));
+
+        let premium = PremiumView::schema();
+        let d = premium
+            .iter()
+            .find(|s| s.name == "detail_d")
+            .expect("flattened");
+        assert_eq!(d.column_type, ColumnType::Timestamp);
+
+        let rows = [OpenInterestOverviewView {
+            exchanges: serde_json::json!({"binance": 1}),
+            ..Default::default()
+        }];
+        let columns = OpenInterestOverviewView::columns(&rows);
+        assert_eq!(
+            columns[0].values,
+            ColumnValues::Utf8(vec![Some(r#"{"binance":1}"#.to_string())])
+        );
+    }
+}
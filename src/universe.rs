@@ -0,0 +1,726 @@
+//! A tradable symbol universe joined from the symbol, fee and wallet
+//! endpoints.
+//!
+//! A [`Universe`](crate::universe::Universe) holds one
+//! [`SymbolRecord`](crate::universe::SymbolRecord) per exchange, market,
+//! base and quote listed by [`CexSymbol::metadata`](crate::CexSymbol::metadata),
+//! joined with
+//!
+//! - tags from [`CexSymbol::tags`](crate::CexSymbol::tags);
+//! - caution flags from [`CexSymbol::cautions`](crate::CexSymbol::cautions);
+//! - scheduled delistings from [`CexSymbol::delistings`](crate::CexSymbol::delistings);
+//! - maker and taker fees of the record's market from
+//!   [`TradingFees::fees`](crate::TradingFees::fees);
+//! - the networks open for deposit and withdrawal of the base asset from
+//!   [`WalletStatus::get`](crate::WalletStatus::get), left unset while the
+//!   API does not document the wallet state values.
+//!
+//! [`UniverseBuilder::build`](crate::universe::UniverseBuilder::build) joins
+//! rows already fetched, and
+//! [`Client::symbol_universe`](crate::Client::symbol_universe) fetches them.
+//! Universes [filter](crate::universe::Universe::filter) with predicates and
+//! [diff](crate::universe::Universe::diff) against an earlier snapshot.
+//!
+//! ```no_run
+//! use datamaxi::universe::{Change, UniverseBuilder};
+//! use datamaxi::Client;
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = Client::new("my_api_key");
+//! let builder = UniverseBuilder::new().exchange("upbit").exchange("bithumb");
+//! let before = client.symbol_universe(&builder).await?;
+//! // ... later
+//! let after = client.symbol_universe(&builder).await?;
+//!
+//! let tradable = after.filter(|record| record.is_tradable() && !record.is_flagged());
+//! println!("{} tradable symbols", tradable.records.len());
+//! for change in before.diff(&after) {
+//!     if let Change::Changed { after, fields, .. } = change {
+//!         println!("{:?}: {:?}", after.key, fields);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::api::{Error, Result};
+use crate::enums::{CautionLevel, Market, SymbolStatus, WalletState};
+use crate::exchange::Exchange;
+use crate::generated::{
+    CexFeesOptions, CexFeesView, CexSymbolCautionsOptions, CexSymbolCautionsView,
+    CexSymbolDelistingsOptions, CexSymbolDelistingsView, CexSymbolMetadataOptions,
+    CexSymbolMetadataView, CexSymbolTagsOptions, CexSymbolTagsView, WalletStatusOptions,
+    WalletStatusView,
+};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Identifies one listed symbol.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SymbolKey {
+    /// The exchange.
+    pub exchange: Exchange,
+    /// Spot or futures.
+    pub market: Market,
+    /// Base asset, upper-cased.
+    pub base: String,
+    /// Quote asset, upper-cased.
+    pub quote: String,
+}
+
+impl SymbolKey {
+    /// A key with the exchange normalized and the assets upper-cased.
+    pub fn new(exchange: &str, market: &str, base: &str, quote: &str) -> Self {
+        SymbolKey {
+            exchange: Exchange::new(exchange),
+            market: Market::from(market),
+            base: upper(base),
+            quote: upper(quote),
+        }
+    }
+}
+
+impl Ord for SymbolKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (
+            &self.exchange,
+            self.market.as_str(),
+            &self.base,
+            &self.quote,
+        )
+            .cmp(&(
+                &other.exchange,
+                other.market.as_str(),
+                &other.base,
+                &other.quote,
+            ))
+    }
+}
+
+impl PartialOrd for SymbolKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A reason to be careful with a symbol.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RiskFlag {
+    /// The status is anything but trading.
+    NotTrading(SymbolStatus),
+    /// The exchange flags the symbol at this level.
+    Caution(CautionLevel),
+    /// A delisting is scheduled.
+    DelistingScheduled,
+    /// Deposits of the base asset are closed on every network (spot only).
+    DepositsClosed,
+    /// Withdrawals of the base asset are closed on every network (spot only).
+    WithdrawalsClosed,
+}
+
+/// The joined view of one symbol.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SymbolRecord {
+    /// Which symbol.
+    pub key: SymbolKey,
+    /// Trading status.
+    pub status: SymbolStatus,
+    /// Listing time, UTC milliseconds.
+    pub listed_at: Option<i64>,
+    /// Tags from the metadata and the tags endpoint, sorted.
+    pub tags: BTreeSet<String>,
+    /// The most severe active caution.
+    pub caution: Option<CautionLevel>,
+    /// Why the exchange raised the caution.
+    pub caution_reasons: Vec<String>,
+    /// Scheduled or past delisting time, UTC milliseconds.
+    pub delisting_at: Option<i64>,
+    /// Maker fee of this market, as a fraction.
+    pub maker_fee: Option<f64>,
+    /// Taker fee of this market, as a fraction.
+    pub taker_fee: Option<f64>,
+    /// Networks open for deposits of the base asset, upper-cased; `None`
+    /// without wallet data or when its states are not understood.
+    pub deposit_networks: Option<Vec<String>>,
+    /// Networks open for withdrawals of the base asset, upper-cased; `None`
+    /// without wallet data or when its states are not understood.
+    pub withdraw_networks: Option<Vec<String>>,
+    /// Every risk that applies, in declaration order.
+    pub flags: Vec<RiskFlag>,
+}
+
+impl SymbolRecord {
+    /// Whether the symbol is trading normally.
+    pub fn is_tradable(&self) -> bool {
+        self.status == SymbolStatus::Trading
+    }
+
+    /// Whether any risk flag applies.
+    pub fn is_flagged(&self) -> bool {
+        !self.flags.is_empty()
+    }
+
+    /// Whether the symbol carries `tag` (case-insensitive).
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.contains(&tag.trim().to_ascii_lowercase())
+    }
+
+    fn flag(&mut self) {
+        let spot = self.key.market == Market::Spot;
+        let closed = |networks: &Option<Vec<String>>| {
+            spot && networks
+                .as_ref()
+                .is_some_and(|networks| networks.is_empty())
+        };
+        let mut flags = Vec::new();
+        if !self.is_tradable() {
+            flags.push(RiskFlag::NotTrading(self.status.clone()));
+        }
+        if let Some(level) = &self.caution {
+            flags.push(RiskFlag::Caution(level.clone()));
+        }
+        if self.delisting_at.is_some() {
+            flags.push(RiskFlag::DelistingScheduled);
+        }
+        if closed(&self.deposit_networks) {
+            flags.push(RiskFlag::DepositsClosed);
+        }
+        if closed(&self.withdraw_networks) {
+            flags.push(RiskFlag::WithdrawalsClosed);
+        }
+        self.flags = flags;
+    }
+}
+
+/// A part of a [`SymbolRecord`] that changed between snapshots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SymbolField {
+    /// [`SymbolRecord::status`].
+    Status,
+    /// [`SymbolRecord::tags`].
+    Tags,
+    /// [`SymbolRecord::caution`] or its reasons.
+    Caution,
+    /// [`SymbolRecord::delisting_at`].
+    Delisting,
+    /// The maker or taker fee.
+    Fees,
+    /// The open deposit or withdrawal networks.
+    Wallet,
+}
+
+/// A difference between two snapshots.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Change {
+    /// Listed in the newer snapshot only.
+    Added(SymbolRecord),
+    /// Listed in the older snapshot only.
+    Removed(SymbolRecord),
+    /// Listed in both, with different fields.
+    Changed {
+        /// The older record.
+        before: Box<SymbolRecord>,
+        /// The newer record.
+        after: Box<SymbolRecord>,
+        /// What differs.
+        fields: Vec<SymbolField>,
+    },
+}
+
+/// Symbol records, sorted by key.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Universe {
+    /// One record per symbol.
+    pub records: Vec<SymbolRecord>,
+}
+
+impl Universe {
+    /// The record of one symbol.
+    pub fn get(&self, key: &SymbolKey) -> Option<&SymbolRecord> {
+        self.records
+            .binary_search_by(|record| record.key.cmp(key))
+            .ok()
+            .map(|index| &self.records[index])
+    }
+
+    /// The records matching `predicate`.
+    pub fn filter(&self, predicate: impl Fn(&SymbolRecord) -> bool) -> Universe {
+        Universe {
+            records: self
+                .records
+                .iter()
+                .filter(|record| predicate(record))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// What changed from `self` to `newer`, by key. Fetch times are not
+    /// compared, so the flags follow the fields they derive from.
+    pub fn diff(&self, newer: &Universe) -> Vec<Change> {
+        let older: BTreeMap<_, _> = self.records.iter().map(|r| (&r.key, r)).collect();
+        let newer: BTreeMap<_, _> = newer.records.iter().map(|r| (&r.key, r)).collect();
+        let keys: BTreeSet<_> = older.keys().chain(newer.keys()).copied().collect();
+        keys.into_iter()
+            .filter_map(|key| match (older.get(key), newer.get(key)) {
+                (Some(&before), Some(&after)) => {
+                    let fields = changed_fields(before, after);
+                    (!fields.is_empty()).then(|| Change::Changed {
+                        before: Box::new(before.clone()),
+                        after: Box::new(after.clone()),
+                        fields,
+                    })
+                }
+                (Some(&before), None) => Some(Change::Removed(before.clone())),
+                (None, Some(&after)) => Some(Change::Added(after.clone())),
+                (None, None) => None,
+            })
+            .collect()
+    }
+}
+
+fn changed_fields(before: &SymbolRecord, after: &SymbolRecord) -> Vec<SymbolField> {
+    [
+        (SymbolField::Status, before.status != after.status),
+        (SymbolField::Tags, before.tags != after.tags),
+        (
+            SymbolField::Caution,
+            (&before.caution, &before.caution_reasons) != (&after.caution, &after.caution_reasons),
+        ),
+        (
+            SymbolField::Delisting,
+            before.delisting_at != after.delisting_at,
+        ),
+        (
+            SymbolField::Fees,
+            (before.maker_fee, before.taker_fee) != (after.maker_fee, after.taker_fee),
+        ),
+        (
+            SymbolField::Wallet,
+            (&before.deposit_networks, &before.withdraw_networks)
+                != (&after.deposit_networks, &after.withdraw_networks),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(field, changed)| changed.then_some(field))
+    .collect()
+}
+
+fn upper(value: &str) -> String {
+    value.trim().to_ascii_uppercase()
+}
+
+fn severity(level: &CautionLevel) -> u8 {
+    match level {
+        CautionLevel::Caution => 1,
+        CautionLevel::Warning => 2,
+        CautionLevel::Danger => 3,
+        CautionLevel::Unknown(_) => 0,
+    }
+}
+
+/// Networks on which `state` is open, or `None` when any state on the
+/// asset is not understood.
+fn open_networks(
+    wallets: &[&WalletStatusView],
+    state: fn(&WalletStatusView) -> WalletState,
+) -> Option<Vec<String>> {
+    let mut networks = BTreeSet::new();
+    for wallet in wallets {
+        if state(wallet).is_open()? {
+            networks.insert(upper(&wallet.network));
+        }
+    }
+    Some(networks.into_iter().collect())
+}
+
+/// Page sizes: the maximum each endpoint accepts.
+const METADATA_PAGE: i64 = 2000;
+const TAGS_PAGE: i64 = 5000;
+const CAUTIONS_PAGE: i64 = 5000;
+const DELISTINGS_PAGE: i64 = 2000;
+
+/// Which exchanges a universe covers, and whether to fetch wallets.
+#[derive(Debug, Clone, Default)]
+pub struct UniverseBuilder {
+    exchanges: Vec<Exchange>,
+    wallets: bool,
+}
+
+impl UniverseBuilder {
+    /// Every exchange, without wallet statuses.
+    pub fn new() -> Self {
+        UniverseBuilder::default()
+    }
+
+    /// Restricts the universe to `exchange`; repeat for more. No call
+    /// means every exchange.
+    pub fn exchange(mut self, exchange: impl Into<Exchange>) -> Self {
+        let exchange = exchange.into();
+        if !self.exchanges.contains(&exchange) {
+            self.exchanges.push(exchange);
+        }
+        self
+    }
+
+    /// Fetches wallet statuses too: one request per spot base asset, so
+    /// off by default.
+    pub fn wallets(mut self, wallets: bool) -> Self {
+        self.wallets = wallets;
+        self
+    }
+
+    /// Joins fetched rows into a universe. Only symbols in `metadata`
+    /// become records; rows of the other inputs are matched to them by
+    /// exchange, market, base and quote (fees and wallets by exchange and
+    /// assets), and rows for other exchanges than the configured ones are
+    /// ignored.
+    pub fn build(
+        &self,
+        metadata: &[CexSymbolMetadataView],
+        tags: &[CexSymbolTagsView],
+        cautions: &[CexSymbolCautionsView],
+        delistings: &[CexSymbolDelistingsView],
+        fees: &[CexFeesView],
+        wallets: &[WalletStatusView],
+    ) -> Universe {
+        let covered = |exchange: &str| {
+            self.exchanges.is_empty() || self.exchanges.contains(&Exchange::new(exchange))
+        };
+        let mut records: BTreeMap<SymbolKey, SymbolRecord> = BTreeMap::new();
+        for row in metadata.iter().filter(|row| covered(&row.exchange)) {
+            let key = SymbolKey::new(&row.exchange, &row.market, &row.base, &row.quote);
+            let caution = row
+                .caution_level
+                .as_deref()
+                .filter(|level| !level.is_empty())
+                .map(CautionLevel::from);
+            records.insert(
+                key.clone(),
+                SymbolRecord {
+                    key,
+                    status: row.status_kind(),
+                    listed_at: row.listed_at,
+                    tags: row
+                        .tags
+                        .iter()
+                        .map(|tag| tag.to_ascii_lowercase())
+                        .collect(),
+                    caution_reasons: if caution.is_some() {
+                        row.caution_reasons.clone()
+                    } else {
+                        Vec::new()
+                    },
+                    caution,
+                    delisting_at: row.delisting_at,
+                    maker_fee: None,
+                    taker_fee: None,
+                    deposit_networks: None,
+                    withdraw_networks: None,
+                    flags: Vec::new(),
+                },
+            );
+        }
+
+        for row in tags {
+            let key = SymbolKey::new(&row.exchange, &row.market, &row.base, &row.quote);
+            if let Some(record) = records.get_mut(&key) {
+                record.tags.insert(row.tag.to_ascii_lowercase());
+            }
+        }
+        for row in cautions {
+            let key = SymbolKey::new(&row.exchange, &row.market, &row.base, &row.quote);
+            let Some(record) = records.get_mut(&key) else {
+                continue;
+            };
+            let level = row.caution_level_kind();
+            if record
+                .caution
+                .as_ref()
+                .is_none_or(|current| severity(&level) > severity(current))
+            {
+                record.caution = Some(level);
+                record.caution_reasons = row.reasons.clone();
+            }
+        }
+        for row in delistings {
+            let key = SymbolKey::new(&row.exchange, &row.market, &row.base, &row.quote);
+            if let Some(record) = records.get_mut(&key) {
+                record.delisting_at = Some(row.delisting_at);
+            }
+        }
+
+        let fees: HashMap<_, _> = fees
+            .iter()
+            .map(|fee| {
+                let key = (
+                    Exchange::new(&fee.exchange),
+                    upper(&fee.base),
+                    upper(&fee.quote),
+                );
+                (key, fee)
+            })
+            .collect();
+        let mut networks: HashMap<_, Vec<&WalletStatusView>> = HashMap::new();
+        for wallet in wallets {
+            networks
+                .entry((Exchange::new(&wallet.exchange), upper(&wallet.currency)))
+                .or_default()
+                .push(wallet);
+        }
+
+        let mut records: Vec<SymbolRecord> = records.into_values().collect();
+        for record in &mut records {
+            let key = &record.key;
+            if let Some(fee) =
+                fees.get(&(key.exchange.clone(), key.base.clone(), key.quote.clone()))
+            {
+                (record.maker_fee, record.taker_fee) = match key.market {
+                    Market::Futures => (fee.futures_maker_fee, fee.futures_taker_fee),
+                    _ => (fee.spot_maker_fee, fee.spot_take_fee),
+                };
+            }
+            if let Some(wallets) = networks.get(&(key.exchange.clone(), key.base.clone())) {
+                record.deposit_networks =
+                    open_networks(wallets, WalletStatusView::deposit_state_kind);
+                record.withdraw_networks =
+                    open_networks(wallets, WalletStatusView::withdraw_state_kind);
+            }
+            record.flag();
+        }
+        Universe { records }
+    }
+
+    fn exchange_filter(&self) -> Option<String> {
+        (!self.exchanges.is_empty()).then(|| {
+            self.exchanges
+                .iter()
+                .map(Exchange::as_str)
+                .collect::<Vec<_>>()
+                .join(",")
+        })
+    }
+}
+
+/// Fetches every page of a `limit`/`page` list endpoint, stopping at the
+/// first short page.
+macro_rules! all_pages {
+    ($endpoint:expr, $method:ident, $options:expr, $limit:expr $(, $aw:ident)?) => {{
+        let mut rows = Vec::new();
+        let mut page = 1;
+        loop {
+            let batch = $endpoint
+                .$method($options.clone().limit($limit).page(page))$(.$aw)?;
+            let batch = batch?;
+            let done = (batch.len() as i64) < $limit;
+            rows.extend(batch);
+            if done {
+                break;
+            }
+            page += 1;
+        }
+        rows
+    }};
+}
+
+/// Body of the async and blocking `symbol_universe`; see `get_loop!` in [`crate::api`].
+macro_rules! symbol_universe {
+    ($client:expr, $builder:expr $(, $aw:ident)?) => {{
+        let client = $client;
+        let builder: &UniverseBuilder = $builder;
+        let cex_symbol = client.cex_symbol();
+        let filter = builder.exchange_filter();
+
+        let mut options = CexSymbolMetadataOptions::new();
+        options.exchange = filter.clone();
+        let metadata = all_pages!(cex_symbol, metadata, options, METADATA_PAGE $(, $aw)?);
+        let mut options = CexSymbolTagsOptions::new();
+        options.exchange = filter.clone();
+        let tags = all_pages!(cex_symbol, tags, options, TAGS_PAGE $(, $aw)?);
+        let mut options = CexSymbolCautionsOptions::new();
+        options.exchange = filter.clone();
+        let cautions = all_pages!(cex_symbol, cautions, options, CAUTIONS_PAGE $(, $aw)?);
+        let mut options = CexSymbolDelistingsOptions::new();
+        options.exchange = filter;
+        let delistings = all_pages!(cex_symbol, delistings, options, DELISTINGS_PAGE $(, $aw)?);
+
+        let mut fees = Vec::new();
+        let scopes: Vec<Option<&Exchange>> = if builder.exchanges.is_empty() {
+            vec![None]
+        } else {
+            builder.exchanges.iter().map(Some).collect()
+        };
+        for exchange in scopes {
+            let mut options = CexFeesOptions::new();
+            options.exchange = exchange.map(|exchange| exchange.to_string());
+            let schedule = client.trading_fees().fees(options)$(.$aw)?;
+            fees.extend(schedule?);
+        }
+
+        let mut wallets = Vec::new();
+        if builder.wallets {
+            let assets: BTreeSet<String> = metadata
+                .iter()
+                .filter(|row| Market::from(row.market.as_str()) == Market::Spot)
+                .map(|row| upper(&row.base))
+                .collect();
+            for asset in assets {
+                let statuses = client
+                    .wallet_status()
+                    .get(asset, WalletStatusOptions::default())$(.$aw)?;
+                match statuses {
+                    Ok(statuses) => wallets.extend(statuses),
+                    // An asset the wallet service does not track keeps no
+                    // wallet data.
+                    Err(Error::NotFound { .. }) => {}
+                    Err(err) => return Err(err),
+                }
+            }
+        }
+
+        Ok(builder.build(&metadata, &tags, &cautions, &delistings, &fees, &wallets))
+    }};
+}
+
+impl crate::api::Client {
+    /// Fetches and joins the universe `builder` describes: every page of
+    /// metadata, tags, active cautions and delistings scheduled in the next
+    /// 30 days, the fee schedule of each exchange, and, when enabled, the
+    /// wallet status of each spot base asset.
+    pub async fn symbol_universe(&self, builder: &UniverseBuilder) -> Result<Universe> {
+        symbol_universe!(self, builder, await)
+    }
+}
+
+#[cfg(feature = "sync")]
+impl crate::api::sync::Client {
+    /// Blocking mirror of the async `Client::symbol_universe`.
+    pub fn symbol_universe(&self, builder: &UniverseBuilder) -> Result<Universe> {
+        symbol_universe!(self, builder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(exchange: &str, market: &str, base: &str, status: &str) -> CexSymbolMetadataView {
+        CexSymbolMetadataView {
+            exchange: exchange.into(),
+            market: market.into(),
+            base: base.into(),
+            quote: "krw".into(),
+            status: status.into(),
+            ..Default::default()
+        }
+    }
+
+    fn wallet(network: &str, deposit: &str, withdraw: &str) -> WalletStatusView {
+        WalletStatusView {
+            currency: "xrp".into(),
+            exchange: "upbit".into(),
+            network: network.into(),
+            deposit_state: deposit.into(),
+            withdraw_state: withdraw.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn build_joins_every_source_and_flags_risks() {
+        let caution = |level: &str| CexSymbolCautionsView {
+            exchange: "upbit".into(),
+            market: "spot".into(),
+            base: "XRP".into(),
+            quote: "KRW".into(),
+            caution_level: level.into(),
+            reasons: vec![level.into()],
+            ..Default::default()
+        };
+        let universe = UniverseBuilder::new().exchange("Upbit").build(
+            &[
+                metadata("upbit", "spot", "xrp", "trading"),
+                metadata("upbit", "spot", "btc", "trading"),
+                metadata("bithumb", "spot", "xrp", "halt"),
+            ],
+            &[CexSymbolTagsView {
+                exchange: "upbit".into(),
+                market: "spot".into(),
+                base: "XRP".into(),
+                quote: "KRW".into(),
+                tag: "Payments".into(),
+                ..Default::default()
+            }],
+            &[caution("warning"), caution("caution")],
+            &[],
+            &[CexFeesView {
+                exchange: "upbit".into(),
+                base: "XRP".into(),
+                quote: "KRW".into(),
+                spot_maker_fee: Some(0.0005),
+                spot_take_fee: Some(0.0005),
+                ..Default::default()
+            }],
+            &[wallet("xrp", "s", "s"), wallet("bep20", "s", "s")],
+        );
+
+        assert_eq!(universe.records.len(), 2);
+        let xrp = universe
+            .get(&SymbolKey::new("upbit", "spot", "XRP", "KRW"))
+            .expect("xrp is listed");
+        assert!(xrp.is_tradable() && xrp.has_tag("payments"));
+        assert_eq!(xrp.caution_reasons, ["warning"]);
+        assert_eq!(xrp.taker_fee, Some(0.0005));
+        assert_eq!(xrp.deposit_networks, None);
+        assert_eq!(xrp.withdraw_networks, None);
+        assert_eq!(xrp.flags, [RiskFlag::Caution(CautionLevel::Warning)]);
+        let clean = universe.filter(|record| !record.is_flagged());
+        assert_eq!(clean.records[0].key.base, "BTC");
+    }
+
+    #[test]
+    fn diff_reports_added_removed_and_changed_fields() {
+        let builder = UniverseBuilder::new();
+        let before = builder.build(
+            &[
+                metadata("upbit", "spot", "XRP", "trading"),
+                metadata("upbit", "spot", "DOGE", "trading"),
+            ],
+            &[],
+            &[],
+            &[],
+            &[],
+            &[],
+        );
+        let after = builder.build(
+            &[
+                metadata("upbit", "spot", "XRP", "trading"),
+                metadata("upbit", "spot", "DOGE", "delisting"),
+                metadata("upbit", "futures", "DOGE", "trading"),
+            ],
+            &[],
+            &[],
+            &[CexSymbolDelistingsView {
+                exchange: "upbit".into(),
+                market: "spot".into(),
+                base: "DOGE".into(),
+                quote: "KRW".into(),
+                delisting_at: 1,
+                ..Default::default()
+            }],
+            &[],
+            &[],
+        );
+
+        let changes = before.diff(&after);
+        assert_eq!(changes.len(), 2);
+        assert!(
+            matches!(&changes[0], Change::Added(record) if record.key.market == Market::Futures)
+        );
+        let Change::Changed { fields, .. } = &changes[1] else {
+            panic!("DOGE spot changed");
+        };
+        assert_eq!(fields, &[SymbolField::Status, SymbolField::Delisting]);
+    }
+}
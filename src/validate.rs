@@ -0,0 +1,526 @@
+//! Client-side validation of endpoint options.
+//!
+//! The API documents a number of constraints on its query parameters: page
+//! sizes capped at 1000–5000, `min_confidence` between 0 and 100, `from`
+//! before `to`, comma-separated filter lists, and so on. Violating one costs
+//! a round trip and, depending on the endpoint, comes back as an opaque
+//! `400` or is silently clamped. This module declares those constraints once
+//! per options struct and checks them before a request is sent:
+//!
+//! - [`Client::get`](crate::api::Client::get) (and its blocking mirror) runs
+//!   the checks for the endpoint it is called with, so every generated
+//!   wrapper, paginator and time-range walker is covered;
+//! - [`Validate`](crate::validate::Validate) exposes the same checks on the
+//!   options structs themselves, for validating user input early.
+//!
+//! A violation is reported as
+//! [`Error::InvalidArgument`](crate::api::Error::InvalidArgument), naming the wire
+//! parameter and what was wrong with it:
+//!
+//! ```
+//! use datamaxi::validate::Validate;
+//! use datamaxi::{CexSymbolTagsOptions, PremiumOptions};
+//!
+//! let tags = CexSymbolTagsOptions { min_confidence: Some(120), ..Default::default() };
+//! assert_eq!(
+//!     tags.validate().unwrap_err().to_string(),
+//!     "Invalid argument `min_confidence`: must be between 0 and 100, got 120",
+//! );
+//!
+//! let premium = PremiumOptions {
+//!     token_include: Some("bitcoin,,ethereum".into()),
+//!     ..Default::default()
+//! };
+//! assert!(premium.validate().is_err());
+//! ```
+//!
+//! The checks are deliberately limited to what the API documents: bounds,
+//! comma-separated list syntax, millisecond timestamps passed as seconds,
+//! window lengths (such as the heatmap's "sub-1h windows are not
+//! supported") and range ordering. The API documents no mutually exclusive
+//! options and no options that require one another, so none are enforced;
+//! how filters combine is left for the server to judge. Nor does it
+//! document a `limit` for the candle endpoint, whose options have none.
+
+use crate::api::{Error, Result};
+use crate::generated::{
+    CexAnnouncementsOptions, CexCandleOptions, CexSymbolCautionsOptions,
+    CexSymbolDelistingsOptions, CexSymbolLiquidationOptions, CexSymbolMetadataOptions,
+    CexSymbolTagsOptions, CexTokenUpdatesOptions, FundingRateHistoryOptions, IndexPriceOptions,
+    LiquidationFeedOptions, LiquidationHeatmapOptions, LiquidationOptions, LiquidationStatsOptions,
+    OpenInterestHistoryAggregatedOptions, OpenInterestOverviewOptions, OpenInterestSummaryOptions,
+    PremiumOptions, TelegramChannelsOptions, TelegramMessagesOptions,
+};
+use std::collections::BTreeMap;
+
+/// A unix timestamp in seconds at or above this would be in the year 5138:
+/// it is almost certainly in milliseconds.
+const MILLIS_THRESHOLD: i64 = 100_000_000_000;
+
+/// Checks an options struct against the constraints the API documents for
+/// its endpoint, without sending anything.
+///
+/// [`Client::get`](crate::api::Client::get) runs the same checks on every
+/// request, so calling this is only needed to reject bad input early.
+pub trait Validate {
+    /// Returns [`Error::InvalidArgument`] for the first violated constraint.
+    fn validate(&self) -> Result<()>;
+}
+
+/// One declared constraint on the query parameters of an endpoint.
+#[derive(Debug, Clone, Copy)]
+enum Rule {
+    /// An integer within `min..=max`.
+    Range(&'static str, i64, i64),
+    /// A finite number `>= 0`.
+    NonNegative(&'static str),
+    /// A comma-separated list without empty or padded items.
+    List(&'static str),
+    /// A unix timestamp in seconds.
+    UnixSeconds(&'static str),
+    /// A window such as `24h` or `7d`, between the given numbers of seconds.
+    Window(&'static str, u64, u64),
+    /// The first timestamp is not after the second.
+    Ordered(&'static str, &'static str),
+}
+
+fn invalid(argument: &str, reason: String) -> Error {
+    Error::InvalidArgument {
+        argument: argument.to_string(),
+        reason,
+    }
+}
+
+fn integer(name: &str, value: &str) -> Result<i64> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| invalid(name, format!("expected an integer, got `{value}`")))
+}
+
+fn window_seconds(value: &str) -> Option<u64> {
+    let unit = match value.chars().last()? {
+        'm' => 60,
+        'h' => 3_600,
+        'd' => 86_400,
+        'w' => 604_800,
+        _ => return None,
+    };
+    let count: u64 = value[..value.len() - 1].parse().ok()?;
+    (count > 0).then(|| count.saturating_mul(unit))
+}
+
+impl Rule {
+    fn check(self, parameters: &BTreeMap<String, String>) -> Result<()> {
+        let get = |name: &str| parameters.get(name).map(String::as_str);
+        match self {
+            Rule::Range(name, min, max) => {
+                let Some(value) = get(name) else {
+                    return Ok(());
+                };
+                let value = integer(name, value)?;
+                if (min..=max).contains(&value) {
+                    Ok(())
+                } else if max == i64::MAX {
+                    Err(invalid(
+                        name,
+                        format!("must be at least {min}, got {value}"),
+                    ))
+                } else {
+                    Err(invalid(
+                        name,
+                        format!("must be between {min} and {max}, got {value}"),
+                    ))
+                }
+            }
+            Rule::NonNegative(name) => match get(name) {
+                Some(value) => match value.trim().parse::<f64>() {
+                    Ok(number) if number.is_finite() && number >= 0.0 => Ok(()),
+                    _ => Err(invalid(
+                        name,
+                        format!("must be a non-negative number, got `{value}`"),
+                    )),
+                },
+                None => Ok(()),
+            },
+            Rule::List(name) => {
+                // An empty value means "no filter"; only a non-empty list
+                // with a blank or padded item is malformed.
+                let Some(value) = get(name).filter(|value| !value.is_empty()) else {
+                    return Ok(());
+                };
+                for (position, item) in value.split(',').enumerate() {
+                    if item.trim().is_empty() {
+                        return Err(invalid(
+                            name,
+                            format!("malformed list `{value}`: item {} is empty", position + 1),
+                        ));
+                    }
+                    if item.trim() != item {
+                        return Err(invalid(
+                            name,
+                            format!(
+                                "malformed list `{value}`: item `{item}` has surrounding whitespace"
+                            ),
+                        ));
+                    }
+                }
+                Ok(())
+            }
+            Rule::UnixSeconds(name) => match get(name) {
+                Some(value) if integer(name, value)?.abs() >= MILLIS_THRESHOLD => Err(invalid(
+                    name,
+                    format!("expects unix seconds, but {value} looks like milliseconds"),
+                )),
+                _ => Ok(()),
+            },
+            Rule::Window(name, min, max) => {
+                let Some(value) = get(name) else {
+                    return Ok(());
+                };
+                match window_seconds(value) {
+                    Some(seconds) if (min..=max).contains(&seconds) => Ok(()),
+                    Some(seconds) if seconds < min => Err(invalid(
+                        name,
+                        format!("must be at least {}h, got `{value}`", min / 3_600),
+                    )),
+                    Some(_) => Err(invalid(
+                        name,
+                        format!("must be at most {}d, got `{value}`", max / 86_400),
+                    )),
+                    None => Err(invalid(
+                        name,
+                        format!("expected a window such as `1h`, `24h` or `7d`, got `{value}`"),
+                    )),
+                }
+            }
+            Rule::Ordered(from, to) => {
+                let (Some(start), Some(end)) = (get(from), get(to)) else {
+                    return Ok(());
+                };
+                if integer(from, start)? > integer(to, end)? {
+                    Err(invalid(
+                        from,
+                        format!("`{from}` ({start}) is after `{to}` ({end})"),
+                    ))
+                } else {
+                    Ok(())
+                }
+            }
+        }
+    }
+}
+
+fn check(rules: &[Rule], parameters: &BTreeMap<String, String>) -> Result<()> {
+    rules.iter().try_for_each(|rule| rule.check(parameters))
+}
+
+/// Expands one rule of the [`constraints!`] table into a [`Rule`].
+macro_rules! rule {
+    (range($name:ident, $min:expr, $max:expr)) => {
+        Rule::Range(stringify!($name), $min, $max)
+    };
+    (at_least($name:ident, $min:expr)) => {
+        Rule::Range(stringify!($name), $min, i64::MAX)
+    };
+    (non_negative($name:ident)) => {
+        Rule::NonNegative(stringify!($name))
+    };
+    (list($name:ident)) => {
+        Rule::List(stringify!($name))
+    };
+    (unix_seconds($name:ident)) => {
+        Rule::UnixSeconds(stringify!($name))
+    };
+    (window($name:ident, $max_days:expr)) => {
+        Rule::Window(stringify!($name), 0, $max_days * 86_400)
+    };
+    (min_window($name:ident, $min_hours:expr)) => {
+        Rule::Window(stringify!($name), $min_hours * 3_600, u64::MAX)
+    };
+    (ordered($from:ident, $to:ident)) => {
+        Rule::Ordered(stringify!($from), stringify!($to))
+    };
+}
+
+/// Copies the options fields a rule reads into `parameters`, under their
+/// wire names.
+macro_rules! collect {
+    ($options:ident, $parameters:ident, $kind:ident($first:ident, $second:ident)) => {
+        collect!($options, $parameters, $first);
+        collect!($options, $parameters, $second);
+    };
+    ($options:ident, $parameters:ident, $kind:ident($name:ident $(, $bound:expr)*)) => {
+        collect!($options, $parameters, $name);
+    };
+    ($options:ident, $parameters:ident, $name:ident) => {
+        if let Some(value) = &$options.$name {
+            $parameters.insert(stringify!($name).to_string(), value.to_string());
+        }
+    };
+}
+
+/// Declares the constraints of each options struct and its endpoint, and
+/// generates both [`Validate`] and the endpoint lookup used by
+/// [`check_parameters`].
+macro_rules! constraints {
+    ($($options:ident at $endpoint:literal { $($kind:ident($($arg:tt)*)),* $(,)? })*) => {
+        $(
+            impl Validate for $options {
+                fn validate(&self) -> Result<()> {
+                    let mut parameters = BTreeMap::new();
+                    $(collect!(self, parameters, $kind($($arg)*));)*
+                    check(rules($endpoint), &parameters)
+                }
+            }
+        )*
+
+        fn rules(endpoint: &str) -> &'static [Rule] {
+            match endpoint {
+                $($endpoint => &[$(rule!($kind($($arg)*))),*],)*
+                _ => &[],
+            }
+        }
+    };
+}
+
+constraints! {
+    CexAnnouncementsOptions at "/api/v1/cex/announcements" {
+        at_least(page, 1),
+        at_least(limit, 1),
+        list(exchange),
+    }
+    CexCandleOptions at "/api/v1/cex/candle" {
+        unix_seconds(from),
+        unix_seconds(to),
+        ordered(from, to),
+    }
+    CexSymbolCautionsOptions at "/api/v1/cex/symbol/cautions" {
+        list(exchange),
+        range(limit, 1, 5000),
+        at_least(page, 1),
+    }
+    CexSymbolDelistingsOptions at "/api/v1/cex/symbol/delistings" {
+        list(exchange),
+        ordered(from_ms, to_ms),
+        range(limit, 1, 2000),
+        at_least(page, 1),
+    }
+    CexSymbolLiquidationOptions at "/api/v1/cex/symbol/liquidation" {
+        window(window, 30),
+    }
+    CexSymbolMetadataOptions at "/api/v1/cex/symbol/metadata" {
+        list(exchange),
+        list(status),
+        range(limit, 1, 2000),
+        at_least(page, 1),
+    }
+    CexSymbolTagsOptions at "/api/v1/cex/symbol/tags" {
+        list(tag),
+        list(exchange),
+        range(min_confidence, 0, 100),
+        range(limit, 1, 5000),
+        at_least(page, 1),
+    }
+    CexTokenUpdatesOptions at "/api/v1/cex/token/updates" {
+        at_least(page, 1),
+        at_least(limit, 1),
+    }
+    FundingRateHistoryOptions at "/api/v1/funding-rate/history" {
+        at_least(page, 1),
+        at_least(limit, 1),
+        unix_seconds(from),
+        unix_seconds(to),
+        ordered(from, to),
+    }
+    IndexPriceOptions at "/api/v1/index-price" {
+        unix_seconds(from),
+        unix_seconds(to),
+        ordered(from, to),
+    }
+    LiquidationOptions at "/api/v1/liquidation" {
+        range(limit, 1, 1000),
+    }
+    LiquidationFeedOptions at "/api/v1/liquidation/feed" {
+        non_negative(min_volume_usd),
+        range(limit, 1, 1000),
+    }
+    LiquidationHeatmapOptions at "/api/v1/liquidation/heatmap" {
+        min_window(window, 1),
+        at_least(top_n, 1),
+    }
+    LiquidationStatsOptions at "/api/v1/liquidation/stats" {
+        non_negative(min_volume_usd),
+    }
+    OpenInterestHistoryAggregatedOptions at "/api/v1/open-interest/history-aggregated" {
+        ordered(from, to),
+    }
+    OpenInterestOverviewOptions at "/api/v1/open-interest/overview" {
+        at_least(page, 1),
+        at_least(limit, 1),
+    }
+    OpenInterestSummaryOptions at "/api/v1/open-interest/summary" {
+        at_least(top_n, 1),
+    }
+    PremiumOptions at "/api/v1/premium" {
+        list(source_exchange),
+        list(target_exchange),
+        list(asset),
+        list(source_quote),
+        list(target_quote),
+        list(network),
+        list(token_include),
+        list(token_exclude),
+        at_least(page, 1),
+        at_least(limit, 1),
+        non_negative(min_sv),
+        non_negative(min_tv),
+    }
+    TelegramChannelsOptions at "/api/v1/telegram/channels" {
+        at_least(page, 1),
+        at_least(limit, 1),
+    }
+    TelegramMessagesOptions at "/api/v1/telegram/messages" {
+        at_least(page, 1),
+        at_least(limit, 1),
+    }
+}
+
+/// Checks the query parameters of a request to `endpoint` against the
+/// constraints declared for it; endpoints without any pass unchecked.
+pub(crate) fn check_parameters(
+    endpoint: &str,
+    parameters: Option<&BTreeMap<String, String>>,
+) -> Result<()> {
+    match parameters {
+        Some(parameters) => check(rules(endpoint), parameters),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reason(result: Result<()>) -> (String, String) {
+        match result {
+            Err(Error::InvalidArgument { argument, reason }) => (argument, reason),
+            other => panic!("expected InvalidArgument, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn bounds_and_timestamp_units() {
+        let limit = CexSymbolCautionsOptions {
+            limit: Some(5001),
+            ..Default::default()
+        };
+        assert_eq!(
+            reason(limit.validate()),
+            (
+                "limit".into(),
+                "must be between 1 and 5000, got 5001".into()
+            )
+        );
+        let page = FundingRateHistoryOptions {
+            page: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(reason(page.validate()).1, "must be at least 1, got 0");
+
+        let millis = CexCandleOptions {
+            from: Some(1_735_657_200_000),
+            ..Default::default()
+        };
+        assert_eq!(
+            reason(millis.validate()),
+            (
+                "from".into(),
+                "expects unix seconds, but 1735657200000 looks like milliseconds".into()
+            )
+        );
+        let reversed = IndexPriceOptions {
+            from: Some(1_735_693_200),
+            to: Some(1_735_657_200),
+            ..Default::default()
+        };
+        assert_eq!(
+            reason(reversed.validate()).1,
+            "`from` (1735693200) is after `to` (1735657200)"
+        );
+    }
+
+    #[test]
+    fn lists_and_undocumented_combinations() {
+        for malformed in [
+            "bitcoin,",
+            ",bitcoin",
+            "bitcoin,,ethereum",
+            "bitcoin, ethereum",
+        ] {
+            let options = PremiumOptions {
+                token_include: Some(malformed.into()),
+                ..Default::default()
+            };
+            assert_eq!(reason(options.validate()).0, "token_include", "{malformed}");
+        }
+
+        // How filters combine is not documented, so it is left to the
+        // server.
+        let valid = PremiumOptions {
+            source_exchange: Some("binance,bybit".into()),
+            token_include: Some("bitcoin,ethereum".into()),
+            token_exclude: Some("ethereum".into()),
+            conversion_base: Some("USDT".into()),
+            min_sv: Some(0.0),
+            ..Default::default()
+        };
+        assert!(valid.validate().is_ok());
+        assert!(CexSymbolCautionsOptions {
+            exchange: Some(String::new()),
+            ..Default::default()
+        }
+        .validate()
+        .is_ok());
+    }
+
+    #[test]
+    fn windows_and_raw_parameters() {
+        let window = |value: &str| CexSymbolLiquidationOptions {
+            window: Some(value.into()),
+        };
+        assert!(window("24h").validate().is_ok());
+        assert!(window("30d").validate().is_ok());
+        assert_eq!(
+            reason(window("31d").validate()).1,
+            "must be at most 30d, got `31d`"
+        );
+        assert!(window("day").validate().is_err());
+
+        let heatmap = BTreeMap::from([("window".to_string(), "30m".to_string())]);
+        assert_eq!(
+            reason(check_parameters(
+                "/api/v1/liquidation/heatmap",
+                Some(&heatmap)
+            ))
+            .1,
+            "must be at least 1h, got `30m`"
+        );
+        assert!(LiquidationHeatmapOptions::new()
+            .window(crate::generated::LiquidationHeatmapWindow::_1h)
+            .validate()
+            .is_ok());
+
+        let parameters = BTreeMap::from([("limit".to_string(), "lots".to_string())]);
+        assert_eq!(
+            reason(check_parameters(
+                "/api/v1/cex/token/updates",
+                Some(&parameters)
+            ))
+            .1,
+            "expected an integer, got `lots`"
+        );
+        assert!(check_parameters("/api/v1/unconstrained", Some(&parameters)).is_ok());
+        assert!(check_parameters("/api/v1/cex/token/updates", None).is_ok());
+    }
+}
@@ -0,0 +1,84 @@
+//! Integration test for [`datamaxi::api::Client::arbitrage`]: premium rows,
+//! the fee schedule of each exchange and the wallet status of each spot
+//! asset are fetched from a mock server and joined into ranked routes.
+
+use datamaxi::api::ClientBuilder;
+use datamaxi::arbitrage::{Arbitrage, Transfer};
+use datamaxi::PremiumOptions;
+use mockito::{Matcher, Mock, ServerGuard};
+
+fn get(server: &mut ServerGuard, path: &str, query: Matcher, body: &str) -> Mock {
+    server
+        .mock("GET", path)
+        .match_query(query)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(body)
+        .expect(1)
+        .create()
+}
+
+fn fees(exchange: &str, quote: &str, taker: f64) -> String {
+    format!(
+        r#"[{{"base":"BTC","exchange":"{exchange}","quote":"{quote}","symbol":"BTC-{quote}","spot_take_fee":{taker}}}]"#
+    )
+}
+
+#[tokio::test]
+async fn arbitrage_joins_premium_fees_and_wallets() {
+    let mut server = mockito::Server::new_async().await;
+    let premium = r#"{"data":[{"detail":{
+        "bid":"bitcoin","d":0,"pdp":1.5,
+        "se":"binance","sm":"spot","sb":"BTC","sq":"USDT","st":0,"sp":100.0,
+        "te":"upbit","tm":"spot","tb":"BTC","tq":"KRW","tt":0,"tp":101.5}}],
+        "limit":10,"page":1,"sort":"desc","total":1}"#;
+    let wallets = r#"[
+        {"currency":"BTC","deposit_message":"","deposit_state":"s","exchange":"binance",
+         "network":"BTC","updated_at":0,"withdraw_message":"","withdraw_state":"s"},
+        {"currency":"BTC","deposit_message":"","deposit_state":"s","exchange":"upbit",
+         "network":"BTC","updated_at":0,"withdraw_message":"","withdraw_state":"s"}]"#;
+    let mocks = [
+        get(&mut server, "/api/v1/premium", Matcher::Any, premium),
+        get(
+            &mut server,
+            "/api/v1/cex/fees",
+            Matcher::UrlEncoded("exchange".into(), "binance".into()),
+            &fees("binance", "USDT", 0.001),
+        ),
+        get(
+            &mut server,
+            "/api/v1/cex/fees",
+            Matcher::UrlEncoded("exchange".into(), "upbit".into()),
+            &fees("upbit", "KRW", 0.0005),
+        ),
+        get(
+            &mut server,
+            "/api/v1/wallet-status",
+            Matcher::UrlEncoded("asset".into(), "BTC".into()),
+            wallets,
+        ),
+    ];
+
+    let ranked = ClientBuilder::new()
+        .api_key("test-api-key")
+        .base_url(server.url())
+        .build()
+        .expect("mock client builds")
+        .arbitrage(
+            PremiumOptions::new().source_exchange("binance"),
+            &Arbitrage::new(1_000.0),
+        )
+        .await
+        .expect("arbitrage evaluates");
+
+    for mock in &mocks {
+        mock.assert();
+    }
+    assert_eq!(ranked.len(), 1);
+    let route = &ranked[0];
+    assert_eq!(route.source.taker_fee, 0.001);
+    assert_eq!(route.target.taker_fee, 0.0005);
+    assert!((route.net_edge_pct - (0.999 * 1.015 * 0.9995 - 1.0) * 100.0).abs() < 1e-12);
+    // The API documents no wallet state values, so none is read as open.
+    assert_eq!(route.transfer, Transfer::Unknown);
+}
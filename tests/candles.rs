@@ -0,0 +1,70 @@
+//! Integration tests for candle gap repair
+//! ([`datamaxi::CexCandle::refetch_missing`]).
+//!
+//! A series with a hole is repaired through a mock server: exactly one
+//! request is made for the missing run, narrowed to its `from`/`to` (unix
+//! seconds) at the series interval, and only candles inside that run are
+//! merged back.
+
+use datamaxi::api::ClientBuilder;
+use datamaxi::candles::CandleSeries;
+use datamaxi::{CexCandleInterval, CexCandleOptions, CexCandleView};
+use mockito::Matcher;
+
+const HOUR: i64 = 3_600_000;
+
+fn candle(timestamp: i64) -> CexCandleView {
+    CexCandleView {
+        close: 1.0,
+        timestamp,
+        high: 1.0,
+        low: 1.0,
+        open: 1.0,
+        volume: 1.0,
+    }
+}
+
+#[tokio::test]
+async fn refetch_missing_requests_only_the_gap() {
+    let mut server = mockito::Server::new_async().await;
+
+    let mock = server
+        .mock("GET", "/api/v1/cex/candle")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("exchange".into(), "binance".into()),
+            Matcher::UrlEncoded("symbol".into(), "BTC-USDT".into()),
+            Matcher::UrlEncoded("interval".into(), "1h".into()),
+            Matcher::UrlEncoded("from".into(), "3600".into()),
+            Matcher::UrlEncoded("to".into(), "7200".into()),
+        ]))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(format!(
+            r#"{{"currency":"USD","exchange":"binance","interval":"1h","market":"spot","symbol":"BTC-USDT",
+                "data":[{{"c":1,"d":{HOUR},"h":1,"l":1,"o":1,"v":1}},{{"c":1,"d":{},"h":1,"l":1,"o":1,"v":1}},
+                        {{"c":1,"d":{},"h":1,"l":1,"o":1,"v":1}}]}}"#,
+            2 * HOUR,
+            9 * HOUR
+        ))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let candle_api = ClientBuilder::new()
+        .api_key("test-api-key")
+        .base_url(server.url())
+        .build()
+        .expect("mock client builds")
+        .cex_candle();
+    let mut series = CandleSeries::new(CexCandleInterval::_1h, vec![candle(0), candle(3 * HOUR)]);
+
+    let recovered = candle_api
+        .refetch_missing("binance", "BTC-USDT", CexCandleOptions::new(), &mut series)
+        .await
+        .expect("refetch ok");
+
+    assert_eq!(recovered, 2);
+    assert!(series.inspect().is_clean());
+    assert_eq!(series.candles().len(), 4);
+    mock.assert_async().await;
+}
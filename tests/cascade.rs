@@ -0,0 +1,102 @@
+//! Integration tests for [`datamaxi::api::Client::poll_cascades`]: each poll
+//! fetches the liquidation feed once, events a previous poll returned are
+//! not counted again, and feed rows carrying the order side (`sell`, `buy`)
+//! count as long and short liquidations.
+
+use datamaxi::api::ClientBuilder;
+use datamaxi::cascade::CascadeDetector;
+use datamaxi::LiquidationFeedOptions;
+use mockito::Matcher;
+
+const T: i64 = 1_735_689_600_000;
+
+#[tokio::test]
+async fn poll_cascades_alerts_once_on_overlapping_feeds() {
+    let mut server = mockito::Server::new_async().await;
+    let event = |timestamp: i64, side: &str, volume_usd: f64| {
+        format!(
+            r#"{{"base":"BTC","exchange":"binance","price":100000.0,"quote":"USDT","side":"{side}","symbol":"BTCUSDT","timestamp":{timestamp},"tokenId":"bitcoin","volume":1.0,"volumeUsd":{volume_usd}}}"#
+        )
+    };
+    let body = format!(
+        r#"{{"data":[{},{}]}}"#,
+        event(T + 2_000, "long", 700_000.0),
+        event(T + 1_000, "short", 400_000.0)
+    );
+    let mock = server
+        .mock("GET", "/api/v1/liquidation/feed")
+        .match_query(Matcher::UrlEncoded("base".into(), "BTC".into()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(body)
+        .expect(2)
+        .create();
+
+    let client = ClientBuilder::new()
+        .api_key("test-api-key")
+        .base_url(server.url())
+        .build()
+        .expect("mock client builds");
+    let mut detector = CascadeDetector::new();
+    let options = || LiquidationFeedOptions::new().base("BTC");
+
+    let first = client
+        .poll_cascades(&mut detector, options())
+        .await
+        .expect("first poll succeeds");
+    let second = client
+        .poll_cascades(&mut detector, options())
+        .await
+        .expect("second poll succeeds");
+
+    mock.assert();
+    assert_eq!(first.len(), 1);
+    assert_eq!(first[0].total_usd, 1_100_000.0);
+    assert_eq!(first[0].events, 2);
+    assert!(second.is_empty());
+}
+
+#[tokio::test]
+async fn poll_cascades_reads_order_sides_from_the_feed() {
+    let mut server = mockito::Server::new_async().await;
+    // Feed rows carry the liquidation order side: `sell` closes a long,
+    // `buy` a short.
+    let body = format!(
+        r#"{{"data":[
+            {{"base":"ETH","exchange":"bybit","price":3312.5,"priceUsd":3312.5,"quote":"USDT","side":"sell","symbol":"ETHUSDT","timestamp":{},"tokenId":"ethereum","volume":150.0,"volumeUsd":496875.0}},
+            {{"base":"ETH","exchange":"binance","price":3310.0,"priceUsd":3310.0,"quote":"USDT","side":"Sell","symbol":"ETHUSDT","timestamp":{},"tokenId":"ethereum","volume":120.0}},
+            {{"base":"ETH","exchange":"okx","price":3315.0,"quote":"USDT","side":"buy","symbol":"ETH-USDT-SWAP","timestamp":{},"tokenId":"ethereum","volume":40.0,"volumeUsd":132600.0}},
+            {{"base":"ETH","exchange":"okx","price":3315.0,"quote":"USDT","side":"net","symbol":"ETH-USDT-SWAP","timestamp":{},"tokenId":"ethereum","volume":1.0,"volumeUsd":3315.0}}
+        ]}}"#,
+        T + 4_000,
+        T + 3_000,
+        T + 2_000,
+        T + 1_000
+    );
+    let mock = server
+        .mock("GET", "/api/v1/liquidation/feed")
+        .match_query(Matcher::UrlEncoded("base".into(), "ETH".into()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(body)
+        .expect(1)
+        .create();
+
+    let client = ClientBuilder::new()
+        .api_key("test-api-key")
+        .base_url(server.url())
+        .build()
+        .expect("mock client builds");
+    let mut detector = CascadeDetector::new();
+    let alerts = client
+        .poll_cascades(&mut detector, LiquidationFeedOptions::new().base("ETH"))
+        .await
+        .expect("poll succeeds");
+
+    mock.assert();
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].long_usd, 496_875.0 + 120.0 * 3310.0);
+    assert_eq!(alerts[0].short_usd, 132_600.0);
+    assert_eq!(alerts[0].events, 3);
+    assert_eq!(detector.unknown_sides(), 1);
+}
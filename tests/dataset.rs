@@ -0,0 +1,154 @@
+//! Integration tests for the incremental candle store
+//! ([`datamaxi::dataset::CandleSync`] / [`datamaxi::dataset::sync::CandleSync`]).
+//!
+//! A series is synced through a mock server into a scratch directory: only
+//! closed candles after the watermark are requested, in windows of
+//! `candles_per_request`, a hole in the response is re-fetched, and the
+//! watermark advances to the newest stored candle.
+
+use datamaxi::api::ClientBuilder;
+use datamaxi::dataset::{CandleStore, CandleSync, CandleSyncOptions, SeriesKey};
+use datamaxi::{CexCandleInterval, CexCandleMarket};
+use mockito::{Matcher, Mock, ServerGuard};
+use std::path::PathBuf;
+
+const HOUR: i64 = 3_600_000;
+
+/// A fresh, empty directory under the system temp dir.
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("datamaxi-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+fn key() -> SeriesKey {
+    SeriesKey::new(
+        "binance",
+        "BTC-USDT",
+        CexCandleMarket::Spot,
+        CexCandleInterval::_1h,
+    )
+}
+
+fn candle_body(timestamps: &[i64]) -> String {
+    let data: Vec<String> = timestamps
+        .iter()
+        .map(|t| format!(r#"{{"c":1,"d":{t},"h":1,"l":1,"o":1,"v":1}}"#))
+        .collect();
+    format!(
+        r#"{{"currency":"USD","exchange":"binance","interval":"1h","market":"spot","symbol":"BTC-USDT","data":[{}]}}"#,
+        data.join(",")
+    )
+}
+
+/// Registers a one-shot candle mock for the `from`/`to` window (unix
+/// seconds).
+fn window(server: &mut ServerGuard, from: i64, to: i64, timestamps: &[i64]) -> Mock {
+    server
+        .mock("GET", "/api/v1/cex/candle")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("market".into(), "spot".into()),
+            Matcher::UrlEncoded("interval".into(), "1h".into()),
+            Matcher::UrlEncoded("from".into(), from.to_string()),
+            Matcher::UrlEncoded("to".into(), to.to_string()),
+        ]))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(candle_body(timestamps))
+        .expect(1)
+        .create()
+}
+
+#[tokio::test]
+async fn sync_backfills_repairs_and_resumes_from_watermark() {
+    let mut server = mockito::Server::new_async().await;
+    let first = window(&mut server, 0, 7200, &[0, 2 * HOUR]);
+    let repair = window(&mut server, 3600, 3600, &[HOUR]);
+    let second = window(&mut server, 10_800, 14_400, &[3 * HOUR, 4 * HOUR]);
+
+    let candle = ClientBuilder::new()
+        .api_key("test-api-key")
+        .base_url(server.url())
+        .build()
+        .expect("mock client builds")
+        .cex_candle();
+    let store = CandleStore::open(scratch_dir("async")).expect("store opens");
+    // Candles closed by 5h + 10ms: the last one opens at 4h.
+    let options = CandleSyncOptions::new(0)
+        .until(5 * HOUR + 10)
+        .candles_per_request(3);
+    let engine = CandleSync::new(candle.clone(), store.clone(), options);
+
+    let report = engine.sync_series(&key()).await.expect("sync ok");
+
+    assert_eq!(report.appended, 5);
+    assert_eq!(report.repaired, 1);
+    assert!(report.missing.is_empty());
+    assert_eq!(report.watermark, Some(4 * HOUR));
+    assert_eq!(store.watermark(&key()).expect("readable"), Some(4 * HOUR));
+    first.assert();
+    repair.assert();
+    second.assert();
+
+    // The next run only asks for what closed since.
+    let next = window(&mut server, 18_000, 18_000, &[5 * HOUR]);
+    let engine = CandleSync::new(
+        candle,
+        store.clone(),
+        CandleSyncOptions::new(0).until(6 * HOUR),
+    );
+    let report = engine.sync_series(&key()).await.expect("sync ok");
+
+    assert_eq!(report.appended, 1);
+    let stored: Vec<i64> = store
+        .load(&key())
+        .expect("loads")
+        .iter()
+        .map(|candle| candle.timestamp)
+        .collect();
+    assert_eq!(stored, [0, HOUR, 2 * HOUR, 3 * HOUR, 4 * HOUR, 5 * HOUR]);
+    next.assert();
+    let _ = std::fs::remove_dir_all(store.root());
+}
+
+/// A hole right after the watermark is repaired too.
+#[cfg(feature = "sync")]
+#[test]
+fn blocking_sync_repairs_hole_after_watermark() {
+    let mut server = mockito::Server::new();
+    let fetch = window(&mut server, 3600, 7200, &[2 * HOUR]);
+    let repair = window(&mut server, 3600, 3600, &[HOUR]);
+
+    let candle = datamaxi::api::sync::ClientBuilder::new()
+        .api_key("test-api-key")
+        .base_url(server.url())
+        .build()
+        .expect("mock blocking client builds")
+        .cex_candle();
+    let store = CandleStore::open(scratch_dir("blocking")).expect("store opens");
+    let seed = store.load(&key()).expect("empty store loads");
+    assert!(seed.is_empty());
+    store
+        .append(
+            &key(),
+            &[datamaxi::CexCandleView {
+                timestamp: 0,
+                ..Default::default()
+            }],
+        )
+        .expect("seeded");
+
+    let engine = datamaxi::dataset::sync::CandleSync::new(
+        candle,
+        store.clone(),
+        CandleSyncOptions::new(0).until(3 * HOUR),
+    );
+    let results = engine.sync_all(&[key()]);
+
+    let report = results[0].1.as_ref().expect("sync ok");
+    assert_eq!((report.appended, report.repaired), (2, 1));
+    assert_eq!(report.watermark, Some(2 * HOUR));
+    fetch.assert();
+    repair.assert();
+    let _ = std::fs::remove_dir_all(store.root());
+}
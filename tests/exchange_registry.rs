@@ -0,0 +1,110 @@
+//! Integration test for [`datamaxi::api::Client::exchange_registry`]: the
+//! registry is assembled from every `exchanges()` listing served by a mock
+//! server, and then answers capability queries offline.
+
+use datamaxi::api::ClientBuilder;
+use datamaxi::exchange::{DataProduct, Exchange};
+use mockito::{Matcher, Mock, ServerGuard};
+
+fn listing(server: &mut ServerGuard, path: &str, market: Option<&str>, body: &str) -> Mock {
+    let query = match market {
+        Some(market) => Matcher::UrlEncoded("market".into(), market.into()),
+        None => Matcher::Any,
+    };
+    server
+        .mock("GET", path)
+        .match_query(query)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(body)
+        .expect(1)
+        .create()
+}
+
+#[tokio::test]
+async fn registry_is_built_from_every_exchanges_listing() {
+    let mut server = mockito::Server::new_async().await;
+    let mocks = [
+        listing(
+            &mut server,
+            "/api/v1/cex/candle/exchanges",
+            Some("spot"),
+            r#"["binance","upbit"]"#,
+        ),
+        listing(
+            &mut server,
+            "/api/v1/cex/candle/exchanges",
+            Some("futures"),
+            r#"["binance"]"#,
+        ),
+        listing(
+            &mut server,
+            "/api/v1/funding-rate/exchanges",
+            None,
+            r#"["binance","bybit"]"#,
+        ),
+        listing(
+            &mut server,
+            "/api/v1/premium/exchanges",
+            None,
+            r#"["binance","upbit"]"#,
+        ),
+        listing(
+            &mut server,
+            "/api/v1/ticker/exchanges",
+            Some("spot"),
+            r#"["upbit"]"#,
+        ),
+        listing(
+            &mut server,
+            "/api/v1/ticker/exchanges",
+            Some("futures"),
+            r#"[]"#,
+        ),
+        listing(
+            &mut server,
+            "/api/v1/cex/fees/exchanges",
+            None,
+            r#"["binance"]"#,
+        ),
+        listing(
+            &mut server,
+            "/api/v1/wallet-status/exchanges",
+            None,
+            r#"["upbit"]"#,
+        ),
+    ];
+
+    let registry = ClientBuilder::new()
+        .api_key("test-api-key")
+        .base_url(server.url())
+        .build()
+        .expect("mock client builds")
+        .exchange_registry()
+        .await
+        .expect("registry builds");
+
+    for mock in &mocks {
+        mock.assert();
+    }
+    assert_eq!(
+        registry
+            .exchanges()
+            .map(Exchange::as_str)
+            .collect::<Vec<_>>(),
+        ["binance", "bybit", "upbit"]
+    );
+    assert_eq!(
+        registry.products("upbit"),
+        [
+            DataProduct::SpotCandle,
+            DataProduct::Premium,
+            DataProduct::SpotTicker,
+            DataProduct::WalletStatus,
+        ]
+    );
+    assert!(registry
+        .exchanges_for(DataProduct::FuturesTicker)
+        .is_empty());
+    assert!(registry.require("bybit", DataProduct::SpotCandle).is_err());
+}
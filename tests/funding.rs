@@ -0,0 +1,60 @@
+//! Integration test for [`datamaxi::api::Client::funding_spreads`]: the
+//! latest rate is fetched from every funding exchange, and exchanges not
+//! listing the symbol are left out of the matrix.
+
+use datamaxi::api::ClientBuilder;
+use mockito::Matcher;
+
+#[tokio::test]
+async fn funding_spreads_skips_exchanges_without_the_symbol() {
+    let mut server = mockito::Server::new_async().await;
+    let latest = |exchange: &str, rate: f64, hours: i64| {
+        format!(
+            r#"{{"b":"BTC","d":0,"e":"{exchange}","f":{rate},"i":{hours},"id":"bitcoin","q":"USDT","s":"BTC-USDT"}}"#
+        )
+    };
+    let mocks = [
+        server
+            .mock("GET", "/api/v1/funding-rate/exchanges")
+            .with_status(200)
+            .with_body(r#"["binance","bybit","okx"]"#)
+            .expect(1)
+            .create(),
+        server
+            .mock("GET", "/api/v1/funding-rate/latest")
+            .match_query(Matcher::UrlEncoded("exchange".into(), "binance".into()))
+            .with_status(200)
+            .with_body(latest("binance", 0.0001, 8))
+            .expect(1)
+            .create(),
+        server
+            .mock("GET", "/api/v1/funding-rate/latest")
+            .match_query(Matcher::UrlEncoded("exchange".into(), "bybit".into()))
+            .with_status(200)
+            .with_body(latest("bybit", 0.0001, 1))
+            .expect(1)
+            .create(),
+        server
+            .mock("GET", "/api/v1/funding-rate/latest")
+            .match_query(Matcher::UrlEncoded("exchange".into(), "okx".into()))
+            .with_status(404)
+            .expect(1)
+            .create(),
+    ];
+
+    let matrix = ClientBuilder::new()
+        .api_key("test-api-key")
+        .base_url(server.url())
+        .build()
+        .expect("mock client builds")
+        .funding_spreads("btc", "usdt")
+        .await
+        .expect("matrix builds");
+
+    for mock in &mocks {
+        mock.assert();
+    }
+    assert_eq!(matrix.venues.len(), 2);
+    let spread = matrix.spread("bybit", "binance").expect("both listed");
+    assert!((spread - 0.0001 * 8760.0 * (1.0 - 1.0 / 8.0)).abs() < 1e-12);
+}
@@ -0,0 +1,65 @@
+//! Integration test for [`datamaxi::kimchi::KimchiPoller`]: one round
+//! fetches the `USD-KRW` rate and a ticker per exchange, Korean legs in KRW
+//! and global legs in USD, from a mock server.
+
+use datamaxi::api::ClientBuilder;
+use datamaxi::kimchi::{KimchiPoller, KimchiTracker};
+use mockito::{Matcher, Mock, ServerGuard};
+use std::time::Duration;
+
+const T: i64 = 1_735_689_600_000;
+
+fn ticker(server: &mut ServerGuard, exchange: &str, currency: &str, price: f64) -> Mock {
+    server
+        .mock("GET", "/api/v1/ticker")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("exchange".into(), exchange.into()),
+            Matcher::UrlEncoded("currency".into(), currency.into()),
+        ]))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(format!(
+            r#"{{"currency":"{currency}","market":"spot","data":
+                {{"b":"BTC","d":{T},"e":"{exchange}","m":"spot","p":{price},"q":"","s":""}}}}"#
+        ))
+        .expect(1)
+        .create()
+}
+
+#[tokio::test]
+async fn poller_converts_the_global_leg_at_the_forex_rate() {
+    let mut server = mockito::Server::new_async().await;
+    let mocks = [
+        server
+            .mock("GET", "/api/v1/forex")
+            .match_query(Matcher::UrlEncoded("symbol".into(), "USD-KRW".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(r#"{{"d":{T},"r":1400.0,"s":"USD-KRW"}}"#))
+            .expect(1)
+            .create(),
+        ticker(&mut server, "upbit", "KRW", 147_000.0),
+        ticker(&mut server, "binance", "USD", 100.0),
+    ];
+
+    let client = ClientBuilder::new()
+        .api_key("test-api-key")
+        .base_url(server.url())
+        .build()
+        .expect("mock client builds");
+    let tracker = KimchiTracker::new()
+        .asset("btc")
+        .korean("upbit", "KRW")
+        .global("binance", "USDT");
+    let mut poller = KimchiPoller::new(client, tracker, Duration::from_secs(60));
+
+    let points = poller.next_update().await.expect("round succeeds");
+
+    for mock in &mocks {
+        mock.assert();
+    }
+    assert_eq!(points.len(), 1);
+    assert_eq!(points[0].usd_krw, 1_400.0);
+    assert!((points[0].premium_pct - 5.0).abs() < 1e-9);
+    assert_eq!(poller.tracker().series(&points[0].route()).len(), 1);
+}
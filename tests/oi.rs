@@ -0,0 +1,122 @@
+//! Integration test for [`datamaxi::api::Client::oi_report`]: the OI stats,
+//! futures volumes, OI summary, aggregated history and candles of one base
+//! asset are fetched once each and joined.
+
+use datamaxi::api::{ClientBuilder, Error};
+use datamaxi::OpenInterestHistoryAggregatedInterval;
+use mockito::{Matcher, Mock, ServerGuard};
+
+const T: i64 = 1_735_689_600_000;
+const HOUR: i64 = 3_600_000;
+
+fn mocks(server: &mut ServerGuard) -> [Mock; 5] {
+    let mut json = |path: &str, query: Matcher, body: String| {
+        server
+            .mock("GET", path)
+            .match_query(query)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(body)
+            .expect(1)
+            .create()
+    };
+    [
+        json(
+            "/api/v1/cex/symbol/oi-stats",
+            Matcher::UrlEncoded("base".into(), "BTC".into()),
+            format!(
+                r#"[{{"b":"BTC","e":"binance","m":"futures","q":"USDT","open_interest":10.0,"open_interest_usd":1000.0,"token_id":"bitcoin","ts":{T}}}]"#
+            ),
+        ),
+        json(
+            "/api/v1/cex/symbol/volume",
+            Matcher::UrlEncoded("market".into(), "futures".into()),
+            format!(
+                r#"[{{"b":"BTC","e":"binance","m":"futures","q":"USDT","quote_volume":4000.0,"ts":{T},"volume":40.0}}]"#
+            ),
+        ),
+        json(
+            "/api/v1/open-interest/summary",
+            Matcher::Any,
+            r#"{"exchanges":[],"generatedAt":0,"grandTotal":4000.0,"tokens":[{"base":"BTC","icon":"","name":"Bitcoin","openInterestUsd":1000.0,"symbol":"BTC","tokenId":"bitcoin","venues":1}],"totalTokens":1}"#
+                .to_string(),
+        ),
+        json(
+            "/api/v1/open-interest/history-aggregated",
+            Matcher::AllOf(vec![
+                Matcher::UrlEncoded("token_id".into(), "bitcoin".into()),
+                Matcher::UrlEncoded("interval".into(), "1h".into()),
+            ]),
+            format!(
+                r#"{{"data":{{"binance":[{{"t":{T},"v":900.0}},{{"t":{},"v":1000.0}}]}},"exchange_url":{{}},"token":{{"icon":"","id":"bitcoin","name":"Bitcoin","symbol":"BTC"}}}}"#,
+                T + HOUR
+            ),
+        ),
+        json(
+            "/api/v1/cex/candle",
+            Matcher::AllOf(vec![
+                Matcher::UrlEncoded("symbol".into(), "BTC-USDT".into()),
+                Matcher::UrlEncoded("interval".into(), "1h".into()),
+                Matcher::UrlEncoded("from".into(), (T / 1000).to_string()),
+                Matcher::UrlEncoded("to".into(), ((T + HOUR) / 1000).to_string()),
+            ]),
+            format!(
+                r#"{{"currency":"USD","exchange":"binance","interval":"1h","market":"futures","symbol":"BTC-USDT","data":[
+                    {{"c":100.0,"d":{T},"h":0,"l":0,"o":0,"v":1.0}},{{"c":95.0,"d":{},"h":0,"l":0,"o":0,"v":2.0}}]}}"#,
+                T + HOUR
+            ),
+        ),
+    ]
+}
+
+#[tokio::test]
+async fn oi_report_aligns_history_with_candles() {
+    let mut server = mockito::Server::new_async().await;
+    let mocks = mocks(&mut server);
+
+    let report = ClientBuilder::new()
+        .api_key("test-api-key")
+        .base_url(server.url())
+        .build()
+        .expect("mock client builds")
+        .oi_report(
+            "btc",
+            "binance",
+            OpenInterestHistoryAggregatedInterval::_1h,
+            |point| point["v"].as_f64(),
+        )
+        .await
+        .expect("report builds");
+
+    for mock in &mocks {
+        mock.assert();
+    }
+    assert_eq!(report.token_id, "bitcoin");
+    assert_eq!(report.market_share, Some(0.25));
+    assert_eq!(report.snapshots[0].oi_to_volume, Some(0.25));
+    assert_eq!(report.series.rows.len(), 2);
+    assert_eq!(report.series.rows[1].oi_delta, Some(100.0));
+    assert_eq!(report.series.divergences(5.0, 1.0).len(), 1);
+}
+
+#[tokio::test]
+async fn oi_report_fails_when_no_point_value_is_readable() {
+    let mut server = mockito::Server::new_async().await;
+    let _mocks = mocks(&mut server);
+
+    let error = ClientBuilder::new()
+        .api_key("test-api-key")
+        .base_url(server.url())
+        .build()
+        .expect("mock client builds")
+        .oi_report(
+            "btc",
+            "binance",
+            OpenInterestHistoryAggregatedInterval::_1h,
+            |point| point["oi"].as_f64(),
+        )
+        .await
+        .expect_err("no value is readable");
+
+    assert!(matches!(error, Error::InvalidArgument { ref argument, .. } if argument == "value"));
+}
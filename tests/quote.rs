@@ -0,0 +1,147 @@
+//! Integration tests for [`datamaxi::quote::QuotePoller`] and
+//! [`datamaxi::api::Client::consolidated_quote`]: the exchange list is
+//! fetched once, every exchange's ticker once per round, exchanges not
+//! listing the symbol are left out, and tickers in another currency are
+//! converted at the forex rate.
+
+use datamaxi::api::ClientBuilder;
+use datamaxi::fx::RateTable;
+use datamaxi::quote::{OrderSide, QuotePoller};
+use datamaxi::{TickerCurrency, TickerMarket};
+use mockito::{Matcher, Mock, ServerGuard};
+use std::time::Duration;
+
+const T: i64 = 1_735_689_600_000;
+
+fn ticker(server: &mut ServerGuard, exchange: &str, bid: f64, ask: f64, hits: usize) -> Mock {
+    server
+        .mock("GET", "/api/v1/ticker")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("exchange".into(), exchange.into()),
+            Matcher::UrlEncoded("currency".into(), "USD".into()),
+        ]))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(format!(
+            r#"{{"currency":"USD","market":"spot","data":
+                {{"b":"BTC","d":{T},"e":"{exchange}","m":"spot","hb":{bid},"la":{ask},"q":"USDT","s":"BTC-USDT"}}}}"#
+        ))
+        .expect(hits)
+        .create()
+}
+
+#[tokio::test]
+async fn poller_reuses_the_exchange_list() {
+    let mut server = mockito::Server::new_async().await;
+    let mocks = [
+        server
+            .mock("GET", "/api/v1/ticker/exchanges")
+            .match_query(Matcher::UrlEncoded("market".into(), "spot".into()))
+            .with_status(200)
+            .with_body(r#"["binance","okx","upbit"]"#)
+            .expect(1)
+            .create(),
+        ticker(&mut server, "binance", 99.0, 100.0, 2),
+        ticker(&mut server, "okx", 99.5, 100.5, 2),
+        server
+            .mock("GET", "/api/v1/ticker")
+            .match_query(Matcher::UrlEncoded("exchange".into(), "upbit".into()))
+            .with_status(404)
+            .expect(2)
+            .create(),
+    ];
+
+    let client = ClientBuilder::new()
+        .api_key("test-api-key")
+        .base_url(server.url())
+        .build()
+        .expect("mock client builds");
+    let mut poller = QuotePoller::new(
+        client,
+        "BTC-USDT",
+        TickerMarket::Spot,
+        TickerCurrency::USD,
+        Duration::from_millis(1),
+    );
+    poller.next_update().await.expect("first round succeeds");
+    let quote = poller.next_update().await.expect("second round succeeds");
+
+    assert_eq!(quote.venues.len(), 2);
+    assert_eq!(
+        quote.route(OrderSide::Buy).unwrap().exchange.as_str(),
+        "binance"
+    );
+    assert_eq!(
+        quote.route(OrderSide::Sell).unwrap().exchange.as_str(),
+        "okx"
+    );
+    for mock in &mocks {
+        mock.assert();
+    }
+}
+
+#[tokio::test]
+async fn consolidated_quote_converts_venues_in_another_currency() {
+    let mut server = mockito::Server::new_async().await;
+    let mocks = [
+        server
+            .mock("GET", "/api/v1/ticker/exchanges")
+            .match_query(Matcher::UrlEncoded("market".into(), "spot".into()))
+            .with_status(200)
+            .with_body(r#"["binance","upbit"]"#)
+            .expect(1)
+            .create(),
+        ticker(&mut server, "binance", 99.0, 100.0, 1),
+        server
+            .mock("GET", "/api/v1/ticker")
+            .match_query(Matcher::UrlEncoded("exchange".into(), "upbit".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(
+                r#"{{"currency":"KRW","market":"spot","data":
+                    {{"b":"BTC","d":{T},"e":"upbit","m":"spot","hb":130000.0,"la":132600.0,"ld":2600000.0,"q":"USDT","s":"BTC-USDT"}}}}"#
+            ))
+            .expect(1)
+            .create(),
+        server
+            .mock("GET", "/api/v1/forex")
+            .match_query(Matcher::UrlEncoded("symbol".into(), "USD-KRW".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(format!(r#"{{"d":{T},"r":1300.0,"s":"USD-KRW"}}"#))
+            .expect(1)
+            .create(),
+    ];
+
+    let client = ClientBuilder::new()
+        .api_key("test-api-key")
+        .base_url(server.url())
+        .build()
+        .expect("mock client builds");
+    let mut rates = RateTable::new();
+    let quote = client
+        .consolidated_quote(
+            "BTC-USDT",
+            TickerMarket::Spot,
+            TickerCurrency::USD,
+            &mut rates,
+        )
+        .await
+        .expect("quote succeeds");
+
+    for mock in &mocks {
+        mock.assert();
+    }
+    assert!(quote.excluded.is_empty());
+    let upbit = &quote.venues[1];
+    assert_eq!(upbit.exchange.as_str(), "upbit");
+    assert_eq!((upbit.bid, upbit.ask), (Some(100.0), Some(102.0)));
+    assert_eq!(upbit.bid_depth, Some(2_000.0));
+    let rate = upbit.rate.as_ref().expect("converted client-side");
+    assert!(rate.inverted && rate.rate == 1.0 / 1300.0);
+    assert_eq!(quote.venues[0].rate, None);
+    assert_eq!(
+        quote.route(OrderSide::Sell).unwrap().exchange.as_str(),
+        "upbit"
+    );
+}
@@ -0,0 +1,237 @@
+//! Integration tests for the time-range walker
+//! ([`datamaxi::api::Client::walk_time_range`] /
+//! [`datamaxi::api::sync::Client::walk_time_range`]).
+//!
+//! These drive real time-bounded envelopes (`IndexPriceResponse`, queried in
+//! unix seconds, and `OpenInterestHistoryAggregatedResponse`, queried in unix
+//! milliseconds) through a mock server and lock: one request per window with
+//! the window's `from`/`to`, boundary de-duplication, and resuming after the
+//! last timestamp seen. The typed entry points (`walk_index_price`, …) are
+//! checked to send the same `from`/`to` as the options they are given.
+
+use datamaxi::api::{Client, ClientBuilder, Error, TimeRange};
+use datamaxi::{
+    IndexPriceInterval, IndexPriceOptions, IndexPriceResponse,
+    OpenInterestHistoryAggregatedOptions, OpenInterestHistoryAggregatedResponse,
+};
+use mockito::Matcher;
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const API_KEY: &str = "test-api-key";
+
+fn mock_client(base_url: String) -> Client {
+    ClientBuilder::new()
+        .api_key(API_KEY)
+        .base_url(base_url)
+        .build()
+        .expect("mock client builds")
+}
+
+/// An `IndexPriceResponse` body with one item per millisecond timestamp.
+fn index_price_body(timestamps: &[i64]) -> String {
+    let data: Vec<String> = timestamps
+        .iter()
+        .map(|t| format!(r#"{{"price":1.0,"timestamp":{t},"volume":2.0}}"#))
+        .collect();
+    format!(r#"{{"data":[{}]}}"#, data.join(","))
+}
+
+fn asset_params() -> BTreeMap<String, String> {
+    let mut params = BTreeMap::new();
+    params.insert("asset".to_string(), "BTC".to_string());
+    params
+}
+
+/// A two-window range issues exactly two requests, with `from`/`to` in unix
+/// seconds, and the bucket on the shared boundary is yielded once.
+#[tokio::test]
+async fn walk_time_range_requests_each_window_and_dedupes_boundary() {
+    let mut server = mockito::Server::new_async().await;
+
+    let first = server
+        .mock("GET", "/api/v1/index-price")
+        .match_header("X-DTMX-APIKEY", API_KEY)
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("asset".into(), "BTC".into()),
+            Matcher::UrlEncoded("from".into(), "0".into()),
+            Matcher::UrlEncoded("to".into(), "3600".into()),
+        ]))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(index_price_body(&[0, 1_800_000, 3_600_000]))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let second = server
+        .mock("GET", "/api/v1/index-price")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("from".into(), "3600".into()),
+            Matcher::UrlEncoded("to".into(), "5400".into()),
+        ]))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(index_price_body(&[3_600_000, 5_400_000]))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = mock_client(server.url());
+    let range = TimeRange::new(0, 5_400_000, Duration::from_secs(3600));
+    let mut windows =
+        client.walk_time_range::<IndexPriceResponse>("/api/v1/index-price", asset_params(), range);
+
+    let mut timestamps = Vec::new();
+    while let Some(items) = windows.next_window().await.expect("window ok") {
+        timestamps.extend(items.into_iter().map(|item| item.timestamp));
+    }
+
+    assert_eq!(timestamps, [0, 1_800_000, 3_600_000, 5_400_000]);
+    assert_eq!(windows.last_timestamp(), Some(5_400_000));
+    first.assert_async().await;
+    second.assert_async().await;
+}
+
+/// Resuming after a timestamp starts the walk there and skips items at or
+/// before it; OI history is queried in unix milliseconds and de-duplicated
+/// per exchange.
+#[tokio::test]
+async fn walk_time_range_resumes_after_last_timestamp() {
+    let mut server = mockito::Server::new_async().await;
+
+    let mock = server
+        .mock("GET", "/api/v1/open-interest/history-aggregated")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("token_id".into(), "bitcoin".into()),
+            Matcher::UrlEncoded("from".into(), "2000".into()),
+            Matcher::UrlEncoded("to".into(), "3000".into()),
+        ]))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"data":{"binance":[{"t":2000},{"t":3000}],"bybit":[{"t":3000}]},"exchange_url":{},
+                "token":{"icon":"","id":"bitcoin","name":"Bitcoin","symbol":"BTC"}}"#,
+        )
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = mock_client(server.url());
+    let mut params = BTreeMap::new();
+    params.insert("token_id".to_string(), "bitcoin".to_string());
+    let range = TimeRange::new(0, 3_000, Duration::from_secs(10)).resume_after(2_000);
+    let mut windows = client.walk_time_range::<OpenInterestHistoryAggregatedResponse>(
+        "/api/v1/open-interest/history-aggregated",
+        params,
+        range,
+    );
+
+    let items = windows
+        .next_window()
+        .await
+        .expect("window ok")
+        .expect("one window");
+    let keys: Vec<(&str, i64)> = items
+        .iter()
+        .map(|p| (p.exchange.as_str(), p.timestamp))
+        .collect();
+    assert_eq!(keys, [("binance", 3_000), ("bybit", 3_000)]);
+    assert!(windows.next_window().await.expect("terminal ok").is_none());
+    mock.assert_async().await;
+}
+
+/// `walk_index_price` reads the seconds-based `from`/`to` set by `since` /
+/// `until` and forwards the options' interval.
+#[tokio::test]
+async fn walk_index_price_takes_the_range_from_options() {
+    let mut server = mockito::Server::new_async().await;
+
+    let mock = server
+        .mock("GET", "/api/v1/index-price")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("asset".into(), "BTC".into()),
+            Matcher::UrlEncoded("interval".into(), "1h".into()),
+            Matcher::UrlEncoded("from".into(), "3600".into()),
+            Matcher::UrlEncoded("to".into(), "7200".into()),
+        ]))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(index_price_body(&[3_600_000, 7_200_000]))
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = mock_client(server.url());
+    let options = IndexPriceOptions::new()
+        .interval(IndexPriceInterval::_1h)
+        .since(UNIX_EPOCH + Duration::from_secs(3_600))
+        .until(UNIX_EPOCH + Duration::from_secs(7_200));
+    let mut windows = client
+        .walk_index_price("BTC", options, Duration::from_secs(3_600))
+        .expect("range is set");
+
+    let items = windows
+        .next_window()
+        .await
+        .expect("window ok")
+        .expect("one window");
+    assert_eq!(items.len(), 2);
+    assert!(windows.next_window().await.expect("terminal ok").is_none());
+    mock.assert_async().await;
+}
+
+/// Options without a `from`/`to` can't be walked.
+#[test]
+fn typed_walks_require_both_bounds() {
+    let client = mock_client("http://127.0.0.1:9".to_string());
+    let open_ended = OpenInterestHistoryAggregatedOptions::new()
+        .since(SystemTime::now() - Duration::from_secs(60));
+
+    let err = client
+        .walk_open_interest_history("bitcoin", open_ended, Duration::from_secs(60))
+        .err()
+        .expect("`to` is unset");
+    assert!(matches!(err, Error::InvalidArgument { ref argument, .. } if argument == "to"));
+}
+
+/// The blocking mirror implements [`Iterator`], one `Result<Vec<_>>` per
+/// window.
+#[cfg(feature = "sync")]
+#[test]
+fn blocking_walk_time_range_iterates_windows() {
+    let mut server = mockito::Server::new();
+
+    let first = server
+        .mock("GET", "/api/v1/index-price")
+        .match_query(Matcher::UrlEncoded("from".into(), "0".into()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(index_price_body(&[0, 1_000]))
+        .expect(1)
+        .create();
+
+    let second = server
+        .mock("GET", "/api/v1/index-price")
+        .match_query(Matcher::UrlEncoded("from".into(), "1".into()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(index_price_body(&[]))
+        .expect(1)
+        .create();
+
+    let client = datamaxi::api::sync::ClientBuilder::new()
+        .api_key(API_KEY)
+        .base_url(server.url())
+        .build()
+        .expect("mock blocking client builds");
+    let range = TimeRange::new(0, 2_000, Duration::from_secs(1));
+    let windows: Vec<usize> = client
+        .walk_time_range::<IndexPriceResponse>("/api/v1/index-price", asset_params(), range)
+        .map(|window| window.expect("window ok").len())
+        .collect();
+
+    assert_eq!(windows, [2, 0]);
+    first.assert();
+    second.assert();
+}
@@ -0,0 +1,109 @@
+//! Integration test for [`datamaxi::Client::symbol_universe`]: every source
+//! is fetched once with the exchange filter, and a base asset the wallet
+//! service does not track keeps no wallet data.
+
+use datamaxi::api::ClientBuilder;
+use datamaxi::enums::CautionLevel;
+use datamaxi::universe::{RiskFlag, SymbolKey, UniverseBuilder};
+use mockito::{Matcher, Mock, ServerGuard};
+
+fn list(server: &mut ServerGuard, path: &str, body: &str) -> Mock {
+    server
+        .mock("GET", path)
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("exchange".into(), "upbit".into()),
+            Matcher::UrlEncoded("page".into(), "1".into()),
+        ]))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(body)
+        .expect(1)
+        .create()
+}
+
+#[tokio::test]
+async fn symbol_universe_joins_every_source() {
+    let mut server = mockito::Server::new_async().await;
+    let mut wallet = |asset: &str, status: usize, body: &str| {
+        server
+            .mock("GET", "/api/v1/wallet-status")
+            .match_query(Matcher::UrlEncoded("asset".into(), asset.into()))
+            .with_status(status)
+            .with_header("content-type", "application/json")
+            .with_body(body)
+            .expect(1)
+            .create()
+    };
+    let wallets = [
+        wallet(
+            "XRP",
+            200,
+            r#"[{"currency":"XRP","deposit_message":"","deposit_state":"s",
+                "exchange":"upbit","network":"XRP","updated_at":0,
+                "withdraw_message":"","withdraw_state":"s"}]"#,
+        ),
+        wallet("BTC", 404, ""),
+    ];
+    let mocks = [
+        list(
+            &mut server,
+            "/api/v1/cex/symbol/metadata",
+            r#"[{"b":"XRP","e":"upbit","m":"spot","q":"KRW","status":"trading","tags":["layer1"]},
+                {"b":"BTC","e":"upbit","m":"spot","q":"KRW","status":"trading"}]"#,
+        ),
+        list(
+            &mut server,
+            "/api/v1/cex/symbol/tags",
+            r#"[{"b":"XRP","e":"upbit","m":"spot","q":"KRW","confidence":90,
+                 "source":"manual","tag":"payments"}]"#,
+        ),
+        list(
+            &mut server,
+            "/api/v1/cex/symbol/cautions",
+            r#"[{"b":"XRP","e":"upbit","m":"spot","q":"KRW","caution_level":"warning",
+                 "reasons":["volume spike"]}]"#,
+        ),
+        list(&mut server, "/api/v1/cex/symbol/delistings", "[]"),
+        server
+            .mock("GET", "/api/v1/cex/fees")
+            .match_query(Matcher::UrlEncoded("exchange".into(), "upbit".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"[{"base":"BTC","exchange":"upbit","quote":"KRW","symbol":"BTC-KRW",
+                     "spot_maker_fee":0.0005,"spot_take_fee":0.0005}]"#,
+            )
+            .expect(1)
+            .create(),
+    ];
+
+    let client = ClientBuilder::new()
+        .api_key("test-api-key")
+        .base_url(server.url())
+        .build()
+        .expect("mock client builds");
+    let builder = UniverseBuilder::new().exchange("upbit").wallets(true);
+    let universe = client
+        .symbol_universe(&builder)
+        .await
+        .expect("universe builds");
+
+    assert_eq!(universe.records.len(), 2);
+    let xrp = universe
+        .get(&SymbolKey::new("upbit", "spot", "XRP", "KRW"))
+        .expect("xrp is listed");
+    assert!(xrp.has_tag("layer1") && xrp.has_tag("payments"));
+    // Wallet states the API does not document are not read as closed.
+    assert_eq!(xrp.deposit_networks, None);
+    assert_eq!(xrp.flags, [RiskFlag::Caution(CautionLevel::Warning)]);
+    let btc = universe
+        .get(&SymbolKey::new("upbit", "spot", "BTC", "KRW"))
+        .expect("btc is listed");
+    assert_eq!(btc.maker_fee, Some(0.0005));
+    assert_eq!(btc.deposit_networks, None);
+    assert!(!btc.is_flagged());
+
+    for mock in mocks.iter().chain(&wallets) {
+        mock.assert();
+    }
+}
@@ -0,0 +1,68 @@
+//! Integration tests for client-side option validation
+//! ([`datamaxi::validate`]): a request whose options violate a declared
+//! constraint fails with `Error::InvalidArgument` and never reaches the
+//! server, on both the async and the blocking client.
+
+use datamaxi::api::{ClientBuilder, Error};
+use datamaxi::PremiumOptions;
+use mockito::Matcher;
+
+#[tokio::test]
+async fn invalid_options_are_rejected_before_sending() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("GET", "/api/v1/premium")
+        .match_query(Matcher::Any)
+        .with_status(200)
+        .with_body(r#"{"data":[],"limit":1,"page":1,"sort":"x","total":0}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let premium = ClientBuilder::new()
+        .api_key("test-api-key")
+        .base_url(server.url())
+        .build()
+        .expect("mock client builds")
+        .premium();
+
+    let rejected = premium
+        .get(PremiumOptions::new().token_include("bitcoin,").limit(0))
+        .await;
+    match rejected {
+        Err(Error::InvalidArgument { argument, reason }) => {
+            assert_eq!(argument, "token_include");
+            assert_eq!(reason, "malformed list `bitcoin,`: item 2 is empty");
+        }
+        other => panic!("expected InvalidArgument, got {other:?}"),
+    }
+
+    let accepted = premium
+        .get(PremiumOptions::new().token_include("bitcoin").limit(10))
+        .await;
+    assert!(accepted.is_ok(), "expected Ok, got {accepted:?}");
+    mock.assert_async().await;
+}
+
+#[cfg(feature = "sync")]
+#[test]
+fn blocking_client_validates_too() {
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("GET", "/api/v1/cex/symbol/cautions")
+        .match_query(Matcher::Any)
+        .expect(0)
+        .create();
+    let symbols = datamaxi::api::sync::ClientBuilder::new()
+        .api_key("test-api-key")
+        .base_url(server.url())
+        .build()
+        .expect("mock blocking client builds")
+        .cex_symbol();
+
+    let result = symbols.cautions(datamaxi::CexSymbolCautionsOptions::new().limit(10_000));
+    assert!(
+        matches!(result, Err(Error::InvalidArgument { ref argument, .. }) if argument == "limit"),
+        "expected InvalidArgument on `limit`, got {result:?}"
+    );
+    mock.assert();
+}